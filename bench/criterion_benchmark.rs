@@ -3,8 +3,12 @@ use std::path::Path;
 use cairo_vm::{
     cairo_run,
     hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+    serde::serialize_utils::{encode_felts_to, FELT_BYTES},
+    types::program::Program,
+    vm::{runners::cairo_runner::CairoRunner, vm_core::VirtualMachine},
 };
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use felt::Felt;
 
 const BENCH_NAMES: &[&str] = &[
     "compare_arrays_200000",
@@ -22,6 +26,7 @@ const BENCH_NAMES: &[&str] = &[
     "operations_with_data_structures_benchmarks",
     "uint256_integration_benchmark",
     "set_integration_benchmark",
+    "immediates_benchmark",
 ];
 const BENCH_PATH: &str = "cairo_programs/benchmarks/";
 
@@ -45,6 +50,57 @@ pub fn criterion_benchmarks(c: &mut Criterion) {
     }
 }
 
+pub fn felt_encoding_benchmarks(c: &mut Criterion) {
+    let felts: Vec<Felt> = (0..100_000_u64)
+        .map(|i| Felt::new(i) * Felt::new(u128::MAX - i as u128))
+        .collect();
+    c.bench_function("encode_felts_to", |b| {
+        b.iter(|| {
+            let mut buffer = Vec::new();
+            encode_felts_to(&mut buffer, black_box(&felts));
+            buffer
+        })
+    });
+    c.bench_function("encode_felts_per_cell", |b| {
+        b.iter(|| {
+            let mut buffer = Vec::new();
+            for felt in black_box(&felts) {
+                let value_bytes = felt.to_signed_bytes_le();
+                let mut cell = [0; FELT_BYTES];
+                cell[..value_bytes.len()].copy_from_slice(&value_bytes);
+                buffer.extend_from_slice(&cell);
+            }
+            buffer
+        })
+    });
+}
+
+pub fn instruction_cache_benchmarks(c: &mut Criterion) {
+    let file_content =
+        std::fs::read(Path::new(BENCH_PATH).join("fibonacci_1000_multirun.json")).unwrap();
+    let program = Program::from_bytes(&file_content, Some("main")).unwrap();
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    for (name, instruction_cache) in [
+        ("fibonacci_1000_multirun_instruction_cache", true),
+        ("fibonacci_1000_multirun_no_instruction_cache", false),
+    ] {
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                let mut runner = CairoRunner::new(black_box(&program), "all", false).unwrap();
+                let mut vm = VirtualMachine::new(false);
+                if !instruction_cache {
+                    vm.disable_instruction_cache();
+                }
+                let end = runner.initialize(&mut vm).unwrap();
+                runner
+                    .run_until_pc(end, &mut vm, &mut hint_executor)
+                    .unwrap();
+                vm
+            })
+        });
+    }
+}
+
 fn build_bench_strings() -> Vec<(String, String)> {
     let mut full_string = Vec::<(String, String)>::new();
 
@@ -60,5 +116,10 @@ fn build_bench_strings() -> Vec<(String, String)> {
     full_string
 }
 
-criterion_group!(benches, criterion_benchmarks);
+criterion_group!(
+    benches,
+    criterion_benchmarks,
+    felt_encoding_benchmarks,
+    instruction_cache_benchmarks
+);
 criterion_main!(benches);
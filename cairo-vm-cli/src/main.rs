@@ -1,5 +1,6 @@
 #![deny(warnings)]
 use bincode::enc::write::Writer;
+use cairo_vm::air_public_input::PublicInputError;
 use cairo_vm::cairo_run::{self, EncodeTraceError};
 use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor;
 use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
@@ -37,6 +38,17 @@ struct Args {
     proof_mode: bool,
     #[structopt(long = "--secure_run")]
     secure_run: Option<bool>,
+    #[clap(long = "--air_public_input", requires = "proof_mode")]
+    air_public_input: Option<PathBuf>,
+    #[clap(
+        long = "--air_private_input",
+        requires_all = &["proof_mode", "trace_file", "memory_file"]
+    )]
+    air_private_input: Option<PathBuf>,
+    #[clap(long = "--program_input", value_hint=ValueHint::FilePath)]
+    program_input: Option<PathBuf>,
+    #[clap(long = "--max_segment_offset", value_parser)]
+    max_segment_offset: Option<usize>,
 }
 
 fn validate_layout(value: &str) -> Result<(), String> {
@@ -56,6 +68,10 @@ enum Error {
     EncodeTrace(#[from] EncodeTraceError),
     #[error(transparent)]
     VirtualMachine(#[from] VirtualMachineError),
+    #[error(transparent)]
+    PublicInput(#[from] PublicInputError),
+    #[error("Failed to parse the program input")]
+    ProgramInput(#[from] serde_json::Error),
 }
 
 struct FileWriter {
@@ -93,7 +109,12 @@ impl FileWriter {
 
 fn main() -> Result<(), Error> {
     let args = Args::parse();
-    let trace_enabled = args.trace_file.is_some();
+    // The AIR public input needs the relocated trace to compute the number of steps
+    let trace_enabled = args.trace_file.is_some() || args.air_public_input.is_some();
+    let program_input = match &args.program_input {
+        Some(path) => Some(serde_json::from_slice(&std::fs::read(path)?)?),
+        None => None,
+    };
     let mut hint_executor = BuiltinHintProcessor::new_empty();
     let cairo_run_config = cairo_run::CairoRunConfig {
         entrypoint: &args.entrypoint,
@@ -101,6 +122,8 @@ fn main() -> Result<(), Error> {
         layout: &args.layout,
         proof_mode: args.proof_mode,
         secure_run: args.secure_run,
+        program_input,
+        max_segment_offset: args.max_segment_offset,
     };
 
     let program_content = std::fs::read(args.filename).map_err(|e| Error::IO(e))?;
@@ -120,19 +143,20 @@ fn main() -> Result<(), Error> {
         print!("{output_buffer}");
     }
 
-    if let Some(trace_path) = args.trace_file {
+    if let Some(trace_path) = &args.trace_file {
         let relocated_trace = cairo_runner
             .relocated_trace
+            .as_ref()
             .ok_or(CairoRunError::Trace(TraceError::TraceNotEnabled))?;
 
         let trace_file = std::fs::File::create(trace_path)?;
         let mut trace_writer = FileWriter::new(io::BufWriter::new(trace_file));
 
-        cairo_run::write_encoded_trace(&relocated_trace, &mut trace_writer)?;
+        cairo_run::write_encoded_trace(relocated_trace, &mut trace_writer)?;
         trace_writer.flush()?;
     }
 
-    if let Some(memory_path) = args.memory_file {
+    if let Some(memory_path) = &args.memory_file {
         let memory_file = std::fs::File::create(memory_path)?;
         let mut memory_writer = FileWriter::new(io::BufWriter::new(memory_file));
 
@@ -140,6 +164,28 @@ fn main() -> Result<(), Error> {
         memory_writer.flush()?;
     }
 
+    if let Some(file_path) = args.air_public_input {
+        let mut writer = io::BufWriter::new(std::fs::File::create(file_path)?);
+        cairo_runner.write_air_public_input(&vm, &mut writer)?;
+        writer.flush()?;
+    }
+
+    if let (Some(file_path), Some(trace_path), Some(memory_path)) =
+        (args.air_private_input, &args.trace_file, &args.memory_file)
+    {
+        // The prover expects absolute paths to the trace and memory files
+        let trace_path = trace_path.canonicalize()?;
+        let memory_path = memory_path.canonicalize()?;
+        let mut writer = io::BufWriter::new(std::fs::File::create(file_path)?);
+        cairo_runner.write_air_private_input(
+            &vm,
+            &trace_path.to_string_lossy(),
+            &memory_path.to_string_lossy(),
+            &mut writer,
+        )?;
+        writer.flush()?;
+    }
+
     Ok(())
 }
 
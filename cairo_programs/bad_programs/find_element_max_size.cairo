@@ -0,0 +1,22 @@
+%builtins range_check
+from starkware.cairo.common.find_element import find_element
+from starkware.cairo.common.alloc import alloc
+
+struct MyStruct {
+    a: felt,
+    b: felt,
+}
+
+func main{range_check_ptr}() -> () {
+    // Create an array with MyStruct elements (1,2), (3,4), (5,6).
+    alloc_locals;
+    let (local array_ptr: MyStruct*) = alloc();
+    assert array_ptr[0] = MyStruct(a=1, b=2);
+    assert array_ptr[1] = MyStruct(a=3, b=4);
+    assert array_ptr[2] = MyStruct(a=5, b=6);
+
+    // Only arrays of up to 2 elements can be searched.
+    %{ __find_element_max_size = 2 %}
+    find_element(array_ptr=array_ptr, elm_size=MyStruct.SIZE, n_elms=3, key=5);
+    return ();
+}
@@ -0,0 +1,18 @@
+// Arithmetic-heavy loop whose operands are mostly large immediates, to measure raw
+// instruction throughput.
+const BIG = 0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff0;
+const OFFSET = 0x3fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7;
+
+func accumulate(acc: felt, n: felt) -> (res: felt) {
+    if (n == 0) {
+        return (res=acc);
+    }
+    tempvar scaled = acc * BIG;
+    tempvar shifted = scaled + OFFSET;
+    return accumulate(shifted * 3, n - 1);
+}
+
+func main() {
+    let (res) = accumulate(1, 200000);
+    return ();
+}
@@ -0,0 +1,17 @@
+// Arithmetic on immediates spanning the whole felt range, checked against precomputed results.
+const BIG = 0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff0;
+
+func main() {
+    tempvar x = -1;
+    tempvar y = x + 2;
+    assert y = 1;
+    tempvar z = x * BIG;
+    assert z = -BIG;
+    tempvar w = z + BIG;
+    assert w = 0;
+    // 2 * BIG wraps around the prime
+    tempvar v = w + BIG;
+    tempvar u = v + BIG;
+    assert u = 0x7ffffffffffffeeffffffffffffffffffffffffffffffffffffffffffffffdf;
+    return ();
+}
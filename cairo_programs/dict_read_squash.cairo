@@ -0,0 +1,33 @@
+%builtins range_check
+
+from starkware.cairo.common.dict_access import DictAccess
+from starkware.cairo.common.dict import dict_read, dict_write, dict_squash
+from starkware.cairo.common.default_dict import default_dict_new
+
+func main{range_check_ptr}() -> () {
+    let (dict_start) = default_dict_new(0);
+    let dict_end = dict_start;
+    // Keys are accessed out of order, so that squashing has to sort them
+    dict_write{dict_ptr=dict_end}(7, 70);
+    dict_write{dict_ptr=dict_end}(3, 30);
+    let (value) = dict_read{dict_ptr=dict_end}(7);
+    assert value = 70;
+    dict_write{dict_ptr=dict_end}(5, 50);
+    // Unwritten keys hold the default value
+    let (value) = dict_read{dict_ptr=dict_end}(11);
+    assert value = 0;
+    dict_write{dict_ptr=dict_end}(3, 31);
+    let (value) = dict_read{dict_ptr=dict_end}(3);
+    assert value = 31;
+
+    let (squashed_dict_start, squashed_dict_end) = dict_squash{range_check_ptr=range_check_ptr}(
+        dict_start, dict_end
+    );
+    // A single access is left per key, sorted by key
+    assert squashed_dict_end - squashed_dict_start = 4 * DictAccess.SIZE;
+    assert squashed_dict_start[0] = DictAccess(key=3, prev_value=0, new_value=31);
+    assert squashed_dict_start[1] = DictAccess(key=5, prev_value=0, new_value=50);
+    assert squashed_dict_start[2] = DictAccess(key=7, prev_value=0, new_value=70);
+    assert squashed_dict_start[3] = DictAccess(key=11, prev_value=0, new_value=0);
+    return ();
+}
@@ -0,0 +1,13 @@
+// Runs n iterations, each one consuming `cost` gas units through a hint.
+func gas_loop(n: felt, cost: felt) {
+    if (n == 0) {
+        return ();
+    }
+    %{ consume_gas(ids.cost) %}
+    return gas_loop(n - 1, cost);
+}
+
+func main() {
+    gas_loop(10, 7);
+    return ();
+}
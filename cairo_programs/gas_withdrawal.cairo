@@ -0,0 +1,42 @@
+%builtins range_check
+
+from starkware.cairo.common.alloc import alloc
+from starkware.cairo.common.math_cmp import is_le
+
+const STEP_COST = 100;
+
+// Sums the integers in [1, n], withdrawing STEP_COST gas units per iteration.
+func sum_inner{range_check_ptr}(gas: felt, n: felt, acc: felt) -> (
+    gas: felt, out_of_gas: felt, acc: felt
+) {
+    if (n == 0) {
+        return (gas=gas, out_of_gas=0, acc=acc);
+    }
+    let enough_gas = is_le(STEP_COST, gas);
+    if (enough_gas == 0) {
+        return (gas=gas, out_of_gas=1, acc=acc);
+    }
+    return sum_inner(gas=gas - STEP_COST, n=n - 1, acc=acc + n);
+}
+
+// Follows the calling convention of a Cairo 1 function returning a PanicResult<Span<felt252>>:
+// the gas counter is the last implicit argument, and the return values end with
+// [gas_counter, variant, span_start, span_end].
+func sum_with_gas{range_check_ptr}(gas: felt, n: felt) -> (
+    remaining_gas: felt, variant: felt, start: felt*, end: felt*
+) {
+    alloc_locals;
+    let (local data: felt*) = alloc();
+    let (remaining_gas, out_of_gas, sum) = sum_inner(gas, n, 0);
+    if (out_of_gas == 0) {
+        assert data[0] = sum;
+        return (remaining_gas, 0, data, data + 1);
+    }
+    assert data[0] = 'Out of gas';
+    return (remaining_gas, 1, data, data + 1);
+}
+
+func main{range_check_ptr}() {
+    sum_with_gas(1000, 5);
+    return ();
+}
@@ -0,0 +1,35 @@
+%builtins output pedersen poseidon
+
+from starkware.cairo.common.alloc import alloc
+from starkware.cairo.common.builtin_poseidon.poseidon import poseidon_hash_many
+from starkware.cairo.common.cairo_builtins import HashBuiltin, PoseidonBuiltin
+from starkware.cairo.common.hash_state import hash_felts
+from starkware.cairo.common.serialize import serialize_word
+
+// Outputs the pedersen chain (hash_felts) and the poseidon hash of the prefixes of [1, 2, 3]
+func main{output_ptr: felt*, pedersen_ptr: HashBuiltin*, poseidon_ptr: PoseidonBuiltin*}() {
+    alloc_locals;
+    let (local data: felt*) = alloc();
+    assert data[0] = 1;
+    assert data[1] = 2;
+    assert data[2] = 3;
+
+    let hash = hash_felts{hash_ptr=pedersen_ptr}(data=data, length=0);
+    serialize_word(hash);
+    let hash = hash_felts{hash_ptr=pedersen_ptr}(data=data, length=1);
+    serialize_word(hash);
+    let hash = hash_felts{hash_ptr=pedersen_ptr}(data=data, length=2);
+    serialize_word(hash);
+    let hash = hash_felts{hash_ptr=pedersen_ptr}(data=data, length=3);
+    serialize_word(hash);
+
+    let (hash) = poseidon_hash_many(0, data);
+    serialize_word(hash);
+    let (hash) = poseidon_hash_many(1, data);
+    serialize_word(hash);
+    let (hash) = poseidon_hash_many(2, data);
+    serialize_word(hash);
+    let (hash) = poseidon_hash_many(3, data);
+    serialize_word(hash);
+    return ();
+}
@@ -0,0 +1,7 @@
+from starkware.cairo.common.alloc import alloc
+
+func main() {
+    let (ptr: felt*) = alloc();
+    assert ptr[1000] = 1;
+    return ();
+}
@@ -0,0 +1,14 @@
+%builtins keccak
+
+from starkware.cairo.common.cairo_builtins import KeccakBuiltin
+from starkware.cairo.common.keccak_state import KeccakBuiltinState
+
+func main{keccak_ptr: KeccakBuiltin*}() {
+    assert keccak_ptr[0].input = KeccakBuiltinState(1, 2, 3, 4, 5, 6, 7, 8);
+    let output = keccak_ptr[0].output;
+    assert keccak_ptr[1].input = KeccakBuiltinState(
+        output.s0, output.s1, output.s2, output.s3, output.s4, output.s5, output.s6, output.s7
+    );
+    let keccak_ptr = keccak_ptr + 2 * KeccakBuiltin.SIZE;
+    return ();
+}
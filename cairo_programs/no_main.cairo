@@ -0,0 +1,8 @@
+// A library of functions, without a main function.
+func add_one(x: felt) -> (res: felt) {
+    return (res=x + 1);
+}
+
+func double(x: felt) -> (res: felt) {
+    return (res=x * 2);
+}
@@ -0,0 +1,10 @@
+%builtins output
+
+from starkware.cairo.common.serialize import serialize_word
+
+func main{output_ptr: felt*}() {
+    serialize_word(1);
+    serialize_word(-17);
+    serialize_word(1000000);
+    return ();
+}
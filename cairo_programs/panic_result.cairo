@@ -0,0 +1,30 @@
+%builtins range_check
+
+from starkware.cairo.common.alloc import alloc
+from starkware.cairo.common.math import unsigned_div_rem
+
+const DIV_COST = 10;
+
+// Follows the calling convention of a Cairo 1 function returning a PanicResult<Span<felt252>>:
+// the gas counter is the last implicit argument, and the return values end with
+// [gas_counter, variant, span_start, span_end].
+// Returns the quotient and the remainder of a divided by b, and panics if b is zero.
+func checked_div_rem{range_check_ptr}(gas: felt, a: felt, b: felt) -> (
+    remaining_gas: felt, variant: felt, start: felt*, end: felt*
+) {
+    alloc_locals;
+    let (local data: felt*) = alloc();
+    if (b == 0) {
+        assert data[0] = 'Division by zero';
+        return (gas - DIV_COST, 1, data, data + 1);
+    }
+    let (q, r) = unsigned_div_rem(a, b);
+    assert data[0] = q;
+    assert data[1] = r;
+    return (gas - DIV_COST, 0, data, data + 2);
+}
+
+func main{range_check_ptr}() {
+    checked_div_rem(1000, 17, 5);
+    return ();
+}
@@ -0,0 +1,8 @@
+// Reads a value supplied by the host through the program input, and checks it.
+func main() {
+    alloc_locals;
+    local x;
+    %{ ids.x = program_input["x"] %}
+    assert x = 42;
+    return ();
+}
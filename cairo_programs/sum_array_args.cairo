@@ -0,0 +1,12 @@
+// Adds the elements of an array to an initial value. The array is passed by the caller as a
+// (pointer, length) pair.
+func sum_array(initial: felt, arr: felt*, len: felt) -> (sum: felt) {
+    if (len == 0) {
+        return (sum=initial);
+    }
+    return sum_array(initial=initial + [arr], arr=arr + 1, len=len - 1);
+}
+
+func main() {
+    return ();
+}
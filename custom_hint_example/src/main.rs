@@ -4,13 +4,13 @@ use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_def
 };
 use cairo_vm::hint_processor::builtin_hint_processor::hint_utils::get_integer_from_var_name;
 use cairo_vm::hint_processor::hint_processor_definition::HintReference;
-use cairo_vm::types::exec_scope::ExecutionScopes;
 use cairo_vm::serde::deserialize_program::ApTracking;
+use cairo_vm::types::exec_scope::ExecutionScopes;
 use cairo_vm::vm::{errors::hint_errors::HintError, vm_core::VirtualMachine};
 use num_bigint::BigInt;
 use std::collections::HashMap;
 use std::path::Path;
-use std::rc::Rc;
+use std::sync::Arc;
 
 // Create the function that implements the custom hint
 fn print_a_hint(
@@ -33,7 +33,7 @@ fn main() {
     let mut hint_processor = BuiltinHintProcessor::new_empty();
 
     //Add the custom hint, together with the Python code
-    hint_processor.add_hint(String::from("print(ids.a)"), Rc::new(hint));
+    hint_processor.add_hint(String::from("print(ids.a)"), Arc::new(hint));
 
     //Run the cairo program
     cairo_run(
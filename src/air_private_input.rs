@@ -0,0 +1,261 @@
+//! Private input of the Cairo AIR, as expected by the STARK prover.
+
+use crate::stdlib::{collections::BTreeMap, fmt, prelude::*};
+use felt::Felt;
+use serde::{
+    de::{IgnoredAny, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[cfg(feature = "std")]
+use std::io::Write;
+
+use crate::air_public_input::{deserialize_felt_hex, parse_felt_hex, serialize_felt_hex};
+
+/// Private input of a single builtin instance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PrivateInput {
+    Value(PrivateInputValue),
+    Pair(PrivateInputPair),
+    KeccakState(KeccakPrivateInput),
+}
+
+/// Used by builtins with a single input cell per instance (range_check)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PrivateInputValue {
+    pub index: usize,
+    #[serde(
+        serialize_with = "serialize_felt_hex",
+        deserialize_with = "deserialize_felt_hex"
+    )]
+    pub value: Felt,
+}
+
+/// Used by builtins with two input cells per instance (pedersen, bitwise)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PrivateInputPair {
+    pub index: usize,
+    #[serde(
+        serialize_with = "serialize_felt_hex",
+        deserialize_with = "deserialize_felt_hex"
+    )]
+    pub x: Felt,
+    #[serde(
+        serialize_with = "serialize_felt_hex",
+        deserialize_with = "deserialize_felt_hex"
+    )]
+    pub y: Felt,
+}
+
+/// Private input of a pedersen instance: the two hashed values
+pub type PedersenPrivateInput = PrivateInputPair;
+
+/// Used by the keccak builtin: the input and output states of an instance, one cell per state
+/// word. Serialized as `{"index": .., "input_s0": .., .., "output_s0": .., ..}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeccakPrivateInput {
+    pub index: usize,
+    pub input: Vec<Felt>,
+    pub output: Vec<Felt>,
+}
+
+impl Serialize for KeccakPrivateInput {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1 + self.input.len() + self.output.len()))?;
+        map.serialize_entry("index", &self.index)?;
+        for (i, value) in self.input.iter().enumerate() {
+            map.serialize_entry(
+                &format!("input_s{i}"),
+                &format!("0x{}", value.to_str_radix(16)),
+            )?;
+        }
+        for (i, value) in self.output.iter().enumerate() {
+            map.serialize_entry(
+                &format!("output_s{i}"),
+                &format!("0x{}", value.to_str_radix(16)),
+            )?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for KeccakPrivateInput {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeccakPrivateInputVisitor;
+
+        impl<'de> Visitor<'de> for KeccakPrivateInputVisitor {
+            type Value = KeccakPrivateInput;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a keccak builtin private input")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut index = None;
+                let mut input = BTreeMap::new();
+                let mut output = BTreeMap::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    let state = if let Some(i) = key.strip_prefix("input_s") {
+                        Some((&mut input, i))
+                    } else {
+                        key.strip_prefix("output_s").map(|i| (&mut output, i))
+                    };
+                    match (key.as_str(), state) {
+                        ("index", _) => index = Some(map.next_value()?),
+                        (_, Some((state, i))) => {
+                            let i: usize = i.parse().map_err(serde::de::Error::custom)?;
+                            let value = map.next_value::<String>()?;
+                            let felt = parse_felt_hex(&value).ok_or_else(|| {
+                                serde::de::Error::custom(format!(
+                                    "Expected a hex felt, got {value}"
+                                ))
+                            })?;
+                            state.insert(i, felt);
+                        }
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                let input: Vec<Felt> = input.into_values().collect();
+                // Untagged enums try every variant, so the input states are required to tell
+                // keccak instances apart from other private inputs
+                if input.is_empty() {
+                    return Err(serde::de::Error::missing_field("input_s0"));
+                }
+                Ok(KeccakPrivateInput {
+                    index: index.ok_or_else(|| serde::de::Error::missing_field("index"))?,
+                    input,
+                    output: output.into_values().collect(),
+                })
+            }
+        }
+
+        deserializer.deserialize_map(KeccakPrivateInputVisitor)
+    }
+}
+
+/// Private input of the Cairo AIR. Its JSON representation matches the `air_private_input.json`
+/// file written by the Python VM and read by the stone prover: the paths to the trace and
+/// memory files, followed by the private inputs of each builtin in the program's order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AirPrivateInput {
+    pub trace_path: String,
+    pub memory_path: String,
+    pub builtins: Vec<(String, Vec<PrivateInput>)>,
+}
+
+impl AirPrivateInput {
+    /// Writes the private input as JSON, using the same indentation as the Python VM
+    #[cfg(feature = "std")]
+    pub fn write_json<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        let mut serializer = serde_json::Serializer::with_formatter(writer, formatter);
+        self.serialize(&mut serializer)?;
+        Ok(())
+    }
+}
+
+impl Serialize for AirPrivateInput {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.builtins.len() + 2))?;
+        map.serialize_entry("trace_path", &self.trace_path)?;
+        map.serialize_entry("memory_path", &self.memory_path)?;
+        for (name, inputs) in self.builtins.iter() {
+            map.serialize_entry(name, inputs)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for AirPrivateInput {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AirPrivateInputVisitor;
+
+        impl<'de> Visitor<'de> for AirPrivateInputVisitor {
+            type Value = AirPrivateInput;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an AIR private input")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut private_input = AirPrivateInput::default();
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "trace_path" => private_input.trace_path = map.next_value()?,
+                        "memory_path" => private_input.memory_path = map.next_value()?,
+                        _ => private_input.builtins.push((key, map.next_value()?)),
+                    }
+                }
+                Ok(private_input)
+            }
+        }
+
+        deserializer.deserialize_map(AirPrivateInputVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn serialize_and_deserialize_private_input() {
+        let private_input = AirPrivateInput {
+            trace_path: "trace.bin".to_string(),
+            memory_path: "memory.bin".to_string(),
+            builtins: vec![
+                (
+                    "range_check".to_string(),
+                    vec![PrivateInput::Value(PrivateInputValue {
+                        index: 0,
+                        value: Felt::new(10),
+                    })],
+                ),
+                (
+                    "bitwise".to_string(),
+                    vec![PrivateInput::Pair(PrivateInputPair {
+                        index: 0,
+                        x: Felt::new(1),
+                        y: Felt::new(2),
+                    })],
+                ),
+            ],
+        };
+        let json = serde_json::to_string(&private_input).unwrap();
+        assert_eq!(
+            json,
+            r#"{"trace_path":"trace.bin","memory_path":"memory.bin","range_check":[{"index":0,"value":"0xa"}],"bitwise":[{"index":0,"x":"0x1","y":"0x2"}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<AirPrivateInput>(&json).unwrap(),
+            private_input
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn serialize_and_deserialize_keccak_private_input() {
+        let inputs = vec![PrivateInput::KeccakState(KeccakPrivateInput {
+            index: 3,
+            input: vec![Felt::new(1), Felt::new(2)],
+            output: vec![Felt::new(0xab), Felt::new(0xcd)],
+        })];
+        let json = serde_json::to_string(&inputs).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"index":3,"input_s0":"0x1","input_s1":"0x2","output_s0":"0xab","output_s1":"0xcd"}]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<PrivateInput>>(&json).unwrap(),
+            inputs
+        );
+    }
+}
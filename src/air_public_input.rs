@@ -0,0 +1,224 @@
+//! Public input of the Cairo AIR, as expected by the STARK prover.
+
+use crate::stdlib::{collections::BTreeMap, fmt, prelude::*};
+use felt::Felt;
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[cfg(feature = "std")]
+use std::io::Write;
+
+#[cfg(feature = "std")]
+use thiserror::Error;
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use thiserror_no_std::Error;
+
+use crate::vm::errors::{
+    memory_errors::MemoryError, runner_errors::RunnerError, trace_errors::TraceError,
+    vm_errors::VirtualMachineError,
+};
+
+/// A public memory cell, along with the page it was assigned to.
+/// Page 0 is the main page, holding the program and the execution public memory.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PublicMemoryEntry {
+    pub address: usize,
+    #[serde(
+        serialize_with = "serialize_felt_hex",
+        deserialize_with = "deserialize_felt_hex"
+    )]
+    pub value: Felt,
+    pub page: usize,
+}
+
+/// Relocated boundaries of a memory segment
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MemorySegmentAddresses {
+    pub begin_addr: usize,
+    pub stop_ptr: usize,
+}
+
+/// Named memory segments of a run, kept in the order the prover expects them: the program
+/// and execution segments first, followed by the builtin segments in the program's order.
+/// Serialized as a JSON object.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemorySegments(pub Vec<(String, MemorySegmentAddresses)>);
+
+impl MemorySegments {
+    pub fn get(&self, name: &str) -> Option<&MemorySegmentAddresses> {
+        self.0
+            .iter()
+            .find_map(|(segment_name, addresses)| (segment_name == name).then_some(addresses))
+    }
+
+    pub(crate) fn insert(&mut self, name: &str, addresses: MemorySegmentAddresses) {
+        self.0.push((name.to_string(), addresses))
+    }
+}
+
+impl Serialize for MemorySegments {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, addresses) in self.0.iter() {
+            map.serialize_entry(name, addresses)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for MemorySegments {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MemorySegmentsVisitor;
+
+        impl<'de> Visitor<'de> for MemorySegmentsVisitor {
+            type Value = MemorySegments;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of memory segment addresses")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut segments = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    segments.push(entry);
+                }
+                Ok(MemorySegments(segments))
+            }
+        }
+
+        deserializer.deserialize_map(MemorySegmentsVisitor)
+    }
+}
+
+/// Public input of the Cairo AIR. Its JSON representation matches the `air_public_input.json`
+/// file written by the Python VM and read by the stone prover.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PublicInput {
+    pub layout: String,
+    pub rc_min: isize,
+    pub rc_max: isize,
+    pub n_steps: usize,
+    pub memory_segments: MemorySegments,
+    pub public_memory: Vec<PublicMemoryEntry>,
+}
+
+impl PublicInput {
+    /// Groups the public memory entries by page index
+    pub fn public_memory_pages(&self) -> BTreeMap<usize, Vec<&PublicMemoryEntry>> {
+        let mut pages = BTreeMap::<usize, Vec<&PublicMemoryEntry>>::new();
+        for entry in self.public_memory.iter() {
+            pages.entry(entry.page).or_default().push(entry);
+        }
+        pages
+    }
+
+    /// Writes the public input as JSON, using the same indentation as the Python VM
+    #[cfg(feature = "std")]
+    pub fn write_json<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        let mut serializer = serde_json::Serializer::with_formatter(writer, formatter);
+        self.serialize(&mut serializer)?;
+        Ok(())
+    }
+}
+
+pub(crate) fn serialize_felt_hex<S: Serializer>(
+    value: &Felt,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{}", value.to_str_radix(16)))
+}
+
+pub(crate) fn deserialize_felt_hex<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Felt, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_felt_hex(&value)
+        .ok_or_else(|| serde::de::Error::custom(format!("Expected a hex felt, got {value}")))
+}
+
+/// Parses a `0x` prefixed hex felt
+pub(crate) fn parse_felt_hex(value: &str) -> Option<Felt> {
+    value
+        .strip_prefix("0x")
+        .and_then(|hex| Felt::parse_bytes(hex.as_bytes(), 16))
+}
+
+#[derive(Debug, Error)]
+pub enum PublicInputError {
+    #[error("The trace must be relocated before computing the public input")]
+    TraceNotRelocated,
+    #[error("The trace is empty, no range check limits could be computed")]
+    NoRangeCheckLimits,
+    #[error("Public memory address {0} is missing from the relocated memory")]
+    MemoryNotFound(usize),
+    #[error(transparent)]
+    Memory(#[from] MemoryError),
+    #[error(transparent)]
+    Runner(#[from] RunnerError),
+    #[error(transparent)]
+    Trace(#[from] TraceError),
+    #[error(transparent)]
+    VirtualMachine(#[from] VirtualMachineError),
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn serialize_public_memory_entry() {
+        let entry = PublicMemoryEntry {
+            address: 3,
+            value: Felt::new(0x1f),
+            page: 1,
+        };
+        assert_eq!(
+            serde_json::to_string(&entry).unwrap(),
+            r#"{"address":3,"value":"0x1f","page":1}"#
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn memory_segments_keep_their_order() {
+        let json = r#"{"program":{"begin_addr":1,"stop_ptr":5},"execution":{"begin_addr":6,"stop_ptr":10},"output":{"begin_addr":10,"stop_ptr":11}}"#;
+        let segments: MemorySegments = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            segments
+                .0
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["program", "execution", "output"]
+        );
+        assert_eq!(
+            segments.get("execution"),
+            Some(&MemorySegmentAddresses {
+                begin_addr: 6,
+                stop_ptr: 10
+            })
+        );
+        assert_eq!(segments.get("pedersen"), None);
+        assert_eq!(serde_json::to_string(&segments).unwrap(), json);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deserialize_public_memory_entry_invalid_value() {
+        assert!(serde_json::from_str::<PublicMemoryEntry>(
+            r#"{"address":3,"value":"1f","page":1}"#
+        )
+        .is_err());
+    }
+}
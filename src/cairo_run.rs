@@ -1,9 +1,20 @@
+use crate::stdlib::{collections::HashMap, prelude::*};
+
 use crate::{
-    hint_processor::hint_processor_definition::HintProcessor,
-    types::program::Program,
+    hint_processor::{
+        builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        hint_processor_definition::HintProcessor,
+    },
+    serde::serialize_utils::{encode_felt_iter_to, FELT_BYTES},
+    types::{program::Program, relocatable::Relocatable},
     vm::{
-        errors::{cairo_run_errors::CairoRunError, vm_exception::VmException},
-        runners::cairo_runner::CairoRunner,
+        errors::{
+            cairo_run_errors::CairoRunError, runner_errors::RunnerError, vm_exception::VmException,
+        },
+        runners::{
+            cairo_pie::CairoPie,
+            cairo_runner::{CairoArg, CairoRunner},
+        },
         security::verify_secure_runner,
         vm_core::VirtualMachine,
     },
@@ -23,6 +34,11 @@ pub struct CairoRunConfig<'a> {
     pub layout: &'a str,
     pub proof_mode: bool,
     pub secure_run: Option<bool>,
+    /// Values made available to hints through the `program_input` variable of the main
+    /// execution scope, as done by the Python VM's `--program_input` file
+    pub program_input: Option<HashMap<String, serde_json::Value>>,
+    /// Highest offset a segment can be written at, see [VirtualMachine::set_max_segment_offset]
+    pub max_segment_offset: Option<usize>,
 }
 
 impl<'a> Default for CairoRunConfig<'a> {
@@ -33,27 +49,56 @@ impl<'a> Default for CairoRunConfig<'a> {
             layout: "plain",
             proof_mode: false,
             secure_run: None,
+            program_input: None,
+            max_segment_offset: None,
         }
     }
 }
 
+/// Exposes the configured program input to hints, in the main execution scope
+fn insert_program_input(cairo_runner: &mut CairoRunner, cairo_run_config: &CairoRunConfig) {
+    if let Some(program_input) = &cairo_run_config.program_input {
+        cairo_runner
+            .exec_scopes
+            .insert_value("program_input", program_input.clone());
+    }
+}
+
 pub fn cairo_run(
     program_content: &[u8],
     cairo_run_config: &CairoRunConfig,
     hint_executor: &mut dyn HintProcessor,
 ) -> Result<(CairoRunner, VirtualMachine), CairoRunError> {
     let program = Program::from_bytes(program_content, Some(cairo_run_config.entrypoint))?;
+    cairo_run_program(&program, cairo_run_config, hint_executor)
+}
 
+/// Runs an already deserialized program, starting from its `main` (`cairo_run_config.entrypoint`
+/// is only used when deserializing the program, so it is ignored here).
+///
+/// Thread safety: [`Program`] is `Send + Sync`, so a single deserialized program can be shared
+/// across threads (e.g. behind an `Arc`) and run concurrently. With the `std` feature, the
+/// [`CairoRunner`], [`VirtualMachine`] and `BuiltinHintProcessor` of a run are `Send`, so they
+/// can be built on one thread and moved to another one, but they aren't `Sync`: each run needs
+/// its own. Runs don't share any state, so running the same program on several threads yields
+/// the same results as running it sequentially.
+pub fn cairo_run_program(
+    program: &Program,
+    cairo_run_config: &CairoRunConfig,
+    hint_executor: &mut dyn HintProcessor,
+) -> Result<(CairoRunner, VirtualMachine), CairoRunError> {
     let secure_run = cairo_run_config
         .secure_run
         .unwrap_or(!cairo_run_config.proof_mode);
 
     let mut cairo_runner = CairoRunner::new(
-        &program,
+        program,
         cairo_run_config.layout,
         cairo_run_config.proof_mode,
     )?;
+    insert_program_input(&mut cairo_runner, cairo_run_config);
     let mut vm = VirtualMachine::new(cairo_run_config.trace_enabled);
+    vm.set_max_segment_offset(cairo_run_config.max_segment_offset);
     let end = cairo_runner.initialize(&mut vm)?;
 
     cairo_runner
@@ -69,7 +114,102 @@ pub fn cairo_run(
     if secure_run {
         verify_secure_runner(&cairo_runner, true, &mut vm)?;
     }
-    cairo_runner.relocate(&mut vm)?;
+    cairo_runner.relocate(&mut vm, true)?;
+
+    Ok((cairo_runner, vm))
+}
+
+/// Runs the function named `cairo_run_config.entrypoint` with the given arguments, until it
+/// returns. The pointers of the program's builtins are passed first, followed by `args` in
+/// order (see [CairoRunner::run_from_entrypoint] for how arrays are passed).
+/// The function's return values are found at the end of the execution segment.
+///     Note: proof mode isn't supported for function runs, `cairo_run_config.proof_mode`
+///     is ignored.
+pub fn cairo_run_with_args(
+    program_content: &[u8],
+    cairo_run_config: &CairoRunConfig,
+    args: &[&CairoArg],
+    hint_executor: &mut dyn HintProcessor,
+) -> Result<(CairoRunner, VirtualMachine), CairoRunError> {
+    let program = Program::from_bytes(program_content, Some(cairo_run_config.entrypoint))?;
+    let entrypoint = program.main.ok_or(RunnerError::MissingMain)?;
+    let secure_run = cairo_run_config.secure_run.unwrap_or(true);
+
+    let mut cairo_runner = CairoRunner::new(&program, cairo_run_config.layout, false)?;
+    insert_program_input(&mut cairo_runner, cairo_run_config);
+    let mut vm = VirtualMachine::new(cairo_run_config.trace_enabled);
+    vm.set_max_segment_offset(cairo_run_config.max_segment_offset);
+    cairo_runner.initialize_builtins(&mut vm)?;
+    cairo_runner.initialize_segments(&mut vm, None);
+
+    let builtin_args: Vec<CairoArg> = vm
+        .builtin_runners
+        .iter()
+        .flat_map(|(_, builtin)| builtin.initial_stack())
+        .map(CairoArg::Single)
+        .collect();
+    let args: Vec<&CairoArg> = builtin_args.iter().chain(args.iter().copied()).collect();
+    cairo_runner.run_from_entrypoint(entrypoint, &args, secure_run, &mut vm, hint_executor)?;
+    cairo_runner.relocate(&mut vm, true)?;
+
+    Ok((cairo_runner, vm))
+}
+
+/// Re-executes the program bundled in a Cairo PIE on top of the PIE's memory.
+///
+/// Fails if the program hash doesn't match `program_hash`, if the execution is inconsistent
+/// with the memory stored in the PIE, or if it doesn't use the execution resources claimed
+/// by the PIE. `layout` must be the one used to produce the PIE.
+pub fn cairo_run_pie(
+    cairo_pie: &CairoPie,
+    program_hash: &Felt,
+    layout: &str,
+) -> Result<(CairoRunner, VirtualMachine), CairoRunError> {
+    let pie_program_hash = cairo_pie.metadata.program.hash(0)?;
+    if &pie_program_hash != program_hash {
+        return Err(RunnerError::CairoPieProgramHashMismatch(
+            program_hash.clone(),
+            pie_program_hash,
+        )
+        .into());
+    }
+
+    let program = Program::from(&cairo_pie.metadata.program);
+    let mut cairo_runner = CairoRunner::new(&program, layout, false)?;
+    let mut vm = VirtualMachine::new(true);
+    let end = cairo_runner.initialize(&mut vm)?;
+
+    // Segments created by hints during the original run aren't created by the runner
+    if let Some(max_segment_index) = cairo_pie
+        .memory
+        .0
+        .iter()
+        .map(|((segment_index, _), _)| *segment_index)
+        .max()
+    {
+        while vm.segments.num_segments() <= max_segment_index {
+            vm.add_memory_segment();
+        }
+    }
+    for ((segment_index, offset), value) in cairo_pie.memory.0.iter() {
+        vm.insert_value(
+            Relocatable::from((*segment_index as isize, *offset)),
+            value.clone(),
+        )?;
+    }
+
+    let mut hint_processor = BuiltinHintProcessor::new_empty();
+    cairo_runner
+        .run_until_pc(end, &mut vm, &mut hint_processor)
+        .map_err(|err| VmException::from_vm_error(&cairo_runner, &vm, err))?;
+    cairo_runner.end_run(false, false, &mut vm, &mut hint_processor)?;
+
+    vm.verify_auto_deductions()?;
+    cairo_runner.read_return_values(&mut vm)?;
+
+    if cairo_runner.get_execution_resources(&vm)? != cairo_pie.execution_resources {
+        return Err(RunnerError::CairoPieExecutionResourcesMismatch.into());
+    }
 
     Ok((cairo_runner, vm))
 }
@@ -103,41 +243,39 @@ pub fn write_encoded_memory(
     relocated_memory: &[Option<Felt>],
     dest: &mut impl Writer,
 ) -> Result<(), EncodeTraceError> {
-    // initialize bytes vector that will be dumped to file
-
-    for (i, memory_cell) in relocated_memory.iter().enumerate() {
-        match memory_cell {
-            None => continue,
-            Some(unwrapped_memory_cell) => {
-                encode_relocated_memory(dest, i, unwrapped_memory_cell)
-                    .map_err(|e| EncodeTraceError(i, e))?;
-            }
+    let mut values = Vec::new();
+    let mut run_bytes = Vec::new();
+
+    let mut start = 0;
+    while start < relocated_memory.len() {
+        if relocated_memory[start].is_none() {
+            start += 1;
+            continue;
         }
-    }
-
-    Ok(())
-}
+        // Encode each run of contiguous cells at once, then interleave the addresses
+        let run_len = relocated_memory[start..]
+            .iter()
+            .take_while(|cell| cell.is_some())
+            .count();
+        values.clear();
+        encode_felt_iter_to(
+            &mut values,
+            relocated_memory[start..start + run_len].iter().flatten(),
+        );
+
+        run_bytes.clear();
+        run_bytes.reserve(run_len * (8 + FELT_BYTES));
+        for (i, value_bytes) in values.chunks_exact(FELT_BYTES).enumerate() {
+            // memory address using a 8 bytes representation
+            run_bytes.extend_from_slice(&((start + i) as u64).to_le_bytes());
+            // memory value at address using a 32 bytes representation
+            run_bytes.extend_from_slice(value_bytes);
+        }
+        dest.write(&run_bytes)
+            .map_err(|e| EncodeTraceError(start, e))?;
 
-// encodes a given memory cell.
-fn encode_relocated_memory(
-    dest: &mut impl Writer,
-    addr: usize,
-    memory_cell: &Felt,
-) -> Result<(), bincode::error::EncodeError> {
-    let config = bincode::config::standard()
-        .with_little_endian()
-        .with_fixed_int_encoding()
-        .skip_fixed_array_length();
-
-    // append memory address to bytes vector using a 8 bytes representation
-    let addr_bytes: [u8; 8] = (addr as u64).to_le_bytes();
-    bincode::encode_into_writer(addr_bytes, &mut *dest, config)?;
-
-    // append memory value at address using a 32 bytes representation
-    let value_bytes = memory_cell.to_signed_bytes_le();
-    let mut x = [0; 32];
-    x[..value_bytes.len()].copy_from_slice(&value_bytes);
-    bincode::encode_into_writer(x, &mut *dest, config)?;
+        start += run_len;
+    }
 
     Ok(())
 }
@@ -146,13 +284,25 @@ fn encode_relocated_memory(
 mod tests {
     use super::*;
     use crate::stdlib::prelude::*;
+    use crate::stdlib::sync::Arc;
+    use crate::types::relocatable::MaybeRelocatable;
+    use crate::vm::trace::trace_entry::RelocatedTraceEntry;
     use crate::{
         hint_processor::{
-            builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
-            hint_processor_definition::HintProcessor,
+            builtin_hint_processor::{
+                builtin_hint_processor_definition::{BuiltinHintProcessor, HintFunc},
+                hint_utils::insert_value_from_var_name,
+            },
+            hint_processor_definition::{HintProcessor, HintReference},
         },
+        serde::deserialize_program::ApTracking,
+        types::exec_scope::ExecutionScopes,
         utils::test_utils::*,
+        vm::errors::{
+            hint_errors::HintError, memory_errors::MemoryError, vm_errors::VirtualMachineError,
+        },
     };
+    use assert_matches::assert_matches;
     use bincode::enc::write::SliceWriter;
     use felt::Felt;
 
@@ -177,6 +327,149 @@ mod tests {
         Ok((cairo_runner, vm))
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn cairo_run_with_args_sums_array() {
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let (_, vm) = cairo_run_with_args(
+            include_bytes!("../cairo_programs/sum_array_args.json"),
+            &CairoRunConfig {
+                entrypoint: "sum_array",
+                layout: "all",
+                ..Default::default()
+            },
+            &[
+                &MaybeRelocatable::from(10).into(),
+                &CairoArg::Span(vec![
+                    MaybeRelocatable::from(1),
+                    MaybeRelocatable::from(2),
+                    MaybeRelocatable::from(3),
+                    MaybeRelocatable::from(4),
+                ]),
+            ],
+            &mut hint_processor,
+        )
+        .unwrap();
+        assert_eq!(
+            vm.get_return_values(1).unwrap(),
+            vec![MaybeRelocatable::from(20)]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn cairo_run_with_args_empty_array() {
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let (_, vm) = cairo_run_with_args(
+            include_bytes!("../cairo_programs/sum_array_args.json"),
+            &CairoRunConfig {
+                entrypoint: "sum_array",
+                layout: "all",
+                ..Default::default()
+            },
+            &[&MaybeRelocatable::from(7).into(), &CairoArg::Span(vec![])],
+            &mut hint_processor,
+        )
+        .unwrap();
+        assert_eq!(
+            vm.get_return_values(1).unwrap(),
+            vec![MaybeRelocatable::from(7)]
+        );
+    }
+
+    fn read_program_input_x(
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        ids_data: &HashMap<String, HintReference>,
+        ap_tracking: &ApTracking,
+        _constants: &HashMap<String, Felt>,
+    ) -> Result<(), HintError> {
+        let program_input: &HashMap<String, serde_json::Value> =
+            exec_scopes.get_ref("program_input")?;
+        let x = program_input
+            .get("x")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or_else(|| HintError::CustomHint(String::from("Missing program input x")))?;
+        insert_value_from_var_name("x", Felt::from(x), vm, ids_data, ap_tracking)
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn cairo_run_write_beyond_max_segment_offset() {
+        let program_content = include_bytes!("../cairo_programs/high_offset_write.json");
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let result = cairo_run(
+            program_content,
+            &CairoRunConfig {
+                max_segment_offset: Some(100),
+                ..Default::default()
+            },
+            &mut hint_processor,
+        );
+        assert_matches!(
+            result,
+            Err(CairoRunError::VmException(VmException {
+                inner_exc: VirtualMachineError::Memory(MemoryError::OffsetExceedsMaximum(
+                    Relocatable { offset: 1000, .. },
+                    100
+                )),
+                ..
+            }))
+        );
+
+        // The write is allowed with a higher maximum
+        let result = cairo_run(
+            program_content,
+            &CairoRunConfig {
+                max_segment_offset: Some(1000),
+                ..Default::default()
+            },
+            &mut hint_processor,
+        );
+        assert_matches!(result, Ok(_));
+    }
+
+    fn run_program_input(
+        program_input: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<(CairoRunner, VirtualMachine), CairoRunError> {
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        hint_processor.add_hint(
+            String::from("ids.x = program_input[\"x\"]"),
+            Arc::new(HintFunc(Box::new(read_program_input_x))),
+        );
+        cairo_run(
+            include_bytes!("../cairo_programs/program_input.json"),
+            &CairoRunConfig {
+                program_input,
+                ..Default::default()
+            },
+            &mut hint_processor,
+        )
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn cairo_run_with_program_input() {
+        let program_input = HashMap::from([(String::from("x"), serde_json::json!(42))]);
+        assert_matches!(run_program_input(Some(program_input)), Ok(_));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn cairo_run_with_wrong_program_input() {
+        let program_input = HashMap::from([(String::from("x"), serde_json::json!(41))]);
+        assert_matches!(
+            run_program_input(Some(program_input)),
+            Err(CairoRunError::VmException(_))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn cairo_run_without_program_input() {
+        assert_matches!(run_program_input(None), Err(CairoRunError::VmException(_)));
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn cairo_run_custom_entry_point() {
@@ -193,7 +486,7 @@ mod tests {
         assert!(cairo_runner
             .run_until_pc(end, &mut vm, &mut hint_processor)
             .is_ok());
-        assert!(cairo_runner.relocate(&mut vm).is_ok());
+        assert!(cairo_runner.relocate(&mut vm, true).is_ok());
         // `main` returns without doing nothing, but `not_main` sets `[ap]` to `1`
         // Memory location was found empirically and simply hardcoded
         assert_eq!(cairo_runner.relocated_memory[2], Some(Felt::new(123)));
@@ -261,7 +554,7 @@ mod tests {
         let (mut cairo_runner, mut vm) = cairo_runner_result.unwrap();
 
         // relocate memory so we can dump it to file
-        assert!(cairo_runner.relocate(&mut vm).is_ok());
+        assert!(cairo_runner.relocate(&mut vm, true).is_ok());
         assert!(vm.trace.is_some());
         assert!(cairo_runner.relocated_trace.is_some());
 
@@ -288,7 +581,7 @@ mod tests {
         let (mut cairo_runner, mut vm) = cairo_runner_result.unwrap();
 
         // relocate memory so we can dump it to file
-        assert!(cairo_runner.relocate(&mut vm).is_ok());
+        assert!(cairo_runner.relocate(&mut vm, true).is_ok());
 
         let mut buffer = [0; 120];
         let mut buff_writer = SliceWriter::new(&mut buffer);
@@ -299,6 +592,47 @@ mod tests {
         assert_eq!(*expected_encoded_memory, buffer);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn write_encoded_memory_batches_contiguous_cells() {
+        // Collects the bytes written along with the number of write calls
+        #[derive(Default)]
+        struct CountingWriter {
+            bytes: Vec<u8>,
+            writes: usize,
+        }
+
+        impl Writer for CountingWriter {
+            fn write(&mut self, bytes: &[u8]) -> Result<(), bincode::error::EncodeError> {
+                self.bytes.extend_from_slice(bytes);
+                self.writes += 1;
+                Ok(())
+            }
+        }
+
+        let relocated_memory = vec![
+            None,
+            Some(Felt::new(1)),
+            Some(Felt::new(2)),
+            None,
+            None,
+            Some(Felt::new(3)),
+        ];
+        let mut writer = CountingWriter::default();
+        write_encoded_memory(&relocated_memory, &mut writer).unwrap();
+
+        let mut expected = Vec::new();
+        for (address, value) in [(1_u64, 1_u8), (2, 2), (5, 3)] {
+            expected.extend_from_slice(&address.to_le_bytes());
+            let mut value_bytes = [0; FELT_BYTES];
+            value_bytes[0] = value;
+            expected.extend_from_slice(&value_bytes);
+        }
+        assert_eq!(writer.bytes, expected);
+        // One write per run of contiguous cells
+        assert_eq!(writer.writes, 2);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn run_with_no_trace() {
@@ -317,4 +651,142 @@ mod tests {
             .is_ok());
         assert!(vm.trace.is_none());
     }
+
+    fn bitwise_output_cairo_pie() -> CairoPie {
+        let cairo_run_config = CairoRunConfig {
+            layout: "all",
+            trace_enabled: true,
+            ..Default::default()
+        };
+        let (cairo_runner, vm) = cairo_run(
+            include_bytes!("../cairo_programs/bitwise_output.json"),
+            &cairo_run_config,
+            &mut BuiltinHintProcessor::new_empty(),
+        )
+        .unwrap();
+        cairo_runner.get_cairo_pie(&vm).unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn program_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Program>();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn runner_vm_and_hint_processor_are_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<CairoRunner>();
+        assert_send::<VirtualMachine>();
+        assert_send::<BuiltinHintProcessor>();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn run_initialized_runner_on_another_thread() {
+        let program = Program::from_bytes(
+            include_bytes!("../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = CairoRunner::new(&program, "all", false).unwrap();
+        let mut vm = VirtualMachine::new(false);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+
+        let (cairo_runner, vm) = std::thread::spawn(move || {
+            cairo_runner
+                .run_until_pc(end, &mut vm, &mut hint_processor)
+                .unwrap();
+            (cairo_runner, vm)
+        })
+        .join()
+        .unwrap();
+        assert!(vm.get_current_step() > 0);
+        assert_eq!(
+            cairo_runner.get_execution_resources(&vm).unwrap().n_steps,
+            vm.get_current_step()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn cairo_run_program_on_several_threads() {
+        let program = Arc::new(
+            Program::from_bytes(
+                include_bytes!("../cairo_programs/fibonacci.json"),
+                Some("main"),
+            )
+            .unwrap(),
+        );
+        fn run(program: &Program) -> (Vec<Option<Felt>>, Option<Vec<RelocatedTraceEntry>>) {
+            let cairo_run_config = CairoRunConfig {
+                trace_enabled: true,
+                ..CairoRunConfig::default()
+            };
+            let mut hint_processor = BuiltinHintProcessor::new_empty();
+            let (cairo_runner, _) =
+                cairo_run_program(program, &cairo_run_config, &mut hint_processor).unwrap();
+            (cairo_runner.relocated_memory, cairo_runner.relocated_trace)
+        }
+
+        let expected = run(&program);
+        assert!(expected.1.is_some());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let program = program.clone();
+                std::thread::spawn(move || run(&program))
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn cairo_pie_zip_round_trip_and_rerun() {
+        let cairo_pie = bitwise_output_cairo_pie();
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        cairo_pie.write_zip(&mut buffer).unwrap();
+        buffer.set_position(0);
+
+        let loaded_cairo_pie = CairoPie::from_zip(buffer).unwrap();
+        assert_eq!(loaded_cairo_pie, cairo_pie);
+
+        let program_hash = cairo_pie.metadata.program.hash(0).unwrap();
+        let (cairo_runner, vm) = cairo_run_pie(&loaded_cairo_pie, &program_hash, "all").unwrap();
+        assert_eq!(
+            cairo_runner.get_execution_resources(&vm).unwrap(),
+            cairo_pie.execution_resources
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn cairo_run_pie_wrong_program_hash() {
+        let cairo_pie = bitwise_output_cairo_pie();
+        let program_hash = cairo_pie.metadata.program.hash(0).unwrap();
+        assert_matches!(
+            cairo_run_pie(&cairo_pie, &Felt::new(1), "all"),
+            Err(CairoRunError::Runner(RunnerError::CairoPieProgramHashMismatch(expected, found)))
+                if expected == Felt::new(1) && found == program_hash
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn cairo_run_pie_execution_resources_mismatch() {
+        let mut cairo_pie = bitwise_output_cairo_pie();
+        cairo_pie.execution_resources.n_steps += 1;
+        let program_hash = cairo_pie.metadata.program.hash(0).unwrap();
+        assert_matches!(
+            cairo_run_pie(&cairo_pie, &program_hash, "all"),
+            Err(CairoRunError::Runner(
+                RunnerError::CairoPieExecutionResourcesMismatch
+            ))
+        );
+    }
 }
@@ -288,4 +288,24 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn blake2s_compress_rfc7693_abc() {
+        //Single-block BLAKE2s-256 of "abc", from RFC 7693 Appendix B
+        let mut h = IV;
+        h[0] ^= 0x01010020;
+        let mut message = [0_u32; 16];
+        message[0] = 0x00636261;
+        let new_state: [u32; 8] = blake2s_compress(&h, &message, 3, 0, 0xFFFFFFFF, 0)
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            new_state,
+            [
+                0x8C5E8C50, 0xE2147C32, 0xA32BA7E1, 0x2F45EB4E, 0x208B4537, 0x293AD69E, 0x4C9B994D,
+                0x82596786
+            ]
+        )
+    }
 }
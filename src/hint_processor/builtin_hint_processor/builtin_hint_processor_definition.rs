@@ -1,4 +1,9 @@
-use crate::stdlib::{any::Any, collections::HashMap, prelude::*, rc::Rc};
+use crate::stdlib::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    prelude::*,
+    sync::Arc,
+};
 
 use crate::{
     hint_processor::{
@@ -93,26 +98,71 @@ pub struct HintFunc(
                 &ApTracking,
                 &HashMap<String, Felt>,
             ) -> Result<(), HintError>
-            + Sync,
+            + Sync
+            + Send,
     >,
 );
+/// Restricts the hints a [BuiltinHintProcessor] will execute, by their code.
+/// Hints rejected by the policy fail with [HintError::HintNotAllowed] instead of running.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum HintProcessorPolicy {
+    /// Every hint can run
+    #[default]
+    AllowAll,
+    /// Only the listed hints can run
+    Allowlist(HashSet<String>),
+    /// Every hint can run, except the listed ones
+    Denylist(HashSet<String>),
+}
+
+impl HintProcessorPolicy {
+    pub fn allows(&self, hint_code: &str) -> bool {
+        match self {
+            HintProcessorPolicy::AllowAll => true,
+            HintProcessorPolicy::Allowlist(hints) => hints.contains(hint_code),
+            HintProcessorPolicy::Denylist(hints) => !hints.contains(hint_code),
+        }
+    }
+}
+
 pub struct BuiltinHintProcessor {
-    pub extra_hints: HashMap<String, Rc<HintFunc>>,
+    pub extra_hints: HashMap<String, Arc<HintFunc>>,
+    pub constant_overrides: HashMap<String, Felt>,
+    pub policy: HintProcessorPolicy,
 }
 impl BuiltinHintProcessor {
     pub fn new_empty() -> Self {
         BuiltinHintProcessor {
             extra_hints: HashMap::new(),
+            constant_overrides: HashMap::new(),
+            policy: HintProcessorPolicy::default(),
+        }
+    }
+
+    pub fn new(extra_hints: HashMap<String, Arc<HintFunc>>) -> Self {
+        BuiltinHintProcessor {
+            extra_hints,
+            constant_overrides: HashMap::new(),
+            policy: HintProcessorPolicy::default(),
         }
     }
 
-    pub fn new(extra_hints: HashMap<String, Rc<HintFunc>>) -> Self {
-        BuiltinHintProcessor { extra_hints }
+    /// Restricts the hints this processor will execute, custom hints included
+    pub fn set_policy(&mut self, policy: HintProcessorPolicy) {
+        self.policy = policy;
     }
 
-    pub fn add_hint(&mut self, hint_code: String, hint_func: Rc<HintFunc>) {
+    /// Registers `hint_func` as the implementation of the hint whose source is `hint_code`.
+    /// Custom hints are looked up before the built-in ones, so they can also replace them.
+    pub fn add_hint(&mut self, hint_code: String, hint_func: Arc<HintFunc>) {
         self.extra_hints.insert(hint_code, hint_func);
     }
+
+    /// Makes `value` available to hints as the constant `name` (e.g. `__main__.MY_CONSTANT`).
+    /// Takes precedence over a program constant with the same name.
+    pub fn add_constant_override(&mut self, name: String, value: Felt) {
+        self.constant_overrides.insert(name, value);
+    }
 }
 
 impl HintProcessor for BuiltinHintProcessor {
@@ -127,6 +177,10 @@ impl HintProcessor for BuiltinHintProcessor {
             .downcast_ref::<HintProcessorData>()
             .ok_or(HintError::WrongHintData)?;
 
+        if !self.policy.allows(&hint_data.code) {
+            return Err(HintError::HintNotAllowed(hint_data.code.clone()));
+        }
+
         if let Some(hint_func) = self.extra_hints.get(&hint_data.code) {
             return hint_func.0(
                 vm,
@@ -450,20 +504,31 @@ impl HintProcessor for BuiltinHintProcessor {
             code => Err(HintError::UnknownHint(code.to_string())),
         }
     }
+
+    fn constant_overrides(&self) -> Option<&HashMap<String, Felt>> {
+        Some(&self.constant_overrides)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::stdlib::any::Any;
     use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
     use crate::{
         any_box,
+        cairo_run::{cairo_run, CairoRunConfig},
         hint_processor::hint_processor_definition::HintProcessor,
-        types::{exec_scope::ExecutionScopes, relocatable::MaybeRelocatable},
+        types::{
+            exec_scope::{ExecutionScopes, ScopeVariable},
+            relocatable::MaybeRelocatable,
+        },
         utils::test_utils::*,
         vm::{
-            errors::{exec_scope_errors::ExecScopeError, memory_errors::MemoryError},
+            errors::{
+                cairo_run_errors::CairoRunError, exec_scope_errors::ExecScopeError,
+                memory_errors::MemoryError, vm_errors::VirtualMachineError,
+                vm_exception::VmException,
+            },
             vm_core::VirtualMachine,
             vm_memory::memory::Memory,
         },
@@ -650,7 +715,7 @@ mod tests {
         let mut vm = vm!();
         // Create new vm scope with dummy variable
         let mut exec_scopes = ExecutionScopes::new();
-        let a_value: Box<dyn Any> = Box::new(Felt::one());
+        let a_value: ScopeVariable = Box::new(Felt::one());
         exec_scopes.enter_scope(HashMap::from([(String::from("a"), a_value)]));
         // Initialize memory segments
         add_segments!(vm, 1);
@@ -867,8 +932,8 @@ mod tests {
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn add_hint_add_same_hint_twice() {
         let mut hint_processor = BuiltinHintProcessor::new_empty();
-        let hint_func = Rc::new(HintFunc(Box::new(enter_scope)));
-        hint_processor.add_hint(String::from("enter_scope_custom_a"), Rc::clone(&hint_func));
+        let hint_func = Arc::new(HintFunc(Box::new(enter_scope)));
+        hint_processor.add_hint(String::from("enter_scope_custom_a"), Arc::clone(&hint_func));
         hint_processor.add_hint(String::from("enter_scope_custom_b"), hint_func);
         let mut vm = vm!();
         let exec_scopes = exec_scopes_ref!();
@@ -898,4 +963,129 @@ mod tests {
         );
         assert_eq!(exec_scopes.data.len(), 3);
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn add_hint_closure_writes_constant() {
+        let value = Felt::new(42);
+        let hint_func = HintFunc(Box::new(
+            move |vm: &mut VirtualMachine,
+                  _: &mut ExecutionScopes,
+                  _: &HashMap<String, HintReference>,
+                  _: &ApTracking,
+                  _: &HashMap<String, Felt>| {
+                vm.insert_value(vm.get_ap(), value.clone())
+                    .map_err(HintError::from)
+            },
+        ));
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        hint_processor.add_hint(String::from("memory[ap] = 42"), Arc::new(hint_func));
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+        let hint_data =
+            HintProcessorData::new_default(String::from("memory[ap] = 42"), HashMap::new());
+        assert_matches!(
+            hint_processor.execute_hint(
+                &mut vm,
+                exec_scopes_ref!(),
+                &any_box!(hint_data),
+                &HashMap::new()
+            ),
+            Ok(())
+        );
+        check_memory!(vm.segments.memory, ((1, 0), 42));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn add_hint_overrides_builtin_hint() {
+        let hint_func = HintFunc(Box::new(
+            |vm: &mut VirtualMachine,
+             _: &mut ExecutionScopes,
+             _: &HashMap<String, HintReference>,
+             _: &ApTracking,
+             _: &HashMap<String, Felt>| {
+                vm.insert_value(vm.get_ap(), Felt::new(7))
+                    .map_err(HintError::from)
+            },
+        ));
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        hint_processor.add_hint(String::from(hint_code::ADD_SEGMENT), Arc::new(hint_func));
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+        let hint_data =
+            HintProcessorData::new_default(String::from(hint_code::ADD_SEGMENT), HashMap::new());
+        assert_matches!(
+            hint_processor.execute_hint(
+                &mut vm,
+                exec_scopes_ref!(),
+                &any_box!(hint_data),
+                &HashMap::new()
+            ),
+            Ok(())
+        );
+        // The custom hint ran instead of adding a segment
+        assert_eq!(vm.segments.num_segments(), 2);
+        check_memory!(vm.segments.memory, ((1, 0), 7));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn hint_processor_policy_allows() {
+        let hints = HashSet::from([String::from(hint_code::ADD_SEGMENT)]);
+        assert!(HintProcessorPolicy::default().allows(hint_code::ADD_SEGMENT));
+        assert!(HintProcessorPolicy::Allowlist(hints.clone()).allows(hint_code::ADD_SEGMENT));
+        assert!(!HintProcessorPolicy::Allowlist(hints.clone()).allows(hint_code::IS_NN));
+        assert!(!HintProcessorPolicy::Denylist(hints.clone()).allows(hint_code::ADD_SEGMENT));
+        assert!(HintProcessorPolicy::Denylist(hints).allows(hint_code::IS_NN));
+    }
+
+    fn run_array_sum(policy: HintProcessorPolicy) -> Result<(), CairoRunError> {
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        hint_processor.set_policy(policy);
+        cairo_run(
+            include_bytes!("../../../cairo_programs/array_sum.json"),
+            &CairoRunConfig {
+                layout: "all",
+                ..Default::default()
+            },
+            &mut hint_processor,
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_program_with_allowed_hint() {
+        let allowlist = HashSet::from([String::from(hint_code::ADD_SEGMENT)]);
+        assert_matches!(
+            run_array_sum(HintProcessorPolicy::Allowlist(allowlist)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_program_with_hint_not_in_allowlist() {
+        assert_matches!(
+            run_array_sum(HintProcessorPolicy::Allowlist(HashSet::new())),
+            Err(CairoRunError::VmException(VmException {
+                inner_exc: VirtualMachineError::Hint(_, err),
+                ..
+            })) if matches!(*err, HintError::HintNotAllowed(ref code) if code == hint_code::ADD_SEGMENT)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_program_with_denied_hint() {
+        let denylist = HashSet::from([String::from(hint_code::ADD_SEGMENT)]);
+        assert_matches!(
+            run_array_sum(HintProcessorPolicy::Denylist(denylist)),
+            Err(CairoRunError::VmException(VmException {
+                inner_exc: VirtualMachineError::Hint(_, err),
+                ..
+            })) if matches!(*err, HintError::HintNotAllowed(ref code) if code == hint_code::ADD_SEGMENT)
+        );
+    }
 }
@@ -1,12 +1,15 @@
-use crate::stdlib::{any::Any, cell::RefCell, collections::HashMap, prelude::*, rc::Rc};
+use crate::stdlib::{collections::HashMap, prelude::*};
 
 use crate::{
-    types::{exec_scope::ExecutionScopes, relocatable::MaybeRelocatable},
+    types::{
+        exec_scope::{ExecutionScopes, ScopeVariable},
+        relocatable::MaybeRelocatable,
+        shared::Shared,
+    },
     vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
 };
 
 use crate::{
-    any_box,
     hint_processor::{
         builtin_hint_processor::hint_utils::{
             get_ptr_from_var_name, insert_value_from_var_name, insert_value_into_ap,
@@ -56,7 +59,7 @@ pub fn dict_new(
     } else {
         let mut dict_manager = DictManager::new();
         let base = dict_manager.new_dict(vm, initial_dict)?;
-        exec_scopes.insert_value("dict_manager", Rc::new(RefCell::new(dict_manager)));
+        exec_scopes.insert_value("dict_manager", Shared::new(dict_manager));
         base
     };
     insert_value_into_ap(vm, base)
@@ -91,7 +94,7 @@ pub fn default_dict_new(
     } else {
         let mut dict_manager = DictManager::new();
         let base = dict_manager.new_default_dict(vm, &default_value, initial_dict)?;
-        exec_scopes.insert_value("dict_manager", Rc::new(RefCell::new(dict_manager)));
+        exec_scopes.insert_value("dict_manager", Shared::new(dict_manager));
         base
     };
     insert_value_into_ap(vm, base)
@@ -212,7 +215,7 @@ pub fn dict_squash_copy_dict(
     let dict_accesses_end = get_ptr_from_var_name("dict_accesses_end", vm, ids_data, ap_tracking)?;
     let dict_manager_ref = exec_scopes.get_dict_manager()?;
     let dict_manager = dict_manager_ref.borrow();
-    let dict_copy: Box<dyn Any> = Box::new(
+    let dict_copy: ScopeVariable = Box::new(
         dict_manager
             .get_tracker(dict_accesses_end)?
             .get_dictionary_copy(),
@@ -220,7 +223,7 @@ pub fn dict_squash_copy_dict(
     exec_scopes.enter_scope(HashMap::from([
         (
             String::from("dict_manager"),
-            any_box!(exec_scopes.get_dict_manager()?),
+            Box::new(exec_scopes.get_dict_manager()?) as ScopeVariable,
         ),
         (String::from("initial_dict"), dict_copy),
     ]));
@@ -397,7 +400,7 @@ mod tests {
         //Initialize fp
         vm.run_context.fp = 3;
         //Create manager
-        let mut exec_scopes = scope![("dict_manager", Rc::new(RefCell::new(DictManager::new())))];
+        let mut exec_scopes = scope![("dict_manager", Shared::new(DictManager::new()))];
 
         //Insert ids into memory
         vm.segments = segments![((1, 0), 6), ((1, 2), (2, 0))];
@@ -875,7 +878,7 @@ mod tests {
         vm.run_context.fp = 1;
         //Create manager
         let dict_manager = DictManager::new();
-        let mut exec_scopes = scope![("dict_manager", Rc::new(RefCell::new(dict_manager)))];
+        let mut exec_scopes = scope![("dict_manager", Shared::new(dict_manager))];
 
         vm.segments = segments![((1, 0), (2, 0))];
         add_segments!(vm, 1);
@@ -896,7 +899,7 @@ mod tests {
         vm.run_context.fp = 2;
         //Create manager
         let dict_manager = DictManager::new();
-        let mut exec_scopes = scope![("dict_manager", Rc::new(RefCell::new(dict_manager)))];
+        let mut exec_scopes = scope![("dict_manager", Shared::new(dict_manager))];
         vm.segments = segments![((1, 0), (2, 0)), ((1, 1), (2, 3))];
         add_segments!(vm, 1);
         //Create ids
@@ -16,6 +16,17 @@ use crate::{
 use felt::Felt;
 use num_traits::{Signed, ToPrimitive};
 
+// Names of the scope variables read by the hints, the ones used by the Python VM come last
+const FIND_ELEMENT_INDEX: [&str; 2] = ["find_element_index", "__find_element_index"];
+const FIND_ELEMENT_MAX_SIZE: [&str; 2] = ["find_element_max_size", "__find_element_max_size"];
+
+// Returns the value of the first scope variable found among `names`
+fn get_scope_felt(exec_scopes: &ExecutionScopes, names: &[&str]) -> Option<Felt> {
+    names
+        .iter()
+        .find_map(|name| exec_scopes.get::<Felt>(name).ok())
+}
+
 pub fn find_element(
     vm: &mut VirtualMachine,
     exec_scopes: &mut ExecutionScopes,
@@ -26,7 +37,7 @@ pub fn find_element(
     let elm_size_bigint = get_integer_from_var_name("elm_size", vm, ids_data, ap_tracking)?;
     let n_elms = get_integer_from_var_name("n_elms", vm, ids_data, ap_tracking)?;
     let array_start = get_ptr_from_var_name("array_ptr", vm, ids_data, ap_tracking)?;
-    let find_element_index = exec_scopes.get::<Felt>("find_element_index").ok();
+    let find_element_index = get_scope_felt(exec_scopes, &FIND_ELEMENT_INDEX);
     let elm_size = elm_size_bigint
         .to_usize()
         .ok_or_else(|| HintError::ValueOutOfRange(elm_size_bigint.as_ref().clone()))?;
@@ -48,17 +59,19 @@ pub fn find_element(
             ));
         }
         insert_value_from_var_name("index", find_element_index_value, vm, ids_data, ap_tracking)?;
-        exec_scopes.delete_variable("find_element_index");
+        for name in FIND_ELEMENT_INDEX {
+            exec_scopes.delete_variable(name);
+        }
         Ok(())
     } else {
         if n_elms.is_negative() {
             return Err(HintError::ValueOutOfRange(n_elms.into_owned()));
         }
 
-        if let Ok(find_element_max_size) = exec_scopes.get_ref::<Felt>("find_element_max_size") {
-            if n_elms.as_ref() > find_element_max_size {
+        if let Some(find_element_max_size) = get_scope_felt(exec_scopes, &FIND_ELEMENT_MAX_SIZE) {
+            if n_elms.as_ref() > &find_element_max_size {
                 return Err(HintError::FindElemMaxSize(
-                    find_element_max_size.clone(),
+                    find_element_max_size,
                     n_elms.into_owned(),
                 ));
             }
@@ -93,7 +106,7 @@ pub fn search_sorted_lower(
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
 ) -> Result<(), HintError> {
-    let find_element_max_size = exec_scopes.get::<Felt>("find_element_max_size");
+    let find_element_max_size = get_scope_felt(exec_scopes, &FIND_ELEMENT_MAX_SIZE);
     let n_elms = get_integer_from_var_name("n_elms", vm, ids_data, ap_tracking)?;
     let rel_array_ptr = get_relocatable_from_var_name("array_ptr", vm, ids_data, ap_tracking)?;
     let elm_size = get_integer_from_var_name("elm_size", vm, ids_data, ap_tracking)?;
@@ -107,7 +120,7 @@ pub fn search_sorted_lower(
         return Err(HintError::ValueOutOfRange(n_elms.into_owned()));
     }
 
-    if let Ok(find_element_max_size) = find_element_max_size {
+    if let Some(find_element_max_size) = find_element_max_size {
         if n_elms.as_ref() > &find_element_max_size {
             return Err(HintError::FindElemMaxSize(
                 find_element_max_size,
@@ -231,6 +244,18 @@ mod tests {
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn element_found_by_oracle() {
+        let (mut vm, ids_data) = init_vm_ids_data(HashMap::new());
+        let mut exec_scopes = scope![("__find_element_index", Felt::one())];
+        assert_matches!(
+            run_hint!(vm, ids_data, hint_code::FIND_ELEMENT, &mut exec_scopes),
+            Ok(())
+        );
+        check_memory![vm.segments.memory, ((1, 3), 1)];
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn element_found_by_oracle_legacy_name() {
         let (mut vm, ids_data) = init_vm_ids_data(HashMap::new());
         let mut exec_scopes = scope![("find_element_index", Felt::one())];
         assert_matches!(
@@ -238,6 +263,8 @@ mod tests {
             Ok(())
         );
         check_memory![vm.segments.memory, ((1, 3), 1)];
+        // The index is only used once
+        assert!(exec_scopes.get::<Felt>("find_element_index").is_err());
     }
 
     #[test]
@@ -257,7 +284,7 @@ mod tests {
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn element_not_found_oracle() {
         let (mut vm, ids_data) = init_vm_ids_data(HashMap::new());
-        let mut exec_scopes = scope![("find_element_index", Felt::new(2))];
+        let mut exec_scopes = scope![("__find_element_index", Felt::new(2))];
         assert_matches!(
             run_hint!(vm, ids_data, hint_code::FIND_ELEMENT, &mut exec_scopes),
             Err(HintError::KeyNotFound)
@@ -354,7 +381,7 @@ mod tests {
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn find_elm_n_elms_gt_max_size() {
         let (mut vm, ids_data) = init_vm_ids_data(HashMap::new());
-        let mut exec_scopes = scope![("find_element_max_size", Felt::one())];
+        let mut exec_scopes = scope![("__find_element_max_size", Felt::one())];
         assert_matches!(
             run_hint!(vm, ids_data, hint_code::FIND_ELEMENT, &mut exec_scopes),
             Err(HintError::FindElemMaxSize(x, y)) if x == Felt::one() && y == Felt::new(2)
@@ -479,6 +506,22 @@ mod tests {
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn search_sorted_lower_n_elms_gt_max_size() {
+        let (mut vm, ids_data) = init_vm_ids_data(HashMap::new());
+        let mut exec_scopes = scope![("__find_element_max_size", Felt::one())];
+        assert_matches!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code::SEARCH_SORTED_LOWER,
+                &mut exec_scopes
+            ),
+            Err(HintError::FindElemMaxSize(x, y)) if x == Felt::one() && y == Felt::new(2)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn search_sorted_lower_n_elms_gt_max_size_legacy_name() {
         let (mut vm, ids_data) = init_vm_ids_data(HashMap::new());
         let mut exec_scopes = scope![("find_element_max_size", Felt::one())];
         assert_matches!(
@@ -181,10 +181,3 @@ fn left_pad(bytes_vector: &mut [u8], n_zeros: usize) -> Vec<u8> {
 
     res
 }
-
-pub(crate) fn left_pad_u64(bytes_vector: &mut [u64], n_zeros: usize) -> Vec<u64> {
-    let mut res: Vec<u64> = vec![0; n_zeros];
-    res.extend(bytes_vector.iter());
-
-    res
-}
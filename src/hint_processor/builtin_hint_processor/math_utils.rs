@@ -4,10 +4,9 @@ use crate::stdlib::{
     prelude::*,
 };
 
-use crate::utils::CAIRO_PRIME;
+use crate::utils::{biguint_to_felt, felt_to_biguint, CAIRO_PRIME};
 
 use crate::{
-    any_box,
     hint_processor::{
         builtin_hint_processor::hint_utils::{
             get_integer_from_var_name, get_ptr_from_var_name, insert_value_from_var_name,
@@ -15,7 +14,7 @@ use crate::{
         },
         hint_processor_definition::HintReference,
     },
-    math_utils::isqrt,
+    math_utils::{as_int, isqrt},
     serde::deserialize_program::ApTracking,
     types::{exec_scope::ExecutionScopes, relocatable::MaybeRelocatable},
     vm::{
@@ -24,7 +23,7 @@ use crate::{
     },
 };
 use felt::Felt;
-use num_bigint::BigUint;
+use num_bigint::{BigInt, BigUint};
 use num_integer::Integer;
 use num_traits::One;
 use num_traits::{Signed, Zero};
@@ -118,7 +117,7 @@ pub fn assert_le_felt(
     }
 
     let excluded = lengths_and_indices[2].1;
-    exec_scopes.assign_or_update_variable("excluded", any_box!(Felt::new(excluded)));
+    exec_scopes.insert_value("excluded", Felt::new(excluded));
 
     let (q_0, r_0) = (lengths_and_indices[0].0).div_mod_floor(prime_over_3_high);
     let (q_1, r_1) = (lengths_and_indices[1].0).div_mod_floor(prime_over_2_high);
@@ -312,16 +311,17 @@ pub fn is_positive(
     ap_tracking: &ApTracking,
 ) -> Result<(), HintError> {
     let value = get_integer_from_var_name("value", vm, ids_data, ap_tracking)?;
+    let value_as_int = as_int(&value, &BigInt::from(CAIRO_PRIME.clone()));
     let range_check_builtin = vm.get_range_check_builtin()?;
     //Main logic (assert a is positive)
     match &range_check_builtin._bound {
-        Some(bound) if &value.abs() > bound => {
+        Some(bound) if value_as_int.abs() > bound.to_bigint() => {
             return Err(HintError::ValueOutsideValidRange(value.into_owned()))
         }
         _ => {}
     };
 
-    let result = if value.is_positive() {
+    let result = if value_as_int.is_positive() {
         Felt::one()
     } else {
         Felt::zero()
@@ -371,10 +371,9 @@ pub fn sqrt(
         return Err(HintError::ValueOutside250BitRange(mod_value.into_owned()));
         //This is equal to mod_value > bigint!(2).pow(250)
     }
-    #[allow(deprecated)]
     insert_value_from_var_name(
         "root",
-        Felt::new(isqrt(&mod_value.to_biguint())?),
+        biguint_to_felt(isqrt(&felt_to_biguint(&mod_value))?),
         vm,
         ids_data,
         ap_tracking,
@@ -416,8 +415,7 @@ pub fn signed_div_rem(
         _ => {}
     }
 
-    #[allow(deprecated)]
-    let int_value = value.to_bigint();
+    let int_value = as_int(value, &BigInt::from(CAIRO_PRIME.clone()));
     #[allow(deprecated)]
     let int_div = div.to_bigint();
     #[allow(deprecated)]
@@ -530,9 +528,7 @@ pub fn assert_lt_felt(
 
 fn div_prime_by_bound(bound: Felt) -> Result<Felt, VirtualMachineError> {
     let prime: &BigUint = &CAIRO_PRIME;
-    #[allow(deprecated)]
-    let limit = prime / bound.to_biguint();
-    Ok(Felt::new(limit))
+    Ok(biguint_to_felt(prime / felt_to_biguint(&bound)))
 }
 
 #[cfg(test)]
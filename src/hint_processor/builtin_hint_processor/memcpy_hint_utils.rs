@@ -1,4 +1,4 @@
-use crate::stdlib::{any::Any, collections::HashMap, prelude::*};
+use crate::stdlib::{collections::HashMap, prelude::*};
 
 use crate::{
     hint_processor::{
@@ -8,7 +8,7 @@ use crate::{
         hint_processor_definition::HintReference,
     },
     serde::deserialize_program::ApTracking,
-    types::exec_scope::ExecutionScopes,
+    types::exec_scope::{ExecutionScopes, ScopeVariable},
     vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
 };
 use felt::Felt;
@@ -40,7 +40,7 @@ pub fn memcpy_enter_scope(
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
 ) -> Result<(), HintError> {
-    let len: Box<dyn Any> =
+    let len: ScopeVariable =
         Box::new(get_integer_from_var_name("len", vm, ids_data, ap_tracking)?.into_owned());
     exec_scopes.enter_scope(HashMap::from([(String::from("n"), len)]));
     Ok(())
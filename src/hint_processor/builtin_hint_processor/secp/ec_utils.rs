@@ -462,6 +462,69 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_compute_doubling_slope_generator() {
+        let hint_code = "from starkware.cairo.common.cairo_secp.secp_utils import SECP_P, pack\nfrom starkware.python.math_utils import ec_double_slope\n\n# Compute the slope.\nx = pack(ids.point.x, PRIME)\ny = pack(ids.point.y, PRIME)\nvalue = slope = ec_double_slope(point=(x, y), alpha=0, p=SECP_P)";
+        let mut vm = vm_with_range_check!();
+        //Insert the limbs of the secp256k1 generator point
+        vm.segments = segments![
+            ((1, 0), 17117865558768631194064792_i128),
+            ((1, 1), 12501176021340589225372855_i128),
+            ((1, 2), 9198697782662356105779718_i128),
+            ((1, 3), 6441780312434748884571320_i128),
+            ((1, 4), 57953919405111227542741658_i128),
+            ((1, 5), 5457536640262350763842127_i128)
+        ];
+
+        //Initialize fp
+        vm.run_context.fp = 1;
+
+        let ids_data = ids_data!["point"];
+        let mut exec_scopes = ExecutionScopes::new();
+
+        //Execute the hint
+        assert_matches!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code,
+                &mut exec_scopes,
+                &[(
+                    SECP_REM,
+                    Felt::one().shl(32_u32)
+                        + Felt::one().shl(9_u32)
+                        + Felt::one().shl(8_u32)
+                        + Felt::one().shl(7_u32)
+                        + Felt::one().shl(6_u32)
+                        + Felt::one().shl(4_u32)
+                        + Felt::one()
+                )]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect()
+            ),
+            Ok(())
+        );
+        check_scope!(
+            &exec_scopes,
+            [
+                (
+                    "value",
+                    bigint_str!(
+                        "91914383230618135761690975197207778399550061809281766160147273830617914855857"
+                    )
+                ),
+                (
+                    "slope",
+                    bigint_str!(
+                        "91914383230618135761690975197207778399550061809281766160147273830617914855857"
+                    )
+                )
+            ]
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn run_compute_slope_ok() {
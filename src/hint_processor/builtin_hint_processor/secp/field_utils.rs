@@ -457,6 +457,54 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_is_zero_pack_secp_p() {
+        let hint_code = "from starkware.cairo.common.cairo_secp.secp_utils import SECP_P, pack\n\nx = pack(ids.x, PRIME) % SECP_P";
+        let mut vm = vm_with_range_check!();
+
+        //Initialize fp
+        vm.run_context.fp = 15;
+
+        //Create hint data
+        let ids_data = HashMap::from([("x".to_string(), HintReference::new_simple(-5))]);
+        //Insert the limbs of SECP_P, which packs to zero modulo SECP_P
+        vm.segments = segments![
+            ((1, 10), 77371252455336262886226991_i128),
+            ((1, 11), 77371252455336267181195263_i128),
+            ((1, 12), 19342813113834066795298815_i128)
+        ];
+
+        let mut exec_scopes = ExecutionScopes::new();
+
+        //Execute the hint
+        assert_matches!(
+            run_hint!(
+                vm,
+                ids_data,
+                hint_code,
+                &mut exec_scopes,
+                &[(
+                    SECP_REM,
+                    Felt::one().shl(32_u32)
+                        + Felt::one().shl(9_u32)
+                        + Felt::one().shl(8_u32)
+                        + Felt::one().shl(7_u32)
+                        + Felt::one().shl(6_u32)
+                        + Felt::one().shl(4_u32)
+                        + Felt::one()
+                )]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect()
+            ),
+            Ok(())
+        );
+
+        //Check 'x' is defined in the vm scope
+        check_scope!(&exec_scopes, [("x", BigInt::zero())]);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn run_is_zero_pack_error() {
@@ -511,7 +559,7 @@ mod tests {
 
         let mut exec_scopes = ExecutionScopes::new();
         //Initialize vm scope with variable `x`
-        exec_scopes.assign_or_update_variable("x", any_box!(BigInt::zero()));
+        exec_scopes.insert_value("x", BigInt::zero());
         //Create hint data
         //Execute the hint
         assert_matches!(
@@ -538,7 +586,7 @@ mod tests {
 
         //Initialize vm scope with variable `x`
         let mut exec_scopes = ExecutionScopes::new();
-        exec_scopes.assign_or_update_variable("x", any_box!(bigint!(123890i32)));
+        exec_scopes.insert_value("x", bigint!(123890i32));
 
         //Execute the hint
         assert_matches!(
@@ -586,7 +634,7 @@ mod tests {
 
         //Initialize vm scope with variable `x`
         let mut exec_scopes = ExecutionScopes::new();
-        exec_scopes.assign_or_update_variable("x", any_box!(BigInt::zero()));
+        exec_scopes.insert_value("x", BigInt::zero());
         //Execute the hint
         assert_matches!(
             run_hint!(vm, HashMap::new(), hint_code, &mut exec_scopes),
@@ -610,11 +658,9 @@ mod tests {
 
         //Initialize vm scope with variable `x`
         let mut exec_scopes = ExecutionScopes::new();
-        exec_scopes.assign_or_update_variable(
+        exec_scopes.insert_value(
             "x",
-            any_box!(bigint_str!(
-                "52621538839140286024584685587354966255185961783273479086367"
-            )),
+            bigint_str!("52621538839140286024584685587354966255185961783273479086367"),
         );
         //Execute the hint
         assert_matches!(
@@ -8,14 +8,18 @@ use crate::{
         get_integer_from_var_name, get_relocatable_from_var_name, insert_value_from_var_name,
         insert_value_into_ap,
     },
-    hint_processor::hint_processor_definition::HintReference,
+    hint_processor::{
+        hint_processor_definition::HintReference,
+        hint_processor_utils::{felt_to_i128, felt_to_u128},
+    },
     math_utils::isqrt,
     serde::deserialize_program::ApTracking,
+    utils::{biguint_to_felt, felt_to_biguint},
     vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
 };
 use felt::Felt;
 use num_integer::div_rem;
-use num_traits::{One, Signed, Zero};
+use num_traits::{One, Zero};
 /*
 Implements hint:
 %{
@@ -30,7 +34,6 @@ pub fn uint256_add(
     ids_data: &HashMap<String, HintReference>,
     ap_tracking: &ApTracking,
 ) -> Result<(), HintError> {
-    let shift = Felt::new(1_u32) << 128_u32;
     let a_relocatable = get_relocatable_from_var_name("a", vm, ids_data, ap_tracking)?;
     let b_relocatable = get_relocatable_from_var_name("b", vm, ids_data, ap_tracking)?;
     let a_low = vm.get_integer(a_relocatable)?;
@@ -48,13 +51,13 @@ pub fn uint256_add(
     //sum_high = ids.a.high + ids.b.high + ids.carry_low
     //ids.carry_high = 1 if sum_high >= ids.SHIFT else 0
 
-    let carry_low = if a_low + b_low >= shift {
+    let carry_low = if felt_to_u128(&(a_low + b_low)).is_err() {
         Felt::one()
     } else {
         Felt::zero()
     };
 
-    let carry_high = if a_high + b_high + &carry_low >= shift {
+    let carry_high = if felt_to_u128(&(a_high + b_high + &carry_low)).is_err() {
         Felt::one()
     } else {
         Felt::zero()
@@ -118,8 +121,7 @@ pub fn uint256_sqrt(
     //ids.root.low = root
     //ids.root.high = 0
 
-    #[allow(deprecated)]
-    let root = isqrt(&(&n_high.to_biguint().shl(128_u32) + n_low.to_biguint()))?;
+    let root = isqrt(&(felt_to_biguint(n_high).shl(128_u32) + felt_to_biguint(n_low)))?;
 
     if root >= num_bigint::BigUint::one().shl(128_u32) {
         return Err(HintError::AssertionFailed(format!(
@@ -127,7 +129,7 @@ pub fn uint256_sqrt(
             &root
         )));
     }
-    vm.insert_value(root_addr, Felt::new(root))?;
+    vm.insert_value(root_addr, biguint_to_felt(root))?;
     vm.insert_value((root_addr + 1_i32)?, Felt::zero())
         .map_err(HintError::Memory)
 }
@@ -145,7 +147,7 @@ pub fn uint256_signed_nn(
     let a_high = vm.get_integer((a_addr + 1_usize)?)?;
     //Main logic
     //memory[ap] = 1 if 0 <= (ids.a.high % PRIME) < 2 ** 127 else 0
-    let result: Felt = if !a_high.is_negative() && a_high.as_ref() <= &Felt::new(i128::MAX) {
+    let result: Felt = if matches!(felt_to_i128(&a_high), Ok(high) if high >= 0) {
         Felt::one()
     } else {
         Felt::zero()
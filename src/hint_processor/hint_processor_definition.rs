@@ -25,10 +25,17 @@ pub trait HintProcessor {
         exec_scopes: &mut ExecutionScopes,
         //Data structure that can be downcasted to the structure generated by compile_hint
         hint_data: &Box<dyn Any>,
-        //Constant values extracted from the program specification.
+        //Constant values extracted from the program specification, merged with the overrides
+        //returned by constant_overrides.
         constants: &HashMap<String, Felt>,
     ) -> Result<(), HintError>;
 
+    //Constants provided by the host, which are passed to execute_hint along with the program's
+    //constants. When both define a constant with the same name, the override wins.
+    fn constant_overrides(&self) -> Option<&HashMap<String, Felt>> {
+        None
+    }
+
     //Transforms hint data outputed by the VM into whichever format will be later used by execute_hint
     fn compile_hint(
         &self,
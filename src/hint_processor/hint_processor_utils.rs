@@ -145,6 +145,20 @@ pub fn felt_to_u32(felt: &Felt) -> Result<u32, MathError> {
         .ok_or_else(|| MathError::FeltToU32Conversion(felt.clone()))
 }
 
+///Tries to convert a Felt value to u128
+pub fn felt_to_u128(felt: &Felt) -> Result<u128, MathError> {
+    felt.to_biguint()
+        .to_u128()
+        .ok_or_else(|| MathError::FeltToU128Conversion(felt.clone()))
+}
+
+///Tries to convert a Felt value to i128, taking the values above PRIME / 2 as negative
+pub fn felt_to_i128(felt: &Felt) -> Result<i128, MathError> {
+    felt.to_bigint()
+        .to_i128()
+        .ok_or_else(|| MathError::FeltToI128Conversion(felt.clone()))
+}
+
 fn get_offset_value_reference(
     vm: &VirtualMachine,
     hint_reference: &HintReference,
@@ -188,6 +202,7 @@ mod tests {
         },
     };
     use assert_matches::assert_matches;
+    use num_traits::One;
 
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::*;
@@ -361,4 +376,39 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_to_u128_boundary() {
+        assert_eq!(felt_to_u128(&Felt::new(u128::MAX)), Ok(u128::MAX));
+        let two_pow_128 = Felt::new(u128::MAX) + 1_u32;
+        assert_eq!(
+            felt_to_u128(&two_pow_128),
+            Err(MathError::FeltToU128Conversion(two_pow_128.clone()))
+        );
+        assert_eq!(
+            felt_to_u128(&-Felt::one()),
+            Err(MathError::FeltToU128Conversion(-Felt::one()))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_to_i128_boundary() {
+        assert_eq!(felt_to_i128(&Felt::new(i128::MAX)), Ok(i128::MAX));
+        assert_eq!(felt_to_i128(&Felt::new(i128::MIN)), Ok(i128::MIN));
+        assert_eq!(felt_to_i128(&-Felt::one()), Ok(-1));
+        let two_pow_127 = Felt::new(i128::MAX) + 1_u32;
+        assert_eq!(
+            felt_to_i128(&two_pow_127),
+            Err(MathError::FeltToI128Conversion(two_pow_127.clone()))
+        );
+        let minus_two_pow_127_minus_one = Felt::new(i128::MIN) - 1_u32;
+        assert_eq!(
+            felt_to_i128(&minus_two_pow_127_minus_one),
+            Err(MathError::FeltToI128Conversion(
+                minus_two_pow_127_minus_one.clone()
+            ))
+        );
+    }
 }
@@ -3,6 +3,11 @@
 //! # Feature Flags
 //! - `skip_next_instruction_hint`: Enable the `skip_next_instruction()` hint. Not enabled by default.
 //! - `hooks`: Enable [Hooks](vm::hooks) support for the [VirtualMachine](vm::vm_core::VirtualMachine). Not enabled by default.
+//! - `memory_access_log`: Record every memory read and write performed during a run, see [access_log](vm::vm_core::VirtualMachine::access_log). Not enabled by default.
+//! - `step_sampling`: Enable [step sampling](vm::step_sampling) to benchmark the [VirtualMachine](vm::vm_core::VirtualMachine) without a wall clock (e.g. on `no_std` targets). Not enabled by default.
+//! - `deduction_profiling`: Record the number of calls to, and the time spent in, each builtin's memory cell deduction, see [deduction profiling](vm::deduction_profiling). Not enabled by default.
+//! - `hint_witness`: Allow recording the effects of the hints run by the [VirtualMachine](vm::vm_core::VirtualMachine), and replaying them instead of running the hints, see [hint witness](vm::hint_witness). Not enabled by default.
+//! - `hint_coverage`: Record the code of every hint executed by a [CairoRunner](vm::runners::cairo_runner::CairoRunner), see [get_executed_hints](vm::runners::cairo_runner::CairoRunner::get_executed_hints). Not enabled by default.
 //! - `with_mimalloc`: Use [MiMalloc](https://crates.io/crates/mimalloc) as the program global allocator.
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
@@ -53,6 +58,8 @@ mod stdlib {
     pub use crate::without_std::*;
 }
 
+pub mod air_private_input;
+pub mod air_public_input;
 pub mod cairo_run;
 pub mod hint_processor;
 pub mod math_utils;
@@ -1,9 +1,13 @@
-use crate::stdlib::ops::Shr;
+use crate::stdlib::{ops::Shr, prelude::*};
 use crate::types::errors::math_errors::MathError;
 use felt::Felt;
 use num_bigint::{BigInt, BigUint};
 use num_integer::Integer;
 use num_traits::{One, Signed, Zero};
+use starknet_crypto::{poseidon_permute_comp, FieldElement};
+
+/// The `alpha` coefficient of the STARK curve, y^2 = x^3 + alpha * x + beta
+pub(crate) const STARK_CURVE_ALPHA: u64 = 1;
 
 ///Returns the integer square root of the nonnegative integer n.
 ///This is the floor of the exact square root of n.
@@ -79,6 +83,26 @@ pub fn safe_div_usize(x: usize, y: usize) -> Result<usize, MathError> {
     Ok(q)
 }
 
+/// Performs integer division between x and y, rounding up; fails if y is zero.
+pub fn div_ceil(x: usize, y: usize) -> Result<usize, MathError> {
+    if y.is_zero() {
+        return Err(MathError::DividedByZero);
+    }
+    Ok(Integer::div_ceil(&x, &y))
+}
+
+/// Returns the additive inverse of x in the field, ie: `-x mod p`.
+/// The result is always in the range [0, p), so the negation of 0 is 0.
+pub fn felt_neg(x: &Felt) -> Felt {
+    -x
+}
+
+/// Returns `(a - b) mod p`, wrapping around the prime when b > a.
+/// The result is always in the range [0, p), so `felt_sub(0, 1)` is `p - 1`.
+pub fn felt_sub(a: &Felt, b: &Felt) -> Felt {
+    a.clone() - b
+}
+
 ///Returns x, y, g such that g = x*a + y*b = gcd(a, b).
 fn igcdex(num_a: &BigInt, num_b: &BigInt) -> (BigInt, BigInt, BigInt) {
     match (num_a, num_b) {
@@ -104,6 +128,81 @@ fn igcdex(num_a: &BigInt, num_b: &BigInt) -> (BigInt, BigInt, BigInt) {
     }
 }
 
+///Returns true if value is a quadratic residue modulo the field prime (zero included),
+///using Euler's criterion.
+pub fn is_quad_residue(value: &Felt) -> bool {
+    let prime = Felt::prime();
+    value.is_zero()
+        || value
+            .to_biguint()
+            .modpow(&((&prime - 1_u32) >> 1_u32), &prime)
+            .is_one()
+}
+
+///Finds the minimum nonnegative integer x such that (x * x) % p == a % p using the
+///Tonelli-Shanks algorithm, or returns None if a is not a quadratic residue modulo p.
+///p must be a prime no larger than the field prime, so that the root fits in a Felt. None is
+///also returned when the search fails because p isn't prime.
+pub fn sqrt_prime_power(a: &Felt, p: &BigUint) -> Option<Felt> {
+    let a = a.to_biguint() % p;
+    if a.is_zero() || p == &BigUint::from(2_u32) {
+        return Some(Felt::from(a));
+    }
+    let one = BigUint::one();
+    let p_minus_one = p - &one;
+    let euler_exponent = &p_minus_one >> 1_u32;
+    if a.modpow(&euler_exponent, p) != one {
+        return None;
+    }
+
+    // p - 1 = q * 2^s, with q odd
+    let s = p_minus_one.trailing_zeros()?;
+    let q = &p_minus_one >> s;
+    // Any quadratic non-residue works, a prime p always has one
+    let mut z = BigUint::from(2_u32);
+    while z.modpow(&euler_exponent, p) != p_minus_one {
+        z += 1_u32;
+        if &z >= p {
+            return None;
+        }
+    }
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut root = a.modpow(&((&q + 1_u32) >> 1_u32), p);
+    while t != one {
+        // Find the least i such that t^(2^i) == 1
+        let mut i = 0_u64;
+        let mut t_pow = t.clone();
+        while t_pow != one {
+            t_pow = &t_pow * &t_pow % p;
+            i += 1;
+            // Only reachable if p isn't prime
+            if i >= m {
+                return None;
+            }
+        }
+        let b = c.modpow(&(BigUint::one() << (m - i - 1)), p);
+        m = i;
+        c = &b * &b % p;
+        t = t * &c % p;
+        root = root * b % p;
+    }
+    let other_root = p - &root;
+    Some(Felt::from(root.min(other_root)))
+}
+
+///Returns the signed representation of value, in the range (-prime/2, prime/2].
+pub fn as_int(value: &Felt, prime: &BigInt) -> BigInt {
+    let value = BigInt::from(value.to_biguint());
+    if value > prime.shr(1_u32) {
+        value - prime
+    } else {
+        value
+    }
+}
+
 ///Finds a nonnegative integer x < p such that (m * x) % p == n.
 pub fn div_mod(n: &BigInt, m: &BigInt, p: &BigInt) -> BigInt {
     let (a, _, c) = igcdex(m, p);
@@ -111,6 +210,15 @@ pub fn div_mod(n: &BigInt, m: &BigInt, p: &BigInt) -> BigInt {
     (n * a).mod_floor(p)
 }
 
+///Finds the felt x such that (b * x) % PRIME == a, multiplying a by the modular inverse of b.
+///Unlike safe_div, a doesn't need to be divisible by b over the integers.
+pub fn div_mod_felt(a: &Felt, b: &Felt) -> Result<Felt, MathError> {
+    if b.is_zero() {
+        return Err(MathError::DividedByZero);
+    }
+    Ok(a / b)
+}
+
 pub fn ec_add(
     point_a: (BigInt, BigInt),
     point_b: (BigInt, BigInt),
@@ -157,17 +265,258 @@ pub fn ec_double_slope(point: &(BigInt, BigInt), alpha: &BigInt, prime: &BigInt)
     )
 }
 
+/// Adds two distinct points on the STARK curve, given in affine form (x, y).
+/// Fails if the points share their x coordinate, as the sum is then either the point at
+/// infinity or a doubling.
+pub fn ec_add_felt(p: (Felt, Felt), q: (Felt, Felt)) -> Result<(Felt, Felt), MathError> {
+    if p.0 == q.0 {
+        return Err(if p.1 == q.1 {
+            MathError::EcAddSameX(p.0)
+        } else {
+            MathError::EcPointAtInfinity
+        });
+    }
+    let m = (&q.1 - &p.1) / (&q.0 - &p.0);
+    let x = &m * &m - &p.0 - &q.0;
+    let y = m * (p.0 - &x) - p.1;
+    Ok((x, y))
+}
+
+/// Doubles a point on the STARK curve, given in affine form (x, y).
+/// Fails if y is zero, as the result is then the point at infinity.
+pub fn ec_double_felt(p: (Felt, Felt)) -> Result<(Felt, Felt), MathError> {
+    if p.1.is_zero() {
+        return Err(MathError::EcPointAtInfinity);
+    }
+    let alpha = Felt::from(STARK_CURVE_ALPHA);
+    let m = (Felt::from(3_u32) * &p.0 * &p.0 + alpha) / (Felt::from(2_u32) * &p.1);
+    let x = &m * &m - Felt::from(2_u32) * &p.0;
+    let y = m * (p.0 - &x) - p.1;
+    Ok((x, y))
+}
+
+///Computes the pedersen hash of two field elements, as done by the pedersen builtin
+pub fn pedersen_hash(a: &Felt, b: &Felt) -> Felt {
+    field_element_to_felt(&starknet_crypto::pedersen_hash(
+        &felt_to_field_element(a),
+        &felt_to_field_element(b),
+    ))
+}
+
+///Computes the pedersen hash of an array as done by StarkNet (compute_hash_on_elements):
+///h(h(h(h(0, data[0]), data[1]), ...), len(data))
+pub fn pedersen_hash_chain(data: &[Felt]) -> Felt {
+    let hash = data.iter().fold(FieldElement::ZERO, |acc, element| {
+        starknet_crypto::pedersen_hash(&acc, &felt_to_field_element(element))
+    });
+    field_element_to_felt(&starknet_crypto::pedersen_hash(
+        &hash,
+        &FieldElement::from(data.len()),
+    ))
+}
+
+///Computes the poseidon hash of an array as done by StarkNet, using a sponge with a rate of 2.
+///The input is padded with a 1 (and a 0 if needed) to fill the last chunk.
+pub fn poseidon_hash_many(data: &[Felt]) -> Felt {
+    let mut state = [FieldElement::ZERO; 3];
+    let mut chunks = data.chunks_exact(2);
+    for chunk in chunks.by_ref() {
+        state[0] += felt_to_field_element(&chunk[0]);
+        state[1] += felt_to_field_element(&chunk[1]);
+        poseidon_permute_comp(&mut state);
+    }
+    match chunks.remainder() {
+        [last] => {
+            state[0] += felt_to_field_element(last);
+            state[1] += FieldElement::ONE;
+        }
+        _ => state[0] += FieldElement::ONE,
+    }
+    poseidon_permute_comp(&mut state);
+    field_element_to_felt(&state[0])
+}
+
+///Prepends n_zeros zero words to values, as done with the keccak builtin's input
+pub fn left_pad_u64(values: &[u64], n_zeros: usize) -> Vec<u64> {
+    let mut res: Vec<u64> = vec![0; n_zeros];
+    res.extend(values.iter());
+
+    res
+}
+
+fn felt_to_field_element(felt: &Felt) -> FieldElement {
+    let felt_bytes = felt.to_bytes_be();
+    let mut bytes = [0_u8; 32];
+    bytes[32 - felt_bytes.len()..].copy_from_slice(&felt_bytes);
+    FieldElement::from_bytes_be(&bytes).expect("Felt values are always below the field prime")
+}
+
+fn field_element_to_felt(field_element: &FieldElement) -> Felt {
+    Felt::from_bytes_be(&field_element.to_bytes_be())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::utils::test_utils::*;
     use crate::utils::CAIRO_PRIME;
+    use crate::{
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        types::program::Program,
+        vm::{runners::cairo_runner::CairoRunner, vm_core::VirtualMachine},
+    };
     use assert_matches::assert_matches;
+    use felt::felt_str;
     use num_traits::Num;
 
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::*;
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_neg_zero() {
+        assert_eq!(felt_neg(&Felt::zero()), Felt::zero());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_neg_wraps_around() {
+        let p_minus_one = Felt::zero() - &Felt::one();
+        assert_eq!(p_minus_one.to_biguint(), CAIRO_PRIME.clone() - 1_u32);
+        assert_eq!(felt_neg(&Felt::one()), p_minus_one);
+        assert_eq!(felt_neg(&p_minus_one), Felt::one());
+        assert_eq!(felt_neg(&felt_neg(&Felt::new(12345))), Felt::new(12345));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_sub_zero_minus_one() {
+        assert_eq!(
+            felt_sub(&Felt::zero(), &Felt::one()).to_biguint(),
+            CAIRO_PRIME.clone() - 1_u32
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_sub_wraps_around() {
+        assert_eq!(felt_sub(&Felt::new(7), &Felt::new(5)), Felt::new(2));
+        // 5 - 7 = p - 2
+        assert_eq!(
+            felt_sub(&Felt::new(5), &Felt::new(7)),
+            felt_neg(&Felt::new(2))
+        );
+        // 1 - (p - 1) = 2 - p = 2 (mod p)
+        assert_eq!(
+            felt_sub(&Felt::one(), &felt_neg(&Felt::one())),
+            Felt::new(2)
+        );
+        assert_eq!(felt_sub(&Felt::new(9), &Felt::new(9)), Felt::zero());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn pedersen_hash_of_two_elements() {
+        assert_eq!(
+            super::pedersen_hash(&Felt::new(32), &Felt::new(72)),
+            felt_str!(
+                "3270867057177188607814717243084834301278723532952411121381966378910183338911"
+            )
+        );
+        // h(0, 0)
+        assert_eq!(
+            super::pedersen_hash(&Felt::zero(), &Felt::zero()),
+            felt_str!(
+                "49ee3eba8c1600700ee1b87eb599f16716b0b1022947733551fde4050ca6804",
+                16
+            )
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn pedersen_hash_chain_empty() {
+        // h(0, 0)
+        assert_eq!(
+            pedersen_hash_chain(&[]),
+            felt_str!(
+                "49ee3eba8c1600700ee1b87eb599f16716b0b1022947733551fde4050ca6804",
+                16
+            )
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn hash_chains_match_cairo_lang() {
+        // The program outputs the hashes of the prefixes of [1, 2, 3] computed by cairo-lang's
+        // hash_felts and poseidon_hash_many
+        let program = Program::from_bytes(
+            include_bytes!("../cairo_programs/hash_chains.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+
+        let data = [Felt::new(1), Felt::new(2), Felt::new(3)];
+        let expected: Vec<Felt> = (0..=data.len())
+            .map(|len| pedersen_hash_chain(&data[..len]))
+            .chain((0..=data.len()).map(|len| poseidon_hash_many(&data[..len])))
+            .collect();
+        assert_eq!(cairo_runner.get_output_as_felts(&vm), Ok(expected));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn poseidon_hash_many_multiple_elements() {
+        // Same vector as cairo_programs/poseidon_hash.cairo
+        let data = [
+            felt_str!("84175983715088675913672849362079546"),
+            felt_str!("9384720329467203286234076408512594689579283578028960384690"),
+            felt_str!("291883989128409324823849293040390493094093"),
+            felt_str!("5849589438543859348593485948598349584395839402940940290490324"),
+            felt_str!("1836254780028456372728992049476335424263474849"),
+        ];
+        assert_eq!(
+            poseidon_hash_many(&data),
+            felt_str!("47102513329160951064697157194713013753695317629154835326726810042406974264")
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn as_int_zero() {
+        let prime = BigInt::from(CAIRO_PRIME.clone());
+        assert_eq!(as_int(&Felt::zero(), &prime), BigInt::zero());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn as_int_prime_minus_one() {
+        let prime = BigInt::from(CAIRO_PRIME.clone());
+        assert_eq!(
+            as_int(&(Felt::zero() - Felt::one()), &prime),
+            -BigInt::one()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn as_int_midpoint() {
+        let prime = BigInt::from(CAIRO_PRIME.clone());
+        let half_prime = prime.clone().shr(1_u32);
+        assert_eq!(as_int(&Felt::new(half_prime.clone()), &prime), half_prime);
+        assert_eq!(
+            as_int(&(Felt::new(half_prime.clone()) + Felt::one()), &prime),
+            -half_prime
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn calculate_divmod_a() {
@@ -261,6 +610,35 @@ mod tests {
         assert_matches!(result, Err(MathError::DividedByZero));
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn compute_div_mod_felt_exact() {
+        let x = Felt::new(26);
+        let y = Felt::new(13);
+        assert_eq!(div_mod_felt(&x, &y), Ok(Felt::new(2)));
+        assert_eq!(div_mod_felt(&x, &y), safe_div(&x, &y));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn compute_div_mod_felt_non_divisor() {
+        let x = Felt::new(25);
+        let y = Felt::new(4);
+        // 4 * (3 * PRIME + 25) / 4 = 25 (mod PRIME)
+        let expected = Felt::from((Felt::prime() * 3_u32 + 25_u32) / 4_u32);
+        assert_eq!(div_mod_felt(&x, &y), Ok(expected.clone()));
+        assert_eq!(expected * y, x);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn compute_div_mod_felt_by_zero() {
+        assert_eq!(
+            div_mod_felt(&Felt::new(25), &Felt::zero()),
+            Err(MathError::DividedByZero)
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn compute_safe_div_usize() {
@@ -282,6 +660,27 @@ mod tests {
         assert_matches!(safe_div_usize(25, 0), Err(MathError::DividedByZero));
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn compute_div_ceil_exact() {
+        assert_eq!(div_ceil(26, 13), Ok(2));
+        assert_eq!(div_ceil(0, 13), Ok(0));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn compute_div_ceil_inexact() {
+        assert_eq!(div_ceil(27, 13), Ok(3));
+        assert_eq!(div_ceil(1, 13), Ok(1));
+        assert_eq!(div_ceil(usize::MAX, 2), Ok(usize::MAX / 2 + 1));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn compute_div_ceil_by_zero() {
+        assert_eq!(div_ceil(25, 0), Err(MathError::DividedByZero));
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn compute_line_slope_for_valid_points() {
@@ -529,6 +928,102 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn ec_add_felt_points() {
+        let p = (
+            felt_str!(
+                "1183418161532233795704555250127335895546712857142554564893196731153957537489"
+            ),
+            felt_str!(
+                "1938007580204102038458825306058547644691739966277761828724036384003180924526"
+            ),
+        );
+        let q = (
+            felt_str!(
+                "1977703130303461992863803129734853218488251484396280000763960303272760326570"
+            ),
+            felt_str!(
+                "2565191853811572867032277464238286011368568368717965689023024980325333517459"
+            ),
+        );
+        let expected = (
+            felt_str!(
+                "1977874238339000383330315148209250828062304908491266318460063803060754089297"
+            ),
+            felt_str!(
+                "2969386888251099938335087541720168257053975603483053253007176033556822156706"
+            ),
+        );
+        assert_eq!(ec_add_felt(p.clone(), q.clone()), Ok(expected.clone()));
+        assert_eq!(ec_add_felt(q, p), Ok(expected));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn ec_add_felt_same_point() {
+        let p = (
+            felt_str!(
+                "634630432210960355305430036410971013200846091773294855689580772209984122075"
+            ),
+            felt_str!(
+                "904896178444785983993402854911777165629036333948799414977736331868834995209"
+            ),
+        );
+        assert_eq!(
+            ec_add_felt(p.clone(), p.clone()),
+            Err(MathError::EcAddSameX(p.0))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn ec_add_felt_opposite_points() {
+        let p = (
+            felt_str!(
+                "634630432210960355305430036410971013200846091773294855689580772209984122075"
+            ),
+            felt_str!(
+                "904896178444785983993402854911777165629036333948799414977736331868834995209"
+            ),
+        );
+        let minus_p = (p.0.clone(), -p.1.clone());
+        assert_eq!(ec_add_felt(p, minus_p), Err(MathError::EcPointAtInfinity));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn ec_double_felt_point() {
+        let p = (
+            felt_str!(
+                "634630432210960355305430036410971013200846091773294855689580772209984122075"
+            ),
+            felt_str!(
+                "904896178444785983993402854911777165629036333948799414977736331868834995209"
+            ),
+        );
+        assert_eq!(
+            ec_double_felt(p),
+            Ok((
+                felt_str!(
+                    "3143372541908290873737380228370996772020829254218248561772745122290262847573"
+                ),
+                felt_str!(
+                    "1721586982687138486000069852568887984211460575851774005637537867145702861131"
+                )
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn ec_double_felt_point_with_zero_y() {
+        assert_eq!(
+            ec_double_felt((Felt::one(), Felt::zero())),
+            Err(MathError::EcPointAtInfinity)
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn calculate_isqrt_a() {
@@ -565,4 +1060,79 @@ mod tests {
         let y = BigInt::zero();
         assert_matches!(safe_div_bigint(&x, &y), Err(MathError::DividedByZero))
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn left_pad_u64_prepends_zeros() {
+        assert_eq!(left_pad_u64(&[1, 2], 3), vec![0, 0, 0, 1, 2]);
+        assert_eq!(left_pad_u64(&[1, 2], 0), vec![1, 2]);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn is_quad_residue_felt() {
+        assert!(is_quad_residue(&Felt::zero()));
+        assert!(is_quad_residue(&Felt::one()));
+        assert!(is_quad_residue(&Felt::new(5)));
+        assert!(!is_quad_residue(&Felt::new(3)));
+        assert!(!is_quad_residue(&Felt::new(6)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sqrt_prime_power_residue() {
+        let root = sqrt_prime_power(&Felt::new(5), &CAIRO_PRIME).unwrap();
+        assert_eq!(
+            root,
+            felt_str!(
+                "703125680814918687568381966029303999302590701505467922907419583337579557417"
+            )
+        );
+        assert_eq!(&root * &root, Felt::new(5));
+
+        assert_eq!(
+            sqrt_prime_power(&Felt::new(16), &CAIRO_PRIME),
+            Some(Felt::new(4))
+        );
+        // The smallest of both roots is returned
+        assert_eq!(
+            sqrt_prime_power(&Felt::new(10), &BigUint::from(13_u32)),
+            Some(Felt::new(6))
+        );
+        assert_eq!(
+            sqrt_prime_power(&Felt::new(16), &BigUint::from(13_u32)),
+            Some(Felt::new(4))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sqrt_prime_power_non_residue() {
+        assert_eq!(sqrt_prime_power(&Felt::new(3), &CAIRO_PRIME), None);
+        assert_eq!(
+            sqrt_prime_power(&Felt::new(5), &BigUint::from(13_u32)),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sqrt_prime_power_composite_modulus() {
+        // 1 passes the Euler criterion modulo 21, which has no element z with z^10 == -1
+        assert_eq!(sqrt_prime_power(&Felt::one(), &BigUint::from(21_u32)), None);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sqrt_prime_power_zero() {
+        assert_eq!(
+            sqrt_prime_power(&Felt::zero(), &CAIRO_PRIME),
+            Some(Felt::zero())
+        );
+        // a is reduced modulo p
+        assert_eq!(
+            sqrt_prime_power(&Felt::new(13), &BigUint::from(13_u32)),
+            Some(Felt::zero())
+        );
+    }
 }
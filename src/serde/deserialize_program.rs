@@ -8,13 +8,16 @@ use crate::{
     },
     vm::runners::builtin_runner::{
         BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
-        OUTPUT_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME,
-        SIGNATURE_BUILTIN_NAME,
+        OUTPUT_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_96_BUILTIN_NAME,
+        RANGE_CHECK_BUILTIN_NAME, SEGMENT_ARENA_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
     },
 };
 use felt::{Felt, PRIME_STR};
 use num_traits::Num;
-use serde::{de, de::MapAccess, de::SeqAccess, Deserialize, Deserializer, Serialize};
+use serde::{
+    de, de::MapAccess, de::SeqAccess, ser, ser::SerializeSeq, Deserialize, Deserializer, Serialize,
+    Serializer,
+};
 use serde_json::Number;
 
 // This enum is used to deserialize program builtins into &str and catch non-valid names
@@ -29,6 +32,8 @@ pub enum BuiltinName {
     bitwise,
     ec_op,
     poseidon,
+    range_check96,
+    segment_arena,
 }
 
 impl BuiltinName {
@@ -42,6 +47,8 @@ impl BuiltinName {
             BuiltinName::bitwise => BITWISE_BUILTIN_NAME,
             BuiltinName::ec_op => EC_OP_BUILTIN_NAME,
             BuiltinName::poseidon => POSEIDON_BUILTIN_NAME,
+            BuiltinName::range_check96 => RANGE_CHECK_96_BUILTIN_NAME,
+            BuiltinName::segment_arena => SEGMENT_ARENA_BUILTIN_NAME,
         }
     }
 }
@@ -59,6 +66,74 @@ pub struct ProgramJson {
     pub debug_info: Option<DebugInfo>,
 }
 
+/// Borrowed counterpart of [ProgramJson], used to serialize a [Program] back into the format
+/// it was parsed from
+#[derive(Serialize)]
+pub(crate) struct ProgramJsonRef<'a> {
+    pub prime: &'a str,
+    pub builtins: &'a [&'static str],
+    #[serde(serialize_with = "serialize_array_of_bigint_hex")]
+    pub data: &'a [MaybeRelocatable],
+    pub identifiers: &'a HashMap<String, Identifier>,
+    pub hints: &'a HashMap<usize, Vec<HintParams>>,
+    pub reference_manager: &'a ReferenceManager,
+    pub attributes: &'a [Attribute],
+    pub debug_info: Option<DebugInfoRef<'a>>,
+}
+
+impl<'a> From<&'a Program> for ProgramJsonRef<'a> {
+    fn from(program: &'a Program) -> Self {
+        ProgramJsonRef {
+            prime: &program.prime,
+            builtins: &program.builtins,
+            data: &program.data,
+            identifiers: &program.identifiers,
+            hints: &program.hints,
+            reference_manager: &program.reference_manager,
+            attributes: &program.error_message_attributes,
+            debug_info: program
+                .instruction_locations
+                .as_ref()
+                .map(|instruction_locations| DebugInfoRef {
+                    instruction_locations,
+                }),
+        }
+    }
+}
+
+/// Cairo 1 program, compiled from Sierra to CASM
+#[derive(Deserialize, Debug)]
+pub struct CasmProgramJson {
+    pub prime: String,
+    #[serde(deserialize_with = "deserialize_array_of_bigint_hex")]
+    pub bytecode: Vec<MaybeRelocatable>,
+    // Hints of each pc, in the compiler's structured format
+    #[serde(default)]
+    pub hints: Vec<(usize, Vec<serde_json::Value>)>,
+    // The same hints, as Python code
+    #[serde(default)]
+    pub pythonic_hints: Option<Vec<(usize, Vec<String>)>>,
+    pub entry_points_by_type: CasmEntryPointsByType,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CasmEntryPointsByType {
+    #[serde(rename = "EXTERNAL", default)]
+    pub external: Vec<CasmEntryPoint>,
+    #[serde(rename = "L1_HANDLER", default)]
+    pub l1_handler: Vec<CasmEntryPoint>,
+    #[serde(rename = "CONSTRUCTOR", default)]
+    pub constructor: Vec<CasmEntryPoint>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CasmEntryPoint {
+    #[serde(deserialize_with = "deserialize_felt_hex")]
+    pub selector: Felt,
+    pub offset: usize,
+    pub builtins: Vec<BuiltinName>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct HintParams {
     pub code: String,
@@ -97,10 +172,12 @@ impl Default for ApTracking {
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct Identifier {
     pub pc: Option<usize>,
-    #[serde(rename(deserialize = "type"))]
+    #[serde(rename = "type")]
     pub type_: Option<String>,
     #[serde(default)]
     #[serde(deserialize_with = "felt_from_number")]
+    #[serde(serialize_with = "felt_to_number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub value: Option<Felt>,
 
     pub full_name: Option<String>,
@@ -138,6 +215,11 @@ pub struct DebugInfo {
     instruction_locations: HashMap<usize, InstructionLocation>,
 }
 
+#[derive(Serialize)]
+pub(crate) struct DebugInfoRef<'a> {
+    instruction_locations: &'a HashMap<usize, InstructionLocation>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct InstructionLocation {
     pub inst: Location,
@@ -163,6 +245,20 @@ where
     Ok(Felt::parse_bytes(n.to_string().as_bytes(), 10))
 }
 
+fn felt_to_number<S>(value: &Option<Felt>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => value
+            .to_str_radix(10)
+            .parse::<Number>()
+            .map_err(ser::Error::custom)?
+            .serialize(serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct ReferenceManager {
     pub references: Vec<Reference>,
@@ -173,7 +269,7 @@ pub struct Reference {
     pub ap_tracking_data: ApTracking,
     pub pc: Option<usize>,
     #[serde(deserialize_with = "deserialize_value_address")]
-    #[serde(rename(deserialize = "value"))]
+    #[serde(rename = "value")]
     pub value_address: ValueAddress,
 }
 
@@ -309,6 +405,14 @@ impl<'de> de::Visitor<'de> for ValueAddressVisitor {
 
         Ok(ValueAddress::no_hint_reference_default())
     }
+
+    // Serialized programs keep the parsed address, as the original string isn't stored
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        ValueAddress::deserialize(de::value::MapAccessDeserializer::new(map))
+    }
 }
 
 pub fn deserialize_felt_hex<'de, D: Deserializer<'de>>(d: D) -> Result<Felt, D::Error> {
@@ -321,6 +425,26 @@ pub fn deserialize_array_of_bigint_hex<'de, D: Deserializer<'de>>(
     d.deserialize_seq(MaybeRelocatableVisitor)
 }
 
+fn serialize_array_of_bigint_hex<S: Serializer>(
+    data: &[MaybeRelocatable],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(data.len()))?;
+    for value in data {
+        match value {
+            MaybeRelocatable::Int(value) => {
+                seq.serialize_element(&format!("0x{}", value.to_str_radix(16)))?
+            }
+            MaybeRelocatable::RelocatableValue(value) => {
+                return Err(ser::Error::custom(format!(
+                    "relocatable value {value} in program data"
+                )))
+            }
+        }
+    }
+    seq.end()
+}
+
 pub fn deserialize_map_to_string_and_usize_hashmap<'de, D: Deserializer<'de>>(
     d: D,
 ) -> Result<HashMap<String, usize>, D::Error> {
@@ -330,7 +454,7 @@ pub fn deserialize_map_to_string_and_usize_hashmap<'de, D: Deserializer<'de>>(
 pub fn deserialize_value_address<'de, D: Deserializer<'de>>(
     d: D,
 ) -> Result<ValueAddress, D::Error> {
-    d.deserialize_str(ValueAddressVisitor)
+    d.deserialize_any(ValueAddressVisitor)
 }
 
 pub fn deserialize_program_json(reader: &[u8]) -> Result<ProgramJson, ProgramError> {
@@ -345,6 +469,26 @@ pub fn deserialize_and_parse_program(
     parse_program_json(program_json, entrypoint)
 }
 
+pub fn deserialize_and_parse_casm_program(
+    reader: &[u8],
+    entrypoint_selector: Option<&Felt>,
+) -> Result<Program, ProgramError> {
+    let casm_json: CasmProgramJson = serde_json::from_slice(reader)?;
+    parse_casm_program_json(casm_json, entrypoint_selector)
+}
+
+/// Returns the names of the functions of the main scope, which can be used as entrypoints,
+/// in alphabetical order
+pub(crate) fn available_entrypoints(identifiers: &HashMap<String, Identifier>) -> Vec<String> {
+    let mut entrypoints: Vec<String> = identifiers
+        .iter()
+        .filter(|(_, identifier)| identifier.type_.as_deref() == Some("function"))
+        .filter_map(|(name, _)| name.strip_prefix("__main__.").map(String::from))
+        .collect();
+    entrypoints.sort();
+    entrypoints
+}
+
 pub fn parse_program_json(
     program_json: ProgramJson,
     entrypoint: Option<&str>,
@@ -359,7 +503,12 @@ pub fn parse_program_json(
             .get(&format!("__main__.{entrypoint}"))
         {
             Some(entrypoint_identifier) => entrypoint_identifier.pc,
-            None => return Err(ProgramError::EntrypointNotFound(entrypoint.to_string())),
+            None => {
+                return Err(ProgramError::EntrypointNotFound(
+                    entrypoint.to_string(),
+                    available_entrypoints(&program_json.identifiers),
+                ))
+            }
         },
         None => None,
     };
@@ -412,6 +561,87 @@ pub fn parse_program_json(
     })
 }
 
+/// Builds a [Program] out of a Cairo 1 CASM program. The program's main and builtins are those of
+/// the entry point with the given selector.
+/// Hints are loaded from their Python code (`pythonic_hints`), as hint processors don't run the
+/// structured form of CASM hints: a hint without Python code fails with
+/// [ProgramError::MissingPythonicHint].
+pub fn parse_casm_program_json(
+    casm_json: CasmProgramJson,
+    entrypoint_selector: Option<&Felt>,
+) -> Result<Program, ProgramError> {
+    if PRIME_STR != casm_json.prime {
+        return Err(ProgramError::PrimeDiffers(casm_json.prime));
+    }
+
+    let (main, builtins) = match entrypoint_selector {
+        Some(selector) => {
+            let entry_points = casm_json
+                .entry_points_by_type
+                .external
+                .iter()
+                .chain(casm_json.entry_points_by_type.l1_handler.iter())
+                .chain(casm_json.entry_points_by_type.constructor.iter());
+            let entry_point = entry_points
+                .clone()
+                .find(|entry_point| &entry_point.selector == selector)
+                .ok_or_else(|| {
+                    ProgramError::EntrypointNotFound(
+                        format!("0x{}", selector.to_str_radix(16)),
+                        entry_points
+                            .map(|entry_point| {
+                                format!("0x{}", entry_point.selector.to_str_radix(16))
+                            })
+                            .collect(),
+                    )
+                })?;
+            (
+                Some(entry_point.offset),
+                entry_point.builtins.iter().map(BuiltinName::name).collect(),
+            )
+        }
+        None => (None, Vec::new()),
+    };
+
+    let pythonic_hints: HashMap<usize, Vec<String>> = casm_json
+        .pythonic_hints
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let hints = casm_json
+        .hints
+        .into_iter()
+        .map(|(pc, pc_hints)| -> Result<_, ProgramError> {
+            let hint_params: Vec<HintParams> = (0..pc_hints.len())
+                .map(|i| {
+                    pythonic_hints
+                        .get(&pc)
+                        .and_then(|codes| codes.get(i))
+                        .cloned()
+                        .ok_or(ProgramError::MissingPythonicHint(pc))
+                        .map(|code| HintParams {
+                            code,
+                            accessible_scopes: Vec::new(),
+                            flow_tracking_data: FlowTrackingData {
+                                ap_tracking: ApTracking::new(),
+                                reference_ids: HashMap::new(),
+                            },
+                        })
+                })
+                .collect::<Result<_, _>>()?;
+            Ok((pc, hint_params))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Program {
+        builtins,
+        data: casm_json.bytecode,
+        main,
+        hints,
+        ..Default::default()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -754,7 +984,8 @@ mod tests {
         assert!(deserialization_result.is_err());
         assert_matches!(
             deserialization_result,
-            Err(ProgramError::EntrypointNotFound(_))
+            Err(ProgramError::EntrypointNotFound(name, available))
+                if name == "missing_function" && available == ["main"]
         );
     }
 
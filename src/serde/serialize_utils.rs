@@ -0,0 +1,76 @@
+use crate::stdlib::prelude::*;
+
+use felt::Felt;
+
+/// Size of the little endian encoding of a felt, as used in the memory file
+pub const FELT_BYTES: usize = 32;
+
+/// Appends the 32-byte little endian encoding of each felt to `dst`, in order.
+pub fn encode_felts_to(dst: &mut Vec<u8>, felts: &[Felt]) {
+    encode_felt_iter_to(dst, felts)
+}
+
+// Same as encode_felts_to, for felts which aren't stored contiguously (e.g. memory cells)
+pub(crate) fn encode_felt_iter_to<'a>(
+    dst: &mut Vec<u8>,
+    felts: impl IntoIterator<Item = &'a Felt>,
+) {
+    let felts = felts.into_iter();
+    dst.reserve(felts.size_hint().0 * FELT_BYTES);
+    for felt in felts {
+        let start = dst.len();
+        for digit in felt.iter_u64_digits() {
+            dst.extend_from_slice(&digit.to_le_bytes());
+        }
+        dst.resize(start + FELT_BYTES, 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::{Bounded, One, Zero};
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn encode_felts_round_trip() {
+        let felts = [
+            Felt::zero(),
+            Felt::one(),
+            Felt::new(u64::MAX),
+            Felt::new(u128::MAX),
+            Felt::max_value(),
+        ];
+        let mut buffer = vec![0xff];
+        encode_felts_to(&mut buffer, &felts);
+
+        // Existing contents are kept
+        assert_eq!(buffer[0], 0xff);
+        assert_eq!(buffer.len(), 1 + felts.len() * FELT_BYTES);
+        assert_eq!(&buffer[1..1 + FELT_BYTES], &[0; FELT_BYTES]);
+        let decoded: Vec<Felt> = buffer[1..]
+            .chunks_exact(FELT_BYTES)
+            .map(|bytes| {
+                let mut bytes = bytes.to_vec();
+                bytes.reverse();
+                Felt::from_bytes_be(&bytes)
+            })
+            .collect();
+        assert_eq!(decoded, felts);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn encode_felts_matches_signed_bytes_le() {
+        let felt = Felt::new(0x1234_5678_9abc_def0_u64) * Felt::new(u128::MAX);
+        let mut buffer = Vec::new();
+        encode_felts_to(&mut buffer, &[felt.clone()]);
+
+        let value_bytes = felt.to_signed_bytes_le();
+        assert_eq!(&buffer[..value_bytes.len()], &value_bytes[..]);
+        assert!(buffer[value_bytes.len()..].iter().all(|byte| *byte == 0));
+    }
+}
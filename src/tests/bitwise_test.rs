@@ -31,7 +31,11 @@ fn bitwise_integration_test() {
         Ok(()),
         "Execution failed"
     );
-    assert_matches!(cairo_runner.relocate(&mut vm,), Ok(()), "Execution failed");
+    assert_matches!(
+        cairo_runner.relocate(&mut vm, true),
+        Ok(()),
+        "Execution failed"
+    );
 
     let python_vm_relocated_trace: Vec<RelocatedTraceEntry> = vec![
         RelocatedTraceEntry {
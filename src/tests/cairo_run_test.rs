@@ -1,7 +1,20 @@
-use crate::stdlib::prelude::*;
+use crate::stdlib::{collections::HashMap, prelude::*, sync::Arc};
 
 use crate::cairo_run::{self, CairoRunConfig};
-use crate::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor;
+use crate::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::{
+    BuiltinHintProcessor, HintFunc,
+};
+use crate::hint_processor::hint_processor_definition::HintReference;
+use crate::serde::deserialize_program::ApTracking;
+use crate::types::{exec_scope::ExecutionScopes, program::Program, relocatable::MaybeRelocatable};
+use crate::vm::errors::{
+    cairo_run_errors::CairoRunError, hint_errors::HintError, vm_errors::VirtualMachineError,
+    vm_exception::VmException,
+};
+use crate::vm::vm_core::VirtualMachine;
+use assert_matches::assert_matches;
+use felt::Felt;
+use num_traits::{Bounded, One};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
@@ -38,6 +51,27 @@ fn array_sum() {
     .expect("Couldn't run program");
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn big_immediates() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    let file = include_bytes!("../../cairo_programs/big_immediates.json");
+    // Immediates are parsed into felts once, when the program is loaded
+    let program = Program::from_bytes(file, Some("main")).unwrap();
+    assert!(program
+        .data
+        .contains(&MaybeRelocatable::from(Felt::max_value())));
+    cairo_run::cairo_run(
+        file,
+        &CairoRunConfig {
+            layout: "all",
+            ..Default::default()
+        },
+        &mut hint_executor,
+    )
+    .expect("Couldn't run program");
+}
+
 #[test]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 fn big_struct() {
@@ -806,6 +840,89 @@ fn search_sorted_lower() {
     .expect("Couldn't run program");
 }
 
+/// Builds a hint setting the scope variable `name` to `value`, as `%{ name = value %}` would
+fn set_scope_variable_hint(name: &'static str, value: Felt) -> Arc<HintFunc> {
+    Arc::new(HintFunc(Box::new(
+        move |_: &mut VirtualMachine,
+              exec_scopes: &mut ExecutionScopes,
+              _: &HashMap<String, HintReference>,
+              _: &ApTracking,
+              _: &HashMap<String, Felt>| {
+            exec_scopes.insert_value(name, value.clone());
+            Ok(())
+        },
+    )))
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn find_element_with_index() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    hint_executor.add_hint(
+        String::from("__find_element_index = 1"),
+        set_scope_variable_hint("__find_element_index", Felt::one()),
+    );
+    let file = include_bytes!("../../cairo_programs/find_element_with_index.json");
+    cairo_run::cairo_run(
+        file,
+        &CairoRunConfig {
+            layout: "all",
+            ..Default::default()
+        },
+        &mut hint_executor,
+    )
+    .expect("Couldn't run program");
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn find_element_not_found() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    let file = include_bytes!("../../cairo_programs/bad_programs/find_element_not_found.json");
+    let res = cairo_run::cairo_run(
+        file,
+        &CairoRunConfig {
+            layout: "all",
+            ..Default::default()
+        },
+        &mut hint_executor,
+    );
+    assert_matches!(
+        res,
+        Err(CairoRunError::VmException(VmException {
+            inner_exc: VirtualMachineError::Hint(_, err),
+            ..
+        })) if matches!(*err, HintError::NoValueForKeyFindElement(ref key) if key == &Felt::new(7))
+    );
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn find_element_max_size_exceeded() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    hint_executor.add_hint(
+        String::from("__find_element_max_size = 2"),
+        set_scope_variable_hint("__find_element_max_size", Felt::new(2)),
+    );
+    let file = include_bytes!("../../cairo_programs/bad_programs/find_element_max_size.json");
+    let res = cairo_run::cairo_run(
+        file,
+        &CairoRunConfig {
+            layout: "all",
+            ..Default::default()
+        },
+        &mut hint_executor,
+    );
+    assert_matches!(
+        res,
+        Err(CairoRunError::VmException(VmException {
+            inner_exc: VirtualMachineError::Hint(_, err),
+            ..
+        })) if matches!(*err, HintError::FindElemMaxSize(ref max_size, ref n_elms)
+            if max_size == &Felt::new(2) && n_elms == &Felt::new(3))
+    );
+}
+
 #[test]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 fn usort() {
@@ -838,6 +955,22 @@ fn squash_dict() {
     .expect("Couldn't run program");
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+fn dict_read_squash() {
+    let mut hint_executor = BuiltinHintProcessor::new_empty();
+    let file = include_bytes!("../../cairo_programs/dict_read_squash.json");
+    cairo_run::cairo_run(
+        file,
+        &CairoRunConfig {
+            layout: "all",
+            ..Default::default()
+        },
+        &mut hint_executor,
+    )
+    .expect("Couldn't run program");
+}
+
 #[test]
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 fn dict_squash() {
@@ -1,3 +1,4 @@
+use crate::stdlib::prelude::*;
 use felt::Felt;
 use num_bigint::{BigInt, BigUint};
 
@@ -27,6 +28,10 @@ pub enum MathError {
     DividedByZero,
     #[error("Failed to calculate the square root of: {0})")]
     FailedToGetSqrt(BigUint),
+    #[error("The result of the EC operation is the point at infinity")]
+    EcPointAtInfinity,
+    #[error("Can't add two EC points with the same x coordinate: {0}, use ec_double_felt instead")]
+    EcAddSameX(Felt),
     // Relocatable Operations
     #[error("Cant convert felt: {0} to Relocatable")]
     FeltToRelocatable(Felt),
@@ -55,4 +60,24 @@ pub enum MathError {
     FeltToUsizeConversion(Felt),
     #[error("Conversion to u64 failed for Felt {0}")]
     FeltToU64Conversion(Felt),
+    #[error("Conversion to u128 failed for Felt {0}")]
+    FeltToU128Conversion(Felt),
+    #[error("Conversion to i128 failed for Felt {0}")]
+    FeltToI128Conversion(Felt),
+    #[error("Conversion to bool failed for {0}, expected 0 or 1")]
+    MaybeRelocatableToBoolConversion(MaybeRelocatable),
+    #[error("Conversion to u128 failed for {0}")]
+    MaybeRelocatableToU128Conversion(MaybeRelocatable),
+    #[error("Conversion to i128 failed for {0}")]
+    MaybeRelocatableToI128Conversion(MaybeRelocatable),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FeltParseError {
+    #[error("Expected a hex felt, got an empty string: \"{0}\"")]
+    Empty(String),
+    #[error("Invalid hex character '{1}' at position {2} in \"{0}\"")]
+    InvalidHexChar(String, char, usize),
+    #[error("Hex value \"{0}\" is not lower than the field prime")]
+    OutOfRange(String),
 }
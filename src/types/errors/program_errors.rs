@@ -14,12 +14,22 @@ pub enum ProgramError {
     IO(#[from] std::io::Error),
     #[error(transparent)]
     Parse(#[from] serde_json::Error),
-    #[error("Entrypoint {0} not found")]
-    EntrypointNotFound(String),
+    #[error("Entrypoint {0} not found, available entrypoints: {entrypoints}", entrypoints = .1.join(", "))]
+    EntrypointNotFound(String, Vec<String>),
     #[error("Constant {0} has no value")]
     ConstWithoutValue(String),
     #[error("Expected prime {PRIME_STR}, got {0}")]
     PrimeDiffers(String),
+    #[error("Main offset {0} is out of the program's bounds (size {1})")]
+    MainOutOfRange(usize, usize),
+    #[error("Hint pc {0} is out of the program's bounds (size {1})")]
+    HintPcOutOfRange(usize, usize),
+    #[error("Hint at pc {0} references {1} with id {2}, which doesn't exist")]
+    UnknownReference(usize, String, usize),
+    #[error("Unknown builtin {0}")]
+    UnknownBuiltin(String),
+    #[error("Hint at pc {0} has no Python code, structured CASM hints aren't supported")]
+    MissingPythonicHint(usize),
 }
 
 #[cfg(test)]
@@ -32,8 +42,14 @@ mod tests {
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn format_entrypoint_not_found_error() {
-        let error = ProgramError::EntrypointNotFound(String::from("my_function"));
+        let error = ProgramError::EntrypointNotFound(
+            String::from("my_function"),
+            vec![String::from("main"), String::from("other_function")],
+        );
         let formatted_error = format!("{error}");
-        assert_eq!(formatted_error, "Entrypoint my_function not found");
+        assert_eq!(
+            formatted_error,
+            "Entrypoint my_function not found, available entrypoints: main, other_function"
+        );
     }
 }
@@ -1,13 +1,20 @@
-use crate::stdlib::{any::Any, cell::RefCell, collections::HashMap, prelude::*, rc::Rc};
+use crate::stdlib::{any::Any, collections::HashMap, prelude::*};
 use crate::{
-    any_box,
     hint_processor::builtin_hint_processor::dict_manager::DictManager,
+    types::shared::Shared,
     vm::errors::{exec_scope_errors::ExecScopeError, hint_errors::HintError},
 };
 
+///A variable stored in the execution scopes.
+///Under `std` it has to be `Send`, so that a `CairoRunner` can be moved to another thread.
+#[cfg(feature = "std")]
+pub type ScopeVariable = Box<dyn Any + Send>;
+#[cfg(not(feature = "std"))]
+pub type ScopeVariable = Box<dyn Any>;
+
 #[derive(Debug)]
 pub struct ExecutionScopes {
-    pub data: Vec<HashMap<String, Box<dyn Any>>>,
+    pub data: Vec<HashMap<String, ScopeVariable>>,
 }
 
 impl ExecutionScopes {
@@ -17,7 +24,7 @@ impl ExecutionScopes {
         }
     }
 
-    pub fn enter_scope(&mut self, new_scope_locals: HashMap<String, Box<dyn Any>>) {
+    pub fn enter_scope(&mut self, new_scope_locals: HashMap<String, ScopeVariable>) {
         self.data.push(new_scope_locals);
     }
 
@@ -33,14 +40,14 @@ impl ExecutionScopes {
     ///Returns a mutable reference to the dictionary containing the variables present in the current scope
     pub fn get_local_variables_mut(
         &mut self,
-    ) -> Result<&mut HashMap<String, Box<dyn Any>>, HintError> {
+    ) -> Result<&mut HashMap<String, ScopeVariable>, HintError> {
         self.data
             .last_mut()
             .ok_or(HintError::FromScopeError(ExecScopeError::NoScopeError))
     }
 
     ///Returns a dictionary containing the variables present in the current scope
-    pub fn get_local_variables(&self) -> Result<&HashMap<String, Box<dyn Any>>, HintError> {
+    pub fn get_local_variables(&self) -> Result<&HashMap<String, ScopeVariable>, HintError> {
         self.data
             .last()
             .ok_or(HintError::FromScopeError(ExecScopeError::NoScopeError))
@@ -54,7 +61,7 @@ impl ExecutionScopes {
     }
 
     ///Creates or updates an existing variable given its name and boxed value
-    pub fn assign_or_update_variable(&mut self, var_name: &str, var_value: Box<dyn Any>) {
+    pub fn assign_or_update_variable(&mut self, var_name: &str, var_value: ScopeVariable) {
         if let Ok(local_variables) = self.get_local_variables_mut() {
             local_variables.insert(var_name.to_string(), var_value);
         }
@@ -94,7 +101,7 @@ impl ExecutionScopes {
     }
 
     ///Returns the value in the current execution scope that matches the name
-    pub fn get_any_boxed_ref(&self, name: &str) -> Result<&Box<dyn Any>, HintError> {
+    pub fn get_any_boxed_ref(&self, name: &str) -> Result<&ScopeVariable, HintError> {
         if let Some(variable) = self.get_local_variables()?.get(name) {
             return Ok(variable);
         }
@@ -102,7 +109,7 @@ impl ExecutionScopes {
     }
 
     ///Returns the value in the current execution scope that matches the name
-    pub fn get_any_boxed_mut(&mut self, name: &str) -> Result<&mut Box<dyn Any>, HintError> {
+    pub fn get_any_boxed_mut(&mut self, name: &str) -> Result<&mut ScopeVariable, HintError> {
         if let Some(variable) = self.get_local_variables_mut()?.get_mut(name) {
             return Ok(variable);
         }
@@ -143,10 +150,10 @@ impl ExecutionScopes {
     }
 
     ///Returns the value in the dict manager
-    pub fn get_dict_manager(&self) -> Result<Rc<RefCell<DictManager>>, HintError> {
-        let mut val: Option<Rc<RefCell<DictManager>>> = None;
+    pub fn get_dict_manager(&self) -> Result<Shared<DictManager>, HintError> {
+        let mut val: Option<Shared<DictManager>> = None;
         if let Some(variable) = self.get_local_variables()?.get("dict_manager") {
-            if let Some(dict_manager) = variable.downcast_ref::<Rc<RefCell<DictManager>>>() {
+            if let Some(dict_manager) = variable.downcast_ref::<Shared<DictManager>>() {
                 val = Some(dict_manager.clone());
             }
         }
@@ -168,13 +175,20 @@ impl ExecutionScopes {
     }
 
     ///Inserts the boxed value into the current scope
-    pub fn insert_box(&mut self, name: &str, value: Box<dyn Any>) {
+    pub fn insert_box(&mut self, name: &str, value: ScopeVariable) {
         self.assign_or_update_variable(name, value);
     }
 
     ///Inserts the value into the current scope
-    pub fn insert_value<T: 'static>(&mut self, name: &str, value: T) {
-        self.assign_or_update_variable(name, any_box!(value));
+    #[cfg(feature = "std")]
+    pub fn insert_value<T: Any + Send>(&mut self, name: &str, value: T) {
+        self.assign_or_update_variable(name, Box::new(value));
+    }
+
+    ///Inserts the value into the current scope
+    #[cfg(not(feature = "std"))]
+    pub fn insert_value<T: Any>(&mut self, name: &str, value: T) {
+        self.assign_or_update_variable(name, Box::new(value));
     }
 }
 
@@ -205,7 +219,7 @@ mod tests {
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_local_variables_test() {
         let var_name = String::from("a");
-        let var_value: Box<dyn Any> = Box::new(Felt::new(2));
+        let var_value: ScopeVariable = Box::new(Felt::new(2));
 
         let scope = HashMap::from([(var_name, var_value)]);
 
@@ -226,14 +240,14 @@ mod tests {
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn enter_new_scope_test() {
         let var_name = String::from("a");
-        let var_value: Box<dyn Any> = Box::new(Felt::new(2_i32));
+        let var_value: ScopeVariable = Box::new(Felt::new(2_i32));
 
         let new_scope = HashMap::from([(var_name, var_value)]);
 
         let mut scopes = ExecutionScopes {
             data: vec![HashMap::from([(
                 String::from("b"),
-                (Box::new(Felt::one()) as Box<dyn Any>),
+                (Box::new(Felt::one()) as ScopeVariable),
             )])],
         };
 
@@ -269,7 +283,7 @@ mod tests {
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn exit_scope_test() {
         let var_name = String::from("a");
-        let var_value: Box<dyn Any> = Box::new(Felt::new(2));
+        let var_value: ScopeVariable = Box::new(Felt::new(2));
 
         let new_scope = HashMap::from([(var_name, var_value)]);
 
@@ -305,7 +319,7 @@ mod tests {
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn assign_local_variable_test() {
-        let var_value: Box<dyn Any> = Box::new(Felt::new(2));
+        let var_value: ScopeVariable = Box::new(Felt::new(2));
 
         let mut scopes = ExecutionScopes::new();
 
@@ -327,13 +341,13 @@ mod tests {
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn re_assign_local_variable_test() {
         let var_name = String::from("a");
-        let var_value: Box<dyn Any> = Box::new(Felt::new(2));
+        let var_value: ScopeVariable = Box::new(Felt::new(2));
 
         let scope = HashMap::from([(var_name, var_value)]);
 
         let mut scopes = ExecutionScopes { data: vec![scope] };
 
-        let var_value_new: Box<dyn Any> = Box::new(Felt::new(3));
+        let var_value_new: ScopeVariable = Box::new(Felt::new(3));
 
         scopes.assign_or_update_variable("a", var_value_new);
 
@@ -353,7 +367,7 @@ mod tests {
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn delete_local_variable_test() {
         let var_name = String::from("a");
-        let var_value: Box<dyn Any> = Box::new(Felt::new(2));
+        let var_value: ScopeVariable = Box::new(Felt::new(2));
 
         let scope = HashMap::from([(var_name, var_value)]);
 
@@ -383,7 +397,7 @@ mod tests {
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_listu64_test() {
-        let list_u64: Box<dyn Any> = Box::new(vec![20_u64, 18_u64]);
+        let list_u64: ScopeVariable = Box::new(vec![20_u64, 18_u64]);
 
         let mut scopes = ExecutionScopes::default();
 
@@ -405,7 +419,7 @@ mod tests {
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_u64_test() {
-        let u64: Box<dyn Any> = Box::new(9_u64);
+        let u64: ScopeVariable = Box::new(9_u64);
 
         let mut scopes = ExecutionScopes::new();
 
@@ -431,7 +445,7 @@ mod tests {
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_mut_int_ref_test() {
-        let bigint: Box<dyn Any> = Box::new(Felt::new(12));
+        let bigint: ScopeVariable = Box::new(Felt::new(12));
 
         let mut scopes = ExecutionScopes::new();
         scopes.assign_or_update_variable("bigint", bigint);
@@ -445,7 +459,7 @@ mod tests {
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_any_boxed_test() {
-        let list_u64: Box<dyn Any> = Box::new(vec![20_u64, 18_u64]);
+        let list_u64: ScopeVariable = Box::new(vec![20_u64, 18_u64]);
 
         let mut scopes = ExecutionScopes::default();
 
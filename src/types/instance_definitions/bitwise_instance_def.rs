@@ -2,7 +2,7 @@ pub(crate) const CELLS_PER_BITWISE: u32 = 5;
 pub(crate) const INPUT_CELLS_PER_BITWISE: u32 = 2;
 
 #[derive(Clone, Debug, PartialEq)]
-pub(crate) struct BitwiseInstanceDef {
+pub struct BitwiseInstanceDef {
     pub(crate) ratio: u32,
     pub(crate) total_n_bits: u32,
 }
@@ -16,9 +16,15 @@ impl BitwiseInstanceDef {
     }
 
     pub(crate) fn new(ratio: u32) -> Self {
+        BitwiseInstanceDef::new_with_bits(ratio, 251)
+    }
+
+    /// Builds the instance definition of a bitwise builtin operating on `total_n_bits` bit
+    /// integers, e.g. to build a custom `BitwiseBuiltinRunner`
+    pub fn new_with_bits(ratio: u32, total_n_bits: u32) -> Self {
         BitwiseInstanceDef {
             ratio,
-            total_n_bits: 251,
+            total_n_bits,
         }
     }
 
@@ -62,6 +68,16 @@ mod tests {
         assert_eq!(BitwiseInstanceDef::new(8), builtin_instance);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_new_with_bits() {
+        let builtin_instance = BitwiseInstanceDef {
+            ratio: 8,
+            total_n_bits: 64,
+        };
+        assert_eq!(BitwiseInstanceDef::new_with_bits(8, 64), builtin_instance);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn test_default() {
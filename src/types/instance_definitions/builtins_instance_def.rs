@@ -15,6 +15,8 @@ pub(crate) struct BuiltinsInstanceDef {
     pub(crate) ec_op: Option<EcOpInstanceDef>,
     pub(crate) keccak: Option<KeccakInstanceDef>,
     pub(crate) poseidon: Option<PoseidonInstanceDef>,
+    pub(crate) range_check96: Option<RangeCheckInstanceDef>,
+    pub(crate) segment_arena: bool,
 }
 
 impl BuiltinsInstanceDef {
@@ -28,6 +30,8 @@ impl BuiltinsInstanceDef {
             ec_op: None,
             keccak: None,
             poseidon: None,
+            range_check96: None,
+            segment_arena: false,
         }
     }
 
@@ -41,6 +45,8 @@ impl BuiltinsInstanceDef {
             ec_op: None,
             keccak: None,
             poseidon: None,
+            range_check96: None,
+            segment_arena: false,
         }
     }
 
@@ -54,6 +60,8 @@ impl BuiltinsInstanceDef {
             ec_op: None,
             keccak: None,
             poseidon: None,
+            range_check96: None,
+            segment_arena: false,
         }
     }
 
@@ -67,6 +75,8 @@ impl BuiltinsInstanceDef {
             ec_op: Some(EcOpInstanceDef::new(1024)),
             keccak: None,
             poseidon: None,
+            range_check96: None,
+            segment_arena: false,
         }
     }
 
@@ -80,6 +90,8 @@ impl BuiltinsInstanceDef {
             ec_op: None,
             keccak: None,
             poseidon: None,
+            range_check96: None,
+            segment_arena: false,
         }
     }
 
@@ -93,6 +105,8 @@ impl BuiltinsInstanceDef {
             ec_op: None,
             keccak: Some(KeccakInstanceDef::new(2048, vec![200; 8])),
             poseidon: None,
+            range_check96: None,
+            segment_arena: false,
         }
     }
 
@@ -106,6 +120,8 @@ impl BuiltinsInstanceDef {
             ec_op: Some(EcOpInstanceDef::default()),
             keccak: None,
             poseidon: Some(PoseidonInstanceDef::default()), // Added for testing
+            range_check96: Some(RangeCheckInstanceDef::range_check96()),
+            segment_arena: true,
         }
     }
 }
@@ -127,6 +143,8 @@ mod tests {
         assert!(builtins.ecdsa.is_none());
         assert!(builtins.bitwise.is_none());
         assert!(builtins.ec_op.is_none());
+        assert!(builtins.range_check96.is_none());
+        assert!(!builtins.segment_arena);
     }
 
     #[test]
@@ -199,5 +217,10 @@ mod tests {
         assert!(builtins.ecdsa.is_some());
         assert!(builtins.bitwise.is_some());
         assert!(builtins.ec_op.is_some());
+        assert_eq!(
+            builtins.range_check96,
+            Some(RangeCheckInstanceDef::new(8, 6))
+        );
+        assert!(builtins.segment_arena);
     }
 }
@@ -1,4 +1,6 @@
 pub(crate) const CELLS_PER_RANGE_CHECK: u32 = 1;
+/// Number of 16-bit parts checked by the `range_check96` builtin
+pub(crate) const RANGE_CHECK_96_N_PARTS: u32 = 6;
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct RangeCheckInstanceDef {
@@ -18,6 +20,13 @@ impl RangeCheckInstanceDef {
         RangeCheckInstanceDef { ratio, n_parts }
     }
 
+    pub(crate) fn range_check96() -> Self {
+        RangeCheckInstanceDef {
+            ratio: 8,
+            n_parts: RANGE_CHECK_96_N_PARTS,
+        }
+    }
+
     pub(crate) fn _cells_per_builtin(&self) -> u32 {
         CELLS_PER_RANGE_CHECK
     }
@@ -41,6 +50,13 @@ mod tests {
         assert_eq!(builtin_instance._range_check_units_per_builtin(), 8);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_range_check_units_per_builtin_range_check96() {
+        let builtin_instance = RangeCheckInstanceDef::range_check96();
+        assert_eq!(builtin_instance._range_check_units_per_builtin(), 6);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_cells_per_builtin() {
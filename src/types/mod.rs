@@ -5,3 +5,4 @@ pub mod instruction;
 pub mod layout;
 pub mod program;
 pub mod relocatable;
+pub mod shared;
@@ -2,18 +2,27 @@ use crate::stdlib::{collections::HashMap, prelude::*};
 
 use crate::{
     serde::deserialize_program::{
-        deserialize_and_parse_program, Attribute, HintParams, Identifier, InstructionLocation,
-        ReferenceManager,
+        deserialize_and_parse_casm_program, deserialize_and_parse_program, Attribute, HintParams,
+        Identifier, InstructionLocation, ProgramJsonRef, Reference, ReferenceManager,
     },
     types::{errors::program_errors::ProgramError, relocatable::MaybeRelocatable},
+    vm::runners::builtin_runner::{
+        BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+        OUTPUT_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_96_BUILTIN_NAME,
+        RANGE_CHECK_BUILTIN_NAME, SEGMENT_ARENA_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
+    },
 };
 use felt::{Felt, PRIME_STR};
-use serde::Serialize;
+use serde::{Serialize, Serializer};
+
+#[cfg(feature = "std")]
+use std::{io::Read, path::Path};
 
+// First bytes of every gzip stream
 #[cfg(feature = "std")]
-use std::path::Path;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Program {
     pub builtins: Vec<&'static str>,
     pub prime: String,
@@ -75,12 +84,130 @@ impl Program {
     #[cfg(feature = "std")]
     pub fn from_file(path: &Path, entrypoint: Option<&str>) -> Result<Program, ProgramError> {
         let file_content = std::fs::read(path)?;
-        deserialize_and_parse_program(&file_content, entrypoint)
+        Self::from_bytes(&file_content, entrypoint)
     }
 
+    /// Parses a compiled program. With the `std` feature, gzip-compressed programs are detected
+    /// by their header and inflated before parsing.
     pub fn from_bytes(bytes: &[u8], entrypoint: Option<&str>) -> Result<Program, ProgramError> {
+        #[cfg(feature = "std")]
+        if bytes.starts_with(&GZIP_MAGIC) {
+            return Self::from_gzip_bytes(bytes, entrypoint);
+        }
         deserialize_and_parse_program(bytes, entrypoint)
     }
+
+    /// Parses a gzip-compressed compiled program.
+    #[cfg(feature = "std")]
+    pub fn from_gzip_bytes(
+        bytes: &[u8],
+        entrypoint: Option<&str>,
+    ) -> Result<Program, ProgramError> {
+        let mut program_json = Vec::new();
+        flate2::read::GzDecoder::new(bytes).read_to_end(&mut program_json)?;
+        deserialize_and_parse_program(&program_json, entrypoint)
+    }
+
+    /// Parses a Cairo 1 program compiled to CASM, such as the output of `starknet-sierra-compile`.
+    /// `entrypoint_selector` picks the entry point used as the program's main, along with its
+    /// builtins.
+    /// The program must hold the Python code of its hints (`pythonic_hints`, emitted by
+    /// `starknet-sierra-compile --add-pythonic-hints`), as their structured form isn't supported.
+    pub fn from_casm_bytes(
+        bytes: &[u8],
+        entrypoint_selector: Option<&Felt>,
+    ) -> Result<Program, ProgramError> {
+        deserialize_and_parse_casm_program(bytes, entrypoint_selector)
+    }
+
+    /// Returns the reference bound to `name` in the program's hints.
+    ///
+    /// `name` can either be the full path of the identifier (ie: `__main__.main.x`) or the
+    /// short name used as `ids.x` inside the hint code. A short name is only resolved if it
+    /// belongs to a single full path, `None` is returned if it is ambiguous.
+    /// If the identifier is rebound along the program, the reference used by the hint with the
+    /// lowest pc is returned.
+    pub fn reference_for(&self, name: &str) -> Option<&Reference> {
+        let mut hint_pcs: Vec<&usize> = self.hints.keys().collect();
+        hint_pcs.sort();
+        let reference_ids: Vec<(&String, &usize)> = hint_pcs
+            .into_iter()
+            .flat_map(|pc| &self.hints[pc])
+            .flat_map(|hint| hint.flow_tracking_data.reference_ids.iter())
+            .collect();
+
+        let ref_id = match reference_ids.iter().find(|(path, _)| path.as_str() == name) {
+            Some((_, ref_id)) => ref_id,
+            None => {
+                let mut matches = reference_ids
+                    .iter()
+                    .filter(|(path, _)| path.rsplit('.').next() == Some(name));
+                let (path, ref_id) = matches.next()?;
+                if matches.any(|(other_path, _)| other_path != path) {
+                    return None;
+                }
+                ref_id
+            }
+        };
+        self.reference_manager.references.get(**ref_id)
+    }
+
+    /// Returns the identifiers of the given type (ie: `"function"`, `"const"`, `"struct"`),
+    /// along with their full names. The iteration order is unspecified.
+    pub fn identifiers_by_type<'a>(
+        &'a self,
+        ty: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a Identifier)> + 'a {
+        self.identifiers
+            .iter()
+            .filter(move |(_, identifier)| identifier.type_.as_deref() == Some(ty))
+            .map(|(name, identifier)| (name.as_str(), identifier))
+    }
+
+    /// Performs a structural validation of the program, catching corrupt artifacts before running them:
+    /// * main must be within the program's data
+    /// * every hint must be placed within the program's data
+    /// * every reference used by a hint must exist
+    /// * every builtin must be known by the VM
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        if let Some(main) = self.main {
+            if main >= self.data.len() {
+                return Err(ProgramError::MainOutOfRange(main, self.data.len()));
+            }
+        }
+        for (pc, hints) in self.hints.iter() {
+            if *pc >= self.data.len() {
+                return Err(ProgramError::HintPcOutOfRange(*pc, self.data.len()));
+            }
+            for hint in hints {
+                for (name, ref_id) in hint.flow_tracking_data.reference_ids.iter() {
+                    if *ref_id >= self.reference_manager.references.len() {
+                        return Err(ProgramError::UnknownReference(*pc, name.clone(), *ref_id));
+                    }
+                }
+            }
+        }
+        let known_builtins = [
+            OUTPUT_BUILTIN_NAME,
+            HASH_BUILTIN_NAME,
+            RANGE_CHECK_BUILTIN_NAME,
+            SIGNATURE_BUILTIN_NAME,
+            BITWISE_BUILTIN_NAME,
+            EC_OP_BUILTIN_NAME,
+            KECCAK_BUILTIN_NAME,
+            POSEIDON_BUILTIN_NAME,
+            RANGE_CHECK_96_BUILTIN_NAME,
+            SEGMENT_ARENA_BUILTIN_NAME,
+        ];
+        if let Some(builtin) = self
+            .builtins
+            .iter()
+            .find(|builtin| !known_builtins.contains(builtin))
+        {
+            return Err(ProgramError::UnknownBuiltin(builtin.to_string()));
+        }
+        Ok(())
+    }
 }
 
 impl Default for Program {
@@ -103,17 +230,80 @@ impl Default for Program {
         }
     }
 }
+
+/// Serializes the program in the compiled program format, so it can be parsed back with
+/// [Program::from_bytes]. Constants, main, start and end aren't serialized, as they are derived
+/// from the identifiers and the entrypoint when parsing.
+impl Serialize for Program {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ProgramJsonRef::from(self).serialize(serializer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::serde::deserialize_program::{ApTracking, FlowTrackingData};
+    use crate::serde::deserialize_program::{
+        ApTracking, FlowTrackingData, OffsetValue, ValueAddress,
+    };
+    use crate::types::instruction::Register;
     use crate::utils::test_utils::*;
+    use assert_matches::assert_matches;
     use felt::felt_str;
     use num_traits::Zero;
 
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::*;
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn validate_compiled_program() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/assert_nn.json"),
+            Some("main"),
+        )
+        .unwrap();
+        assert_matches!(program.validate(), Ok(()));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn validate_hint_pc_out_of_range() {
+        let hint = HintParams {
+            code: "memory[ap] = 1".to_string(),
+            accessible_scopes: Vec::new(),
+            flow_tracking_data: FlowTrackingData {
+                ap_tracking: ApTracking::default(),
+                reference_ids: HashMap::new(),
+            },
+        };
+        let program = program!(
+            data = vec_data!((5189976364521848832_i64), (1)),
+            hints = HashMap::from([(2, vec![hint])]),
+        );
+        assert_matches!(
+            program.validate(),
+            Err(ProgramError::HintPcOutOfRange(2, 2))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn validate_unknown_builtin() {
+        let program = program!(builtins = vec![OUTPUT_BUILTIN_NAME, "not_a_builtin"],);
+        assert_matches!(
+            program.validate(),
+            Err(ProgramError::UnknownBuiltin(name)) if name == "not_a_builtin"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn validate_main_out_of_range() {
+        let program = program!(data = vec_data!((1)), main = Some(1),);
+        assert_matches!(program.validate(), Err(ProgramError::MainOutOfRange(1, 1)));
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn new() {
@@ -363,6 +553,67 @@ mod tests {
         assert_eq!(program.identifiers, identifiers);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn identifiers_by_type_lists_functions() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/manually_compiled/valid_program_b.json"),
+            Some("main"),
+        )
+        .unwrap();
+
+        let mut functions: Vec<(&str, Option<usize>)> = program
+            .identifiers_by_type("function")
+            .map(|(name, identifier)| (name, identifier.pc))
+            .collect();
+        functions.sort();
+        assert_eq!(
+            functions,
+            [
+                (
+                    "__main__.check_range",
+                    program.identifiers["__main__.check_range"].pc
+                ),
+                ("__main__.main", program.main),
+                (
+                    "starkware.cairo.common.serialize.serialize_word",
+                    program.identifiers["starkware.cairo.common.serialize.serialize_word"].pc
+                ),
+            ]
+        );
+        assert!(functions.iter().all(|(_, pc)| pc.is_some()));
+        assert_eq!(program.identifiers_by_type("unknown_type").count(), 0);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn identifiers_by_type_lists_constants() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/manually_compiled/deserialize_constant_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+
+        assert_eq!(program.identifiers_by_type("const").count(), 5);
+        assert!(program
+            .identifiers_by_type("const")
+            .all(|(_, identifier)| identifier.value.is_some()));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deserialize_program_without_main() {
+        let result = Program::from_bytes(
+            include_bytes!("../../cairo_programs/no_main.json"),
+            Some("main"),
+        );
+        assert_matches!(
+            result,
+            Err(ProgramError::EntrypointNotFound(name, available))
+                if name == "main" && available == ["add_one", "double"]
+        );
+    }
+
     /// Deserialize a program without an entrypoint.
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
@@ -464,6 +715,77 @@ mod tests {
         assert_eq!(program.error_message_attributes, error_message_attributes)
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deserialize_casm_program() {
+        let selector = felt_str!(
+            "1d57794b1828b5fb83c8b9ba0212fc9e3b0b24007a3ec9c9ff4040523a04763",
+            16
+        );
+        let program = Program::from_casm_bytes(
+            include_bytes!("../../cairo_programs/manually_compiled/casm_add_constants.json"),
+            Some(&selector),
+        )
+        .unwrap();
+
+        assert_eq!(program.builtins, vec![RANGE_CHECK_BUILTIN_NAME]);
+        assert_eq!(program.main, Some(0));
+        assert_eq!(program.data.len(), 8);
+        assert_eq!(program.data[7], mayberelocatable!(2345108766317314046));
+        // The Python code of the hint is kept over its structured form
+        assert_eq!(program.hints.len(), 1);
+        assert_eq!(program.hints[&0].len(), 1);
+        assert_eq!(program.hints[&0][0].code, "memory[ap] = segments.add()");
+        assert!(program.identifiers.is_empty());
+        assert_matches!(program.validate(), Ok(()));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deserialize_casm_program_without_entrypoint() {
+        let program = Program::from_casm_bytes(
+            include_bytes!("../../cairo_programs/manually_compiled/casm_add_constants.json"),
+            None,
+        )
+        .unwrap();
+
+        assert!(program.builtins.is_empty());
+        assert_eq!(program.main, None);
+        assert_eq!(program.data.len(), 8);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deserialize_casm_program_unknown_selector() {
+        assert_matches!(
+            Program::from_casm_bytes(
+                include_bytes!("../../cairo_programs/manually_compiled/casm_add_constants.json"),
+                Some(&Felt::new(1)),
+            ),
+            Err(ProgramError::EntrypointNotFound(selector, selectors))
+                if selector == "0x1"
+                    && selectors
+                        == vec![String::from(
+                            "0x1d57794b1828b5fb83c8b9ba0212fc9e3b0b24007a3ec9c9ff4040523a04763"
+                        )]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deserialize_casm_program_structured_hints_without_python_code() {
+        let casm = r#"{
+            "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+            "bytecode": ["0x208b7fff7fff7ffe"],
+            "hints": [[0, [{"AllocSegment": {"dst": {"register": "AP", "offset": 0}}}]]],
+            "entry_points_by_type": {"EXTERNAL": []}
+        }"#;
+        assert_matches!(
+            Program::from_casm_bytes(casm.as_bytes(), None),
+            Err(ProgramError::MissingPythonicHint(0))
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn deserialize_program_constants_test() {
@@ -503,6 +825,86 @@ mod tests {
         assert_eq!(program.constants, constants);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn reference_for_known_identifier() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/assert_nn.json"),
+            Some("main"),
+        )
+        .unwrap();
+
+        let reference = program
+            .reference_for("__main__.assert_nn_manual_implementation.a")
+            .unwrap();
+        assert_eq!(
+            reference.value_address,
+            ValueAddress {
+                offset1: OffsetValue::Reference(Register::FP, -3, false),
+                offset2: OffsetValue::Value(0),
+                dereference: true,
+                value_type: "felt".to_string(),
+            }
+        );
+        // Both assert_nn and assert_nn_manual_implementation bind an `a` reference
+        assert_eq!(program.reference_for("a"), None);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn reference_for_resolves_in_pc_order() {
+        let hint = |reference_ids: &[(&str, usize)]| HintParams {
+            code: "".to_string(),
+            accessible_scopes: Vec::new(),
+            flow_tracking_data: FlowTrackingData {
+                ap_tracking: ApTracking::default(),
+                reference_ids: reference_ids
+                    .iter()
+                    .map(|(name, ref_id)| (name.to_string(), *ref_id))
+                    .collect(),
+            },
+        };
+        let reference = |pc: usize| Reference {
+            ap_tracking_data: ApTracking::default(),
+            pc: Some(pc),
+            value_address: ValueAddress::no_hint_reference_default(),
+        };
+        let program = program!(
+            hints = HashMap::from([
+                (4, vec![hint(&[("__main__.f.x", 1), ("__main__.f.y", 2)])]),
+                (0, vec![hint(&[("__main__.f.x", 0), ("__main__.g.y", 3)])]),
+            ]),
+            reference_manager = ReferenceManager {
+                references: (0..4).map(reference).collect(),
+            },
+        );
+
+        // `x` is rebound at pc 4, the reference of the first hint is returned
+        assert_eq!(
+            program.reference_for("__main__.f.x").and_then(|r| r.pc),
+            Some(0)
+        );
+        assert_eq!(program.reference_for("x").and_then(|r| r.pc), Some(0));
+        // `y` can be either `__main__.f.y` or `__main__.g.y`
+        assert_eq!(program.reference_for("y"), None);
+        assert_eq!(
+            program.reference_for("__main__.g.y").and_then(|r| r.pc),
+            Some(3)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn reference_for_unknown_identifier() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/assert_nn.json"),
+            Some("main"),
+        )
+        .unwrap();
+
+        assert_eq!(program.reference_for("__main__.main.not_a_reference"), None);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn default_program() {
@@ -525,4 +927,62 @@ mod tests {
 
         assert_eq!(program, Program::default())
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn serialize_program_round_trip() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+
+        let bytes = serde_json::to_vec(&program).unwrap();
+        let reparsed_program = Program::from_bytes(&bytes, Some("main")).unwrap();
+
+        assert_eq!(reparsed_program, program);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn serialize_program_with_relocatable_data() {
+        let program = Program {
+            data: vec![mayberelocatable!(1, 2)],
+            ..Default::default()
+        };
+
+        assert!(serde_json::to_vec(&program).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn deserialize_gzip_program() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let bytes = include_bytes!("../../cairo_programs/pedersen_test.json");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        let compressed_bytes = encoder.finish().unwrap();
+
+        let program = Program::from_bytes(bytes, Some("main")).unwrap();
+        assert_eq!(
+            Program::from_gzip_bytes(&compressed_bytes, Some("main")).unwrap(),
+            program
+        );
+        assert_eq!(
+            Program::from_bytes(&compressed_bytes, Some("main")).unwrap(),
+            program
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn deserialize_truncated_gzip_program() {
+        let bytes = [0x1f, 0x8b, 0x08];
+        assert_matches!(
+            Program::from_bytes(&bytes, Some("main")),
+            Err(ProgramError::IO(_))
+        );
+    }
 }
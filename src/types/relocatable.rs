@@ -8,7 +8,7 @@ use crate::{
     relocatable, types::errors::math_errors::MathError, vm::errors::memory_errors::MemoryError,
 };
 use felt::Felt;
-use num_traits::{ToPrimitive, Zero};
+use num_traits::{One, ToPrimitive, Zero};
 use serde::{Deserialize, Serialize};
 
 #[derive(Eq, Hash, PartialEq, PartialOrd, Clone, Copy, Debug, Serialize, Deserialize)]
@@ -169,6 +169,14 @@ impl Sub<Relocatable> for Relocatable {
     }
 }
 
+impl Relocatable {
+    /// Returns true if self belongs to the segment with index `base`.
+    /// Bases that don't fit in an isize never match.
+    pub fn in_segment(&self, base: usize) -> bool {
+        isize::try_from(base).map_or(false, |base| base == self.segment_index)
+    }
+}
+
 impl TryInto<Relocatable> for MaybeRelocatable {
     type Error = MemoryError;
     fn try_into(self) -> Result<Relocatable, MemoryError> {
@@ -240,8 +248,11 @@ impl MaybeRelocatable {
     }
 
     /// Substracts two MaybeRelocatable values and returns the result as a MaybeRelocatable value.
-    /// Only values of the same type may be substracted.
-    /// Relocatable values can only be substracted if they belong to the same segment.
+    /// - Int - Int: the felt difference (modulo the field prime).
+    /// - Relocatable - Int: a new relocatable in the same segment, erroring if the offset would be negative.
+    /// - Relocatable - Relocatable: the (non-negative) distance between both addresses as an Int.
+    ///   Both values must belong to the same segment.
+    /// - Int - Relocatable: always an error.
     pub fn sub(&self, other: &MaybeRelocatable) -> Result<MaybeRelocatable, MathError> {
         match (self, other) {
             (MaybeRelocatable::Int(num_a), MaybeRelocatable::Int(num_b)) => {
@@ -301,6 +312,38 @@ impl MaybeRelocatable {
             MaybeRelocatable::Int(_) => None,
         }
     }
+
+    /// Reads a boolean flag: returns Ok(false) for 0 and Ok(true) for 1. Any other value,
+    /// relocatables included, is an error.
+    pub fn as_bool(&self) -> Result<bool, MathError> {
+        match self {
+            MaybeRelocatable::Int(num) if num.is_zero() => Ok(false),
+            MaybeRelocatable::Int(num) if num.is_one() => Ok(true),
+            _ => Err(MathError::MaybeRelocatableToBoolConversion(self.clone())),
+        }
+    }
+
+    /// Reads an integer value lower than `2^128`, such as the limbs of a `Uint256`.
+    /// Relocatables and integers out of range are an error.
+    pub fn get_u128(&self) -> Result<u128, MathError> {
+        self.get_int_ref()
+            .and_then(|num| num.to_biguint().to_u128())
+            .ok_or_else(|| MathError::MaybeRelocatableToU128Conversion(self.clone()))
+    }
+
+    /// Reads an integer value in `[-2^127, 2^127)`, taking field elements above `PRIME / 2`
+    /// as negative. Relocatables and integers out of range are an error.
+    pub fn get_i128(&self) -> Result<i128, MathError> {
+        self.get_int_ref()
+            .and_then(|num| num.to_bigint().to_i128())
+            .ok_or_else(|| MathError::MaybeRelocatableToI128Conversion(self.clone()))
+    }
+
+    /// Builds an integer value from a signed integer, with negative values reduced modulo the
+    /// prime. The inverse of [get_i128](Self::get_i128).
+    pub fn from_signed(num: i128) -> MaybeRelocatable {
+        MaybeRelocatable::Int(Felt::new(num))
+    }
 }
 
 impl<'a> Add<usize> for &'a Relocatable {
@@ -359,6 +402,34 @@ mod tests {
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::*;
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn in_segment_same_index() {
+        assert!(relocatable!(0, 0).in_segment(0));
+        assert!(relocatable!(isize::MAX, 5).in_segment(isize::MAX as usize));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn in_segment_different_index() {
+        assert!(!relocatable!(1, 0).in_segment(0));
+        assert!(!relocatable!(0, 0).in_segment(1));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn in_segment_temporary_segment() {
+        assert!(!relocatable!(-1, 0).in_segment(usize::MAX));
+        assert!(!relocatable!(-1, 0).in_segment(0));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn in_segment_base_exceeds_isize() {
+        assert!(!relocatable!(isize::MAX, 0).in_segment(isize::MAX as usize + 1));
+        assert!(!relocatable!(isize::MIN, 0).in_segment(isize::MAX as usize + 1));
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn add_bigint_to_int() {
@@ -604,6 +675,59 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sub_int_from_int_wraps_around_prime() {
+        let addr_a = &MaybeRelocatable::from(Felt::zero());
+        let addr_b = &MaybeRelocatable::from(Felt::one());
+        assert_eq!(
+            addr_a.sub(addr_b),
+            Ok(MaybeRelocatable::Int(felt_str!(
+                "3618502788666131213697322783095070105623107215331596699973092056135872020480"
+            )))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sub_relocatable_from_itself_is_zero() {
+        let addr = &mayberelocatable!(3, 4);
+        assert_eq!(addr.sub(addr), Ok(mayberelocatable!(0)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sub_relocatable_from_relocatable_negative_distance() {
+        let addr_a = &mayberelocatable!(7, 7);
+        let addr_b = &mayberelocatable!(7, 17);
+        assert_eq!(
+            addr_a.sub(addr_b),
+            Err(MathError::RelocatableSubNegOffset(relocatable!(7, 7), 17))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sub_int_from_relocatable_offset_underflow() {
+        let addr_a = &mayberelocatable!(7, 3);
+        let addr_b = &mayberelocatable!(4);
+        assert_eq!(
+            addr_a.sub(addr_b),
+            Err(MathError::RelocatableAddFeltOffsetExceeded(
+                relocatable!(7, 3),
+                Felt::new(4)
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sub_int_from_relocatable_to_segment_start() {
+        let addr_a = &mayberelocatable!(7, 3);
+        let addr_b = &mayberelocatable!(3);
+        assert_eq!(addr_a.sub(addr_b), Ok(mayberelocatable!(7, 0)));
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn divmod_working() {
@@ -848,4 +972,112 @@ mod tests {
         addr += 1;
         assert_eq!(addr, Relocatable::from((1, 1)))
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn as_bool() {
+        assert_eq!(MaybeRelocatable::from(Felt::zero()).as_bool(), Ok(false));
+        assert_eq!(MaybeRelocatable::from(Felt::one()).as_bool(), Ok(true));
+        assert_eq!(
+            MaybeRelocatable::from(Felt::new(2)).as_bool(),
+            Err(MathError::MaybeRelocatableToBoolConversion(
+                MaybeRelocatable::from(Felt::new(2))
+            ))
+        );
+        assert_eq!(
+            MaybeRelocatable::from(-Felt::one()).as_bool(),
+            Err(MathError::MaybeRelocatableToBoolConversion(
+                MaybeRelocatable::from(-Felt::one())
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn as_bool_relocatable() {
+        assert_eq!(
+            MaybeRelocatable::from((1, 0)).as_bool(),
+            Err(MathError::MaybeRelocatableToBoolConversion(
+                MaybeRelocatable::from((1, 0))
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_u128_boundary() {
+        assert_eq!(
+            MaybeRelocatable::from(Felt::new(u128::MAX)).get_u128(),
+            Ok(u128::MAX)
+        );
+        let two_pow_128 = MaybeRelocatable::from(Felt::new(u128::MAX) + 1_u32);
+        assert_eq!(
+            two_pow_128.get_u128(),
+            Err(MathError::MaybeRelocatableToU128Conversion(
+                two_pow_128.clone()
+            ))
+        );
+        let minus_one = MaybeRelocatable::from(-Felt::one());
+        assert_eq!(
+            minus_one.get_u128(),
+            Err(MathError::MaybeRelocatableToU128Conversion(
+                minus_one.clone()
+            ))
+        );
+        assert_eq!(
+            MaybeRelocatable::from((1, 0)).get_u128(),
+            Err(MathError::MaybeRelocatableToU128Conversion(
+                MaybeRelocatable::from((1, 0))
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_i128_boundary() {
+        assert_eq!(
+            MaybeRelocatable::from(Felt::new(i128::MAX)).get_i128(),
+            Ok(i128::MAX)
+        );
+        assert_eq!(
+            MaybeRelocatable::from(Felt::new(i128::MIN)).get_i128(),
+            Ok(i128::MIN)
+        );
+        let two_pow_127 = MaybeRelocatable::from(Felt::new(i128::MAX) + 1_u32);
+        assert_eq!(
+            two_pow_127.get_i128(),
+            Err(MathError::MaybeRelocatableToI128Conversion(
+                two_pow_127.clone()
+            ))
+        );
+        let minus_two_pow_127_minus_one = MaybeRelocatable::from(Felt::new(i128::MIN) - 1_u32);
+        assert_eq!(
+            minus_two_pow_127_minus_one.get_i128(),
+            Err(MathError::MaybeRelocatableToI128Conversion(
+                minus_two_pow_127_minus_one.clone()
+            ))
+        );
+        assert_eq!(
+            MaybeRelocatable::from((1, 0)).get_i128(),
+            Err(MathError::MaybeRelocatableToI128Conversion(
+                MaybeRelocatable::from((1, 0))
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn from_signed() {
+        assert_eq!(
+            MaybeRelocatable::from_signed(-1),
+            MaybeRelocatable::Int(felt_str!(
+                "3618502788666131213697322783095070105623107215331596699973092056135872020480"
+            ))
+        );
+        assert_eq!(MaybeRelocatable::from_signed(0), MaybeRelocatable::from(0));
+        assert_eq!(MaybeRelocatable::from_signed(7), MaybeRelocatable::from(7));
+        for num in [-7, i128::MIN, i128::MAX] {
+            assert_eq!(MaybeRelocatable::from_signed(num).get_i128(), Ok(num));
+        }
+    }
 }
@@ -0,0 +1,134 @@
+use crate::stdlib::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+#[cfg(feature = "std")]
+use crate::stdlib::sync::{Arc, PoisonError, RwLock};
+#[cfg(not(feature = "std"))]
+use crate::stdlib::{cell::RefCell, rc::Rc};
+
+///A handle to a value shared between several owners (e.g. the dict manager of the execution
+///scopes, or the signatures of the ecdsa builtin and its validation rule).
+///Under `std` it is backed by an `Arc<RwLock<T>>`, so that the runners and vms holding it can be
+///moved to other threads, and by an `Rc<RefCell<T>>` otherwise.
+///Several shared borrows of the value can be alive at once, but a mutable borrow is exclusive:
+///taking one while another borrow is alive blocks under `std` and panics otherwise.
+pub struct Shared<T> {
+    #[cfg(feature = "std")]
+    inner: Arc<RwLock<T>>,
+    #[cfg(not(feature = "std"))]
+    inner: Rc<RefCell<T>>,
+}
+
+impl<T> Shared<T> {
+    pub fn new(value: T) -> Self {
+        Shared {
+            #[cfg(feature = "std")]
+            inner: Arc::new(RwLock::new(value)),
+            #[cfg(not(feature = "std"))]
+            inner: Rc::new(RefCell::new(value)),
+        }
+    }
+
+    ///Returns a reference to the shared value
+    #[cfg(feature = "std")]
+    pub fn borrow(&self) -> impl Deref<Target = T> + '_ {
+        // The lock is only poisoned if a previous borrower panicked, the value is still usable
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    ///Returns a reference to the shared value
+    #[cfg(not(feature = "std"))]
+    pub fn borrow(&self) -> impl Deref<Target = T> + '_ {
+        self.inner.borrow()
+    }
+
+    ///Returns a mutable reference to the shared value
+    #[cfg(feature = "std")]
+    pub fn borrow_mut(&self) -> impl DerefMut<Target = T> + '_ {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    ///Returns a mutable reference to the shared value
+    #[cfg(not(feature = "std"))]
+    pub fn borrow_mut(&self) -> impl DerefMut<Target = T> + '_ {
+        self.inner.borrow_mut()
+    }
+
+    ///Returns true if both handles point to the same value
+    #[cfg(feature = "std")]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    ///Returns true if both handles point to the same value
+    #[cfg(not(feature = "std"))]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Shared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || *self.borrow() == *other.borrow()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Shared").field(&*self.borrow()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn clones_share_the_value() {
+        let shared = Shared::new(1);
+        let other = shared.clone();
+        *other.borrow_mut() += 1;
+        assert_eq!(*shared.borrow(), 2);
+        assert!(shared.ptr_eq(&other));
+        assert_eq!(shared, other);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn different_handles_compare_values() {
+        assert_eq!(Shared::new(1), Shared::new(1));
+        assert_ne!(Shared::new(1), Shared::new(2));
+        assert!(!Shared::new(1).ptr_eq(&Shared::new(1)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn nested_shared_borrows() {
+        let shared = Shared::new(1);
+        let other = shared.clone();
+        let borrow = shared.borrow();
+        assert_eq!(*other.borrow(), 1);
+        assert_eq!(*borrow, 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn shared_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Shared<Vec<usize>>>();
+    }
+}
@@ -1,6 +1,6 @@
 use crate::stdlib::prelude::*;
 
-use crate::types::relocatable::Relocatable;
+use crate::types::{errors::math_errors::FeltParseError, relocatable::Relocatable};
 use felt::Felt;
 use lazy_static::lazy_static;
 use num_bigint::BigUint;
@@ -37,6 +37,53 @@ pub fn is_subsequence<T: PartialEq>(subsequence: &[T], mut sequence: &[T]) -> bo
     true
 }
 
+/// Parses a hex string, optionally prefixed by `0x`, into a felt.
+/// Unlike `Felt::from_str_radix`, values greater than or equal to the field prime are rejected
+/// instead of being reduced, and errors point at the offending character.
+pub fn felt_from_hex(value: &str) -> Result<Felt, FeltParseError> {
+    let (prefix_len, digits) = match value.strip_prefix("0x") {
+        Some(digits) => (2, digits),
+        None => (0, value),
+    };
+    if digits.is_empty() {
+        return Err(FeltParseError::Empty(value.to_string()));
+    }
+    if let Some((position, invalid_char)) = digits
+        .char_indices()
+        .find(|(_, digit)| !digit.is_ascii_hexdigit())
+    {
+        return Err(FeltParseError::InvalidHexChar(
+            value.to_string(),
+            invalid_char,
+            prefix_len + position,
+        ));
+    }
+    // Only hex digits are left at this point, so parsing can't fail
+    match BigUint::parse_bytes(digits.as_bytes(), 16) {
+        Some(number) if number < *CAIRO_PRIME => Ok(Felt::from(number)),
+        _ => Err(FeltParseError::OutOfRange(value.to_string())),
+    }
+}
+
+/// Converts a felt into its canonical integer representative, in the range [0, PRIME).
+/// This is the conversion hints should go through when they need `BigUint` arithmetic; convert
+/// once, operate on the `BigUint` and convert the final result back with [`biguint_to_felt`].
+pub fn felt_to_biguint(felt: &Felt) -> BigUint {
+    felt.to_biguint()
+}
+
+/// Converts an integer into a felt, reducing it modulo PRIME.
+/// Takes the value by ownership so that no copy of its digits is made.
+pub fn biguint_to_felt(value: BigUint) -> Felt {
+    Felt::from(value)
+}
+
+/// Returns the number of bits needed to represent the felt's canonical integer value, which is
+/// 0 for zero and at most 252.
+pub fn felt_bit_length(felt: &Felt) -> u64 {
+    felt.bits()
+}
+
 pub fn from_relocatable_to_indexes(relocatable: Relocatable) -> (usize, usize) {
     if relocatable.segment_index.is_negative() {
         (
@@ -107,6 +154,7 @@ pub mod test_utils {
                     segment_sizes: HashMap::new(),
                     segment_used_sizes: None,
                     public_memory_offsets: HashMap::new(),
+                    accessed_addresses_cache: crate::stdlib::cell::RefCell::new(None),
                 }
 
             }
@@ -413,15 +461,28 @@ pub mod test_utils {
     }
     pub(crate) use check_scope;
 
+    /// Asserts that two `Felt`s are equal, printing both in decimal and hexadecimal on failure
+    macro_rules! assert_felt_eq {
+        ($actual: expr, $expected: expr) => {{
+            let (actual, expected): (&felt::Felt, &felt::Felt) = (&$actual, &$expected);
+            assert!(
+                actual == expected,
+                "assertion failed: `(actual == expected)`\n  actual: {} (0x{})\nexpected: {} (0x{})",
+                actual,
+                actual.to_str_radix(16),
+                expected,
+                expected.to_str_radix(16)
+            );
+        }};
+    }
+    pub(crate) use assert_felt_eq;
+
     macro_rules! scope {
         (  $( ($name: expr, $val: expr)),*  ) => {
             {
                 let mut exec_scopes = ExecutionScopes::new();
                 $(
-                    exec_scopes.assign_or_update_variable(
-                        $name,
-                        any_box!($val),
-                    );
+                    exec_scopes.insert_value($name, $val);
                 )*
                 exec_scopes
             }
@@ -473,13 +534,13 @@ pub mod test_utils {
             )*
             let mut dict_manager = DictManager::new();
             dict_manager.trackers.insert(2, tracker);
-            $exec_scopes.insert_value("dict_manager", crate::stdlib::rc::Rc::new(core::cell::RefCell::new(dict_manager)))
+            $exec_scopes.insert_value("dict_manager", crate::types::shared::Shared::new(dict_manager))
         };
         ($exec_scopes:expr, $tracker_num:expr) => {
             let  tracker = DictTracker::new_empty(relocatable!($tracker_num, 0));
             let mut dict_manager = DictManager::new();
             dict_manager.trackers.insert(2, tracker);
-            $exec_scopes.insert_value("dict_manager", crate::stdlib::rc::Rc::new(core::cell::RefCell::new(dict_manager)))
+            $exec_scopes.insert_value("dict_manager", crate::types::shared::Shared::new(dict_manager))
         };
 
     }
@@ -493,13 +554,13 @@ pub mod test_utils {
             )*
             let mut dict_manager = DictManager::new();
             dict_manager.trackers.insert(2, tracker);
-            $exec_scopes.insert_value("dict_manager", crate::stdlib::rc::Rc::new(core::cell::RefCell::new(dict_manager)))
+            $exec_scopes.insert_value("dict_manager", crate::types::shared::Shared::new(dict_manager))
         };
         ($exec_scopes:expr, $tracker_num:expr,$default:expr) => {
             let tracker = DictTracker::new_default_dict(relocatable!($tracker_num, 0), &MaybeRelocatable::from($default), None);
             let mut dict_manager = DictManager::new();
             dict_manager.trackers.insert(2, tracker);
-            $exec_scopes.insert_value("dict_manager", crate::stdlib::rc::Rc::new(core::cell::RefCell::new(dict_manager)))
+            $exec_scopes.insert_value("dict_manager", crate::types::shared::Shared::new(dict_manager))
         };
     }
     pub(crate) use dict_manager_default;
@@ -521,6 +582,60 @@ pub mod test_utils {
     }
     pub(crate) use vec_data_inner;
 
+    /// Deterministic SplitMix64 generator, used to make randomized tests reproducible
+    pub struct TestRng(pub u64);
+
+    impl rand_core::RngCore for TestRng {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^ (z >> 31)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    /// Returns a felt uniformly distributed over [0, PRIME)
+    pub fn random_felt<R: rand_core::RngCore>(rng: &mut R) -> felt::Felt {
+        let prime = felt::Felt::prime();
+        let mut bytes = [0_u8; 32];
+        // Rejection sampling over 252 bits, which keeps the distribution uniform
+        loop {
+            rng.fill_bytes(&mut bytes);
+            bytes[0] &= 0x0f;
+            let value = num_bigint::BigUint::from_bytes_be(&bytes);
+            if value < prime {
+                return felt::Felt::from(value);
+            }
+        }
+    }
+
+    /// Returns a relocatable in one of the first `num_segments` segments, with an offset
+    /// lower than `max_offset`
+    pub fn random_relocatable<R: rand_core::RngCore>(
+        rng: &mut R,
+        num_segments: usize,
+        max_offset: usize,
+    ) -> crate::types::relocatable::Relocatable {
+        crate::types::relocatable::Relocatable::from((
+            (rng.next_u64() % num_segments as u64) as isize,
+            (rng.next_u64() % max_offset as u64) as usize,
+        ))
+    }
+
     pub fn check_scope_value<T: core::fmt::Debug + core::cmp::PartialEq + 'static>(
         scopes: &ExecutionScopes,
         name: &str,
@@ -533,7 +648,7 @@ pub mod test_utils {
 
 #[cfg(test)]
 mod test {
-    use crate::stdlib::{cell::RefCell, collections::HashMap, rc::Rc, string::String, vec::Vec};
+    use crate::stdlib::{collections::HashMap, string::String, vec::Vec};
     use crate::{
         hint_processor::{
             builtin_hint_processor::{
@@ -543,7 +658,10 @@ mod test {
             hint_processor_definition::{HintProcessor, HintReference},
         },
         serde::deserialize_program::ReferenceManager,
-        types::{exec_scope::ExecutionScopes, program::Program, relocatable::MaybeRelocatable},
+        types::{
+            exec_scope::ExecutionScopes, program::Program, relocatable::MaybeRelocatable,
+            shared::Shared,
+        },
         utils::test_utils::*,
         vm::{
             errors::memory_errors::MemoryError, runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME,
@@ -551,7 +669,7 @@ mod test {
         },
     };
     use felt::Felt;
-    use num_traits::One;
+    use num_traits::{Bounded, One, Zero};
 
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::*;
@@ -724,43 +842,46 @@ mod test {
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn check_scope_test_pass() {
         let mut exec_scopes = ExecutionScopes::new();
-        exec_scopes.assign_or_update_variable("a", any_box!(String::from("Hello")));
-        exec_scopes.assign_or_update_variable(
-            "",
-            any_box!(Rc::new(RefCell::new(HashMap::<usize, Vec<usize>>::new()))),
-        );
-        exec_scopes.assign_or_update_variable("c", any_box!(vec![1, 2, 3, 4]));
+        exec_scopes.insert_value("a", String::from("Hello"));
+        exec_scopes.insert_value("", Shared::new(HashMap::<usize, Vec<usize>>::new()));
+        exec_scopes.insert_value("c", vec![1, 2, 3, 4]);
         check_scope!(
             &exec_scopes,
             [
                 ("a", String::from("Hello")),
-                (
-                    "",
-                    Rc::new(RefCell::new(HashMap::<usize, Vec<usize>>::new()))
-                ),
+                ("", Shared::new(HashMap::<usize, Vec<usize>>::new())),
                 ("c", vec![1, 2, 3, 4])
             ]
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn assert_felt_eq_pass() {
+        assert_felt_eq!(Felt::new(255), Felt::new(0xff));
+        assert_felt_eq!(-Felt::one(), Felt::zero() - 1_u32);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "  actual: 3086936446498698982 (0x2ad70057c1c2f6e6)\nexpected: 255 (0xff)"
+    )]
+    fn assert_felt_eq_fail() {
+        assert_felt_eq!(Felt::new(3086936446498698982_u64), Felt::new(255));
+    }
+
     #[test]
     #[should_panic]
     fn check_scope_test_fail() {
         let mut exec_scopes = ExecutionScopes::new();
-        exec_scopes.assign_or_update_variable("a", any_box!(String::from("Hello")));
-        exec_scopes.assign_or_update_variable(
-            "",
-            any_box!(Rc::new(RefCell::new(HashMap::<usize, Vec<usize>>::new()))),
-        );
-        exec_scopes.assign_or_update_variable("c", any_box!(vec![1, 2, 3, 4]));
+        exec_scopes.insert_value("a", String::from("Hello"));
+        exec_scopes.insert_value("", Shared::new(HashMap::<usize, Vec<usize>>::new()));
+        exec_scopes.insert_value("c", vec![1, 2, 3, 4]);
         check_scope!(
             &exec_scopes,
             [
                 ("a", String::from("Hello")),
-                (
-                    "",
-                    Rc::new(RefCell::new(HashMap::<usize, Vec<usize>>::new()))
-                ),
+                ("", Shared::new(HashMap::<usize, Vec<usize>>::new())),
                 ("c", vec![1, 2, 3, 5])
             ]
         );
@@ -771,7 +892,7 @@ mod test {
     fn scope_macro_test() {
         let scope_from_macro = scope![("a", Felt::one())];
         let mut scope_verbose = ExecutionScopes::new();
-        scope_verbose.assign_or_update_variable("a", any_box!(Felt::one()));
+        scope_verbose.insert_value("a", Felt::one());
         assert_eq!(scope_from_macro.data.len(), scope_verbose.data.len());
         assert_eq!(scope_from_macro.data[0].len(), scope_verbose.data[0].len());
         assert_eq!(
@@ -791,10 +912,7 @@ mod test {
         let mut dict_manager = DictManager::new();
         dict_manager.trackers.insert(2, tracker);
         let mut exec_scopes = ExecutionScopes::new();
-        exec_scopes.assign_or_update_variable(
-            "dict_manager",
-            any_box!(Rc::new(RefCell::new(dict_manager))),
-        );
+        exec_scopes.insert_value("dict_manager", Shared::new(dict_manager));
         check_dictionary!(&exec_scopes, 2, (5, 10));
     }
 
@@ -809,10 +927,7 @@ mod test {
         let mut dict_manager = DictManager::new();
         dict_manager.trackers.insert(2, tracker);
         let mut exec_scopes = ExecutionScopes::new();
-        exec_scopes.assign_or_update_variable(
-            "dict_manager",
-            any_box!(Rc::new(RefCell::new(dict_manager))),
-        );
+        exec_scopes.insert_value("dict_manager", Shared::new(dict_manager));
         check_dictionary!(&exec_scopes, 2, (5, 11));
     }
 
@@ -823,10 +938,7 @@ mod test {
         let mut dict_manager = DictManager::new();
         dict_manager.trackers.insert(2, tracker);
         let mut exec_scopes = ExecutionScopes::new();
-        exec_scopes.assign_or_update_variable(
-            "dict_manager",
-            any_box!(Rc::new(RefCell::new(dict_manager))),
-        );
+        exec_scopes.insert_value("dict_manager", Shared::new(dict_manager));
         check_dict_ptr!(&exec_scopes, 2, (2, 0));
     }
 
@@ -837,10 +949,7 @@ mod test {
         let mut dict_manager = DictManager::new();
         dict_manager.trackers.insert(2, tracker);
         let mut exec_scopes = ExecutionScopes::new();
-        exec_scopes.assign_or_update_variable(
-            "dict_manager",
-            any_box!(Rc::new(RefCell::new(dict_manager))),
-        );
+        exec_scopes.insert_value("dict_manager", Shared::new(dict_manager));
         check_dict_ptr!(&exec_scopes, 2, (3, 0));
     }
 
@@ -854,7 +963,7 @@ mod test {
         dict_manager!(exec_scopes, 2);
         assert_matches::assert_matches!(
             exec_scopes.get_dict_manager(),
-            Ok(x) if x == Rc::new(RefCell::new(dict_manager))
+            Ok(x) if x == Shared::new(dict_manager)
         );
     }
 
@@ -872,7 +981,7 @@ mod test {
         dict_manager_default!(exec_scopes, 2, 17);
         assert_matches::assert_matches!(
             exec_scopes.get_dict_manager(),
-            Ok(x) if x == Rc::new(RefCell::new(dict_manager))
+            Ok(x) if x == Shared::new(dict_manager)
         );
     }
 
@@ -967,4 +1076,132 @@ mod test {
             program!(builtins = vec![RANGE_CHECK_BUILTIN_NAME], main = Some(2),)
         )
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn random_felt_is_canonical_and_reproducible() {
+        let prime = Felt::prime();
+        let mut rng = TestRng(42);
+        let felts: Vec<Felt> = (0..100).map(|_| random_felt(&mut rng)).collect();
+        assert!(felts.iter().all(|felt| felt.to_biguint() < prime));
+        // Values are spread over the whole field, not just the low bits
+        assert!(felts.iter().any(|felt| felt.bits() > 240));
+
+        let mut rng = TestRng(42);
+        assert!(felts.iter().all(|felt| felt == &random_felt(&mut rng)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn random_relocatable_within_bounds() {
+        let mut rng = TestRng(7);
+        for _ in 0..100 {
+            let relocatable = random_relocatable(&mut rng, 4, 10);
+            assert!((0..4).contains(&relocatable.segment_index));
+            assert!(relocatable.offset < 10);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_from_hex_valid() {
+        assert_eq!(felt_from_hex("0x1f"), Ok(Felt::new(31)));
+        assert_eq!(felt_from_hex("1F"), Ok(Felt::new(31)));
+        assert_eq!(felt_from_hex("0x0000"), Ok(Felt::new(0)));
+        assert_eq!(
+            felt_from_hex("0x800000000000011000000000000000000000000000000000000000000000000"),
+            Ok(Felt::new(-1))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_biguint_round_trip() {
+        let prime = CAIRO_PRIME.clone();
+        for value in [
+            BigUint::zero(),
+            BigUint::one(),
+            BigUint::from(u64::MAX),
+            BigUint::one() << 128_usize,
+            &prime >> 1_usize,
+            &prime - 1_u32,
+        ] {
+            assert_eq!(felt_to_biguint(&biguint_to_felt(value.clone())), value);
+        }
+        assert_eq!(felt_to_biguint(&Felt::new(-1)), &prime - 1_u32);
+        assert_eq!(felt_to_biguint(&Felt::max_value()), &prime - 1_u32);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn biguint_to_felt_reduces_modulo_prime() {
+        let prime = CAIRO_PRIME.clone();
+        assert_eq!(biguint_to_felt(prime.clone()), Felt::zero());
+        assert_eq!(biguint_to_felt(&prime + 1_u32), Felt::one());
+        assert_eq!(biguint_to_felt(&prime * 2_u32 - 1_u32), Felt::new(-1));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_from_hex_malformed() {
+        assert_eq!(
+            felt_from_hex("0x"),
+            Err(FeltParseError::Empty(String::from("0x")))
+        );
+        assert_eq!(felt_from_hex(""), Err(FeltParseError::Empty(String::new())));
+        assert_eq!(
+            felt_from_hex("0x12g4"),
+            Err(FeltParseError::InvalidHexChar(
+                String::from("0x12g4"),
+                'g',
+                4
+            ))
+        );
+        assert_eq!(
+            felt_from_hex("-1"),
+            Err(FeltParseError::InvalidHexChar(String::from("-1"), '-', 0))
+        );
+        assert_eq!(
+            felt_from_hex("0x0x1"),
+            Err(FeltParseError::InvalidHexChar(
+                String::from("0x0x1"),
+                'x',
+                3
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_from_hex_over_range() {
+        // The field prime itself
+        let prime = "0x800000000000011000000000000000000000000000000000000000000000001";
+        assert_eq!(
+            felt_from_hex(prime),
+            Err(FeltParseError::OutOfRange(String::from(prime)))
+        );
+        let too_long = "0x10000000000000000000000000000000000000000000000000000000000000000";
+        assert_eq!(
+            felt_from_hex(too_long),
+            Err(FeltParseError::OutOfRange(String::from(too_long)))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_bit_length_of_small_values() {
+        assert_eq!(felt_bit_length(&Felt::zero()), 0);
+        assert_eq!(felt_bit_length(&Felt::one()), 1);
+        assert_eq!(felt_bit_length(&Felt::new(0xff)), 8);
+        assert_eq!(felt_bit_length(&Felt::new(0x100)), 9);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn felt_bit_length_of_251_bit_values() {
+        assert_eq!(felt_bit_length(&(Felt::one() << 250_u32)), 251);
+        assert_eq!(felt_bit_length(&((Felt::one() << 251_u32) - 1_u32)), 251);
+        // The field prime minus one is the largest value, and takes 252 bits
+        assert_eq!(felt_bit_length(&Felt::max_value()), 252);
+    }
 }
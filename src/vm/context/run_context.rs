@@ -9,6 +9,7 @@ use crate::{
 };
 use num_traits::abs;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct RunContext {
     pub(crate) pc: Relocatable,
     pub(crate) ap: usize,
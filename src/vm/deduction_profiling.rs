@@ -0,0 +1,118 @@
+//! Deduction profiling
+//!
+//! Records how many times each builtin is asked to deduce a memory cell, and how long those
+//! deductions take, to find out which builtin dominates the deduction work of a run.
+//!
+//! Call counts are recorded on every target. Timings need a wall clock, so they are only recorded
+//! with the `std` feature and stay at zero otherwise.
+//!
+//! ```ignore
+//! cairo_runner.run_until_pc(end, &mut vm, &mut hint_processor)?;
+//! for (name, stats) in vm.get_deduction_profile() {
+//!     println!("{name}: {} calls, {:?}", stats.calls, stats.total_time);
+//! }
+//! ```
+
+use crate::stdlib::{collections::HashMap, time::Duration};
+
+use crate::{
+    types::relocatable::{MaybeRelocatable, Relocatable},
+    vm::{
+        errors::runner_errors::RunnerError, runners::builtin_runner::BuiltinRunner,
+        vm_core::VirtualMachine,
+    },
+};
+
+/// Deduction work of a single builtin
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeductionStats {
+    /// Number of calls to the builtin's `deduce_memory_cell`
+    pub calls: usize,
+    /// Time spent in those calls, always zero without the `std` feature
+    pub total_time: Duration,
+}
+
+impl VirtualMachine {
+    /// Returns the deduction work recorded so far, by builtin name
+    pub fn get_deduction_profile(&self) -> HashMap<&'static str, DeductionStats> {
+        self.deduction_profile.borrow().clone()
+    }
+
+    /// Discards the deduction work recorded so far
+    pub fn reset_deduction_profile(&mut self) {
+        self.deduction_profile.get_mut().clear();
+    }
+
+    pub(crate) fn profile_deduction(
+        &self,
+        builtin: &BuiltinRunner,
+        address: Relocatable,
+    ) -> Result<Option<MaybeRelocatable>, RunnerError> {
+        #[cfg(feature = "std")]
+        let start = std::time::Instant::now();
+        let result = builtin.deduce_memory_cell(address, &self.segments.memory);
+        let mut profile = self.deduction_profile.borrow_mut();
+        let stats = profile.entry(builtin.name()).or_default();
+        stats.calls += 1;
+        #[cfg(feature = "std")]
+        {
+            stats.total_time += start.elapsed();
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stdlib::prelude::*;
+    use crate::{
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        types::program::Program,
+        utils::test_utils::*,
+        vm::{
+            runners::{
+                builtin_runner::{HASH_BUILTIN_NAME, OUTPUT_BUILTIN_NAME},
+                cairo_runner::CairoRunner,
+            },
+            vm_core::VirtualMachine,
+        },
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    // Only checks the call counts, so the test doesn't depend on timings
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn count_deductions_of_pedersen_run() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        assert!(vm.get_deduction_profile().is_empty());
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+
+        // Reading the hash output deduces it, as it isn't in memory yet
+        let profile = vm.get_deduction_profile();
+        assert_eq!(profile.keys().collect::<Vec<_>>(), vec![&HASH_BUILTIN_NAME]);
+        assert!(profile[HASH_BUILTIN_NAME].calls >= 1);
+
+        // Verifying the auto deductions deduces every cell set in each builtin segment
+        vm.reset_deduction_profile();
+        vm.verify_auto_deductions().unwrap();
+        let profile = vm.get_deduction_profile();
+        for (name, builtin) in vm.builtin_runners.iter() {
+            let cells = vm.segments.memory.segment_cells(builtin.base()).count();
+            let calls = profile.get(name).map_or(0, |stats| stats.calls);
+            assert_eq!(calls, cells, "{name}");
+        }
+        assert_eq!(profile[OUTPUT_BUILTIN_NAME].calls, 1);
+        assert_eq!(profile[HASH_BUILTIN_NAME].calls, 3);
+    }
+}
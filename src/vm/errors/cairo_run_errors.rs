@@ -25,3 +25,68 @@ pub enum CairoRunError {
     #[error(transparent)]
     VmException(#[from] VmException),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn program_error_into_cairo_run_error() {
+        let error: CairoRunError =
+            ProgramError::EntrypointNotFound("main".to_string(), Vec::new()).into();
+        assert_matches!(
+            error,
+            CairoRunError::Program(ProgramError::EntrypointNotFound(name, _)) if name == "main"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_error_into_cairo_run_error() {
+        let error: CairoRunError =
+            ProgramError::from(std::io::Error::from(std::io::ErrorKind::NotFound)).into();
+        assert_matches!(error, CairoRunError::Program(ProgramError::IO(_)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn vm_error_into_cairo_run_error() {
+        let error: CairoRunError = VirtualMachineError::NoDst.into();
+        assert_matches!(
+            error,
+            CairoRunError::VirtualMachine(VirtualMachineError::NoDst)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn trace_error_into_cairo_run_error() {
+        let error: CairoRunError = TraceError::TraceNotEnabled.into();
+        assert_matches!(error, CairoRunError::Trace(TraceError::TraceNotEnabled));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn runner_error_into_cairo_run_error() {
+        let error: CairoRunError = RunnerError::NoStopPointer("output").into();
+        assert_matches!(
+            error,
+            CairoRunError::Runner(RunnerError::NoStopPointer("output"))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn memory_error_into_cairo_run_error() {
+        let error: CairoRunError = MemoryError::AddressNotRelocatable.into();
+        assert_matches!(
+            error,
+            CairoRunError::MemoryError(MemoryError::AddressNotRelocatable)
+        );
+    }
+}
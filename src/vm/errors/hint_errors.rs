@@ -165,6 +165,8 @@ pub enum HintError {
     NonLeFelt(Felt, Felt),
     #[error("Unknown Hint: {0}")]
     UnknownHint(String),
+    #[error("Hint is not allowed by the hint processor's policy: {0}")]
+    HintNotAllowed(String),
     #[error("Signature hint must point to the signature builtin segment, not {0}.")]
     AddSignatureWrongEcdsaPtr(Relocatable),
     #[error("Signature hint must point to the public key cell, not {0}.")]
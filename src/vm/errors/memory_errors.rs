@@ -18,11 +18,13 @@ pub enum MemoryError {
     UnallocatedSegment(usize, usize),
     #[error("Memory addresses must be relocatable")]
     AddressNotRelocatable,
+    #[error("Can't write to {0}, the maximum segment offset is {1}")]
+    OffsetExceedsMaximum(Relocatable, usize),
     #[error("Range-check validation failed, number {0} is out of valid range [0, {1}]")]
     RangeCheckNumOutOfBounds(Felt, Felt),
     #[error("Range-check validation failed, encountered non-int value at address {0}")]
     RangeCheckFoundNonInt(Relocatable),
-    #[error("Inconsistent memory assignment at address {0:?}. {1:?} != {2:?}")]
+    #[error("Inconsistent memory assignment at address {0}: the cell already holds {1}, attempted to write {2}")]
     InconsistentMemory(MaybeRelocatable, MaybeRelocatable, MaybeRelocatable),
     #[error("Inconsistent Relocation")]
     Relocation,
@@ -91,6 +93,16 @@ pub enum MemoryError {
     ExpectedRelocatable(Relocatable),
     #[error("Unknown memory cell at address {0}")]
     UnknownMemoryCell(Relocatable),
+    #[error(
+        "The segment arena builtin segment has a used size of {0}, smaller than its initial info"
+    )]
+    InvalidUsedSizeSegmentArena(usize),
+    #[error("Public memory references a segment missing from the relocation table")]
+    MalformedPublicMemory,
+    #[error("The memory must be relocated before writing the memory file")]
+    MemoryNotRelocated,
+    #[error("Failed to write the memory file: {0}")]
+    Write(String),
 }
 
 #[derive(Debug, PartialEq, Eq, Error)]
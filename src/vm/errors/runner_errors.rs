@@ -5,7 +5,7 @@ use thiserror::Error;
 #[cfg(all(not(feature = "std"), feature = "alloc"))]
 use thiserror_no_std::Error;
 
-use super::memory_errors::MemoryError;
+use super::{memory_errors::MemoryError, trace_errors::TraceError};
 use crate::types::{
     errors::math_errors::MathError,
     relocatable::{MaybeRelocatable, Relocatable},
@@ -86,6 +86,34 @@ pub enum RunnerError {
     KeccakNoFirstInput,
     #[error("keccak_builtin: Failed to convert input cells to u64 values")]
     KeccakInputCellsNotU64,
+    #[error("keccak_builtin: {0} input cells don't fit in a state of {1} lanes")]
+    KeccakInputExceedsLanes(usize, usize),
     #[error("{0}: Expected integer at address {1}")]
     BuiltinExpectedInteger(&'static str, Relocatable),
+    #[error("poseidon_builtin: Expected the input cell at address {0} to be an integer")]
+    PoseidonInputCellNotInteger(Relocatable),
+    #[error(transparent)]
+    Trace(#[from] TraceError),
+    #[error("Expected the segment pointed by the return fp to be empty")]
+    UnexpectedRetFpSegmentSize,
+    #[error("Expected the segment pointed by the return pc to be empty")]
+    UnexpectedRetPcSegmentSize,
+    #[error("Cairo PIE program hash mismatch: expected {0}, got {1}")]
+    CairoPieProgramHashMismatch(Felt, Felt),
+    #[error("Execution resources don't match the ones claimed by the Cairo PIE")]
+    CairoPieExecutionResourcesMismatch,
+    #[error("Public memory page id {0} is reserved for the main page")]
+    InvalidPublicMemoryPageId(usize),
+    #[error("Public memory page start {0} is not in the {1} segment")]
+    PublicMemoryPageOutOfSegment(Relocatable, &'static str),
+    #[error("Public memory page {0} was already added")]
+    PublicMemoryPageAlreadyExists(usize),
+    #[error("Public memory page {0} includes offset {1}, which is outside the output segment")]
+    PublicMemoryPageOutOfBounds(usize, usize),
+    #[error("Expected the PanicResult variant to be 0 (Ok) or 1 (Err), got {0}")]
+    InvalidPanicResultVariant(Felt),
+    #[error("The program doesn't use the output builtin")]
+    NoOutputBuiltin,
+    #[error("The program doesn't use the segment_arena builtin")]
+    NoSegmentArenaBuiltin,
 }
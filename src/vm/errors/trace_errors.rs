@@ -3,7 +3,9 @@ use thiserror::Error;
 #[cfg(all(not(feature = "std"), feature = "alloc"))]
 use thiserror_no_std::Error;
 
-use crate::vm::errors::memory_errors::MemoryError;
+use crate::stdlib::prelude::*;
+use crate::vm::{errors::memory_errors::MemoryError, trace::trace_entry::RelocatedTraceEntry};
+use felt::Felt;
 
 #[derive(Debug, PartialEq, Error)]
 pub enum TraceError {
@@ -11,10 +13,42 @@ pub enum TraceError {
     TraceNotEnabled,
     #[error("Trace is already relocated")]
     AlreadyRelocated,
+    #[error("Trace is not relocated, either because it isn't enabled or relocate() wasn't called")]
+    TraceNotRelocated,
     #[error("Trace register must be relocatable")]
     RegNotRelocatable,
     #[error("No relocation found for this segment")]
     NoRelocationFound,
     #[error(transparent)]
     MemoryError(#[from] MemoryError),
+    #[error("Failed to write the trace: {0}")]
+    Write(String),
+}
+
+#[derive(Debug, PartialEq, Error)]
+pub enum VerifyError {
+    #[error("The trace is empty")]
+    EmptyTrace,
+    #[error("Expected the trace to start with the registers {:?}, found {:?}", (*.0).0, (*.0).1)]
+    InvalidInitialRegisters(Box<(RelocatedTraceEntry, RelocatedTraceEntry)>),
+    #[error("Expected the last step to reach the end pc {0}, it reaches pc {1}")]
+    EndPcNotReached(usize, usize),
+    #[error("Memory cell {0} doesn't hold the program's bytecode")]
+    ProgramMismatch(usize),
+    #[error("Memory cell {0} is unknown")]
+    UnknownMemoryCell(usize),
+    #[error("Step {0}: an operand address is not a valid memory address")]
+    InvalidAddress(usize),
+    #[error("Step {0}: failed to decode the instruction at pc {1}")]
+    InvalidInstruction(usize, usize),
+    #[error("Step {0}: res is unconstrained for an instruction that uses it")]
+    UnconstrainedRes(usize),
+    #[error("Step {0}: an assert_eq instruction failed, dst is {1} while res is {2}")]
+    DiffAssertValues(usize, Felt, Felt),
+    #[error("Step {0}: a call instruction didn't store the caller's fp")]
+    CantWriteReturnFp(usize),
+    #[error("Step {0}: a call instruction didn't store the return pc")]
+    CantWriteReturnPc(usize),
+    #[error("Step {0}: expected the next registers to be {:?}, found {:?}", (*.1).0, (*.1).1)]
+    InvalidTransition(usize, Box<(RelocatedTraceEntry, RelocatedTraceEntry)>),
 }
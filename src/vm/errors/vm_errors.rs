@@ -91,6 +91,8 @@ pub enum VirtualMachineError {
     NoImm,
     #[error("Execution reached the end of the program. Requested remaining steps: {0}.")]
     EndOfProgram(usize),
+    #[error("Execution reached the maximum number of steps: {0}")]
+    MaxStepsExceeded(usize),
     #[error(transparent)]
     TracerError(#[from] TraceError),
     #[error(transparent)]
@@ -103,6 +105,10 @@ pub enum VirtualMachineError {
     CouldntParsePrime(String),
     #[error("Got an exception while executing a hint: {1}")]
     Hint(usize, Box<HintError>),
+    #[error("The hint witness has no entry left for the hints at pc {0}")]
+    HintWitnessExhausted(Relocatable),
+    #[error("The hints run at pc {0}, but the next hint witness entry was recorded at pc {1}")]
+    HintWitnessPcMismatch(Relocatable, Relocatable),
     #[error("Unexpected Failure")]
     Unexpected,
     #[error("Out of bounds access to builtin segment")]
@@ -117,6 +123,8 @@ pub enum VirtualMachineError {
     Math(#[from] MathError),
     #[error("Failed to write the output builtin content")]
     FailedToWriteOutput,
+    #[error("Ran out of gas, only {0} units were left")]
+    OutOfGas(usize),
     #[error(transparent)]
     Other(anyhow::Error),
 }
@@ -101,7 +101,10 @@ pub fn get_traceback(vm: &VirtualMachine, runner: &CairoRunner) -> Option<String
                 "{}\n",
                 location.to_string_with_content(&format!("(pc=0:{})", traceback_pc.offset))
             )),
-            None => traceback.push_str(&format!("Unknown location (pc=0:{})", traceback_pc.offset)),
+            None => traceback.push_str(&format!(
+                "Unknown location (pc=0:{})\n",
+                traceback_pc.offset
+            )),
         }
     }
     (!traceback.is_empty())
@@ -842,6 +845,38 @@ cairo_programs/bad_programs/bad_range_check.cairo:11:5: (pc=0:6)
         assert_eq!(vm_excepction.to_string(), expected_error_string);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_bad_range_check_without_debug_info_and_check_error_displayed() {
+        let expected_error_string = r#"Error message: Failed range-check
+Error at pc=0:0:
+An ASSERT_EQ instruction failed: 4 != 5.
+Cairo traceback (most recent call last):
+Unknown location (pc=0:29)
+Unknown location (pc=0:21)
+Unknown location (pc=0:17)
+Unknown location (pc=0:6)
+"#;
+        let mut program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/bad_programs/bad_range_check.json"),
+            Some("main"),
+        )
+        .unwrap();
+        program.instruction_locations = None;
+
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program, "all", false);
+        let mut vm = vm!();
+
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let error = cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap_err();
+        let vm_excepction = VmException::from_vm_error(&cairo_runner, &vm, error);
+        assert_eq!(vm_excepction.inst_location, None);
+        assert_eq!(vm_excepction.to_string(), expected_error_string);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn run_bad_usort_and_check_error_displayed() {
@@ -0,0 +1,398 @@
+//! Hint witness
+//!
+//! Hints are where the nondeterminism of a Cairo run lives: the index returned by `find_element`,
+//! the root picked by `sqrt`, the segments allocated by `alloc`... The hint witness records the
+//! effects the hints of each pc had on the [VirtualMachine], so that a later run can replay them
+//! instead of running the hints, and is guaranteed to make the same choices.
+//! Besides the memory, the witness holds the builtin state set by hints, i.e. the signatures
+//! added to the ecdsa builtin.
+//!
+//! Recording is opt-in, through [VirtualMachine::record_hint_witness], as it copies every cell
+//! written by the hints. Setting a witness before the run switches the [VirtualMachine] to replay
+//! mode, where the hints are never passed to the hint processor.
+//!
+//! ```ignore
+//! vm.record_hint_witness();
+//! cairo_runner.run_until_pc(end, &mut vm, &mut hint_processor)?;
+//! let witness = vm.get_hint_witness().unwrap().clone();
+//!
+//! // Somewhere else, with the same program
+//! other_vm.set_hint_witness(witness);
+//! other_runner.run_until_pc(end, &mut other_vm, &mut hint_processor)?;
+//! ```
+//!
+//! Changes made by the hints to the execution scopes aren't part of the witness, so a run must
+//! replay the witness from start to end.
+
+use crate::stdlib::{any::Any, collections::HashMap, prelude::*};
+
+use crate::{
+    hint_processor::hint_processor_definition::HintProcessor,
+    types::{
+        exec_scope::ExecutionScopes,
+        relocatable::{MaybeRelocatable, Relocatable},
+    },
+    vm::{
+        errors::vm_errors::VirtualMachineError, runners::builtin_runner::BuiltinRunner,
+        vm_core::VirtualMachine,
+    },
+};
+use felt::Felt;
+use serde::{Deserialize, Serialize};
+
+/// Effects of the hints run at a single pc
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintWitnessEntry {
+    /// Pc of the hints
+    pub pc: Relocatable,
+    /// Number of segments added by the hints
+    pub segments: usize,
+    /// Number of temporary segments added by the hints
+    pub temporary_segments: usize,
+    /// Memory cells written by the hints, in order
+    pub writes: Vec<(Relocatable, MaybeRelocatable)>,
+    /// Relocation rules added by the hints
+    pub relocation_rules: Vec<(Relocatable, Relocatable)>,
+    /// (r, s) signatures added to the ecdsa builtin by the hints, by public key address
+    pub signatures: Vec<(Relocatable, (Felt, Felt))>,
+    /// Whether the hints skipped the execution of the instruction at `pc`
+    pub skip_instruction: bool,
+}
+
+/// Effects of every hint run so far, in order
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintWitness {
+    pub entries: Vec<HintWitnessEntry>,
+}
+
+impl VirtualMachine {
+    /// Starts recording the hint witness, dropping any witness recorded so far
+    pub fn record_hint_witness(&mut self) {
+        self.hint_witness = Some(HintWitness::default());
+    }
+
+    /// Returns the hint witness recorded so far, or None if recording wasn't enabled through
+    /// [record_hint_witness](Self::record_hint_witness)
+    pub fn get_hint_witness(&self) -> Option<&HintWitness> {
+        self.hint_witness.as_ref()
+    }
+
+    /// Replays `witness` instead of running the hints from now on. If recording is enabled, the
+    /// replayed entries are recorded as well.
+    pub fn set_hint_witness(&mut self, witness: HintWitness) {
+        self.hint_witness_replay = Some((witness, 0));
+    }
+
+    pub(crate) fn witness_hints(
+        &mut self,
+        hint_executor: &mut dyn HintProcessor,
+        exec_scopes: &mut ExecutionScopes,
+        hint_list: &[Box<dyn Any>],
+        constants: &HashMap<String, Felt>,
+    ) -> Result<(), VirtualMachineError> {
+        let entry = match self.hint_witness_replay {
+            Some((ref replay, ref mut replayed)) => {
+                let pc = self.run_context.pc;
+                let entry = replay
+                    .entries
+                    .get(*replayed)
+                    .cloned()
+                    .ok_or(VirtualMachineError::HintWitnessExhausted(pc))?;
+                if entry.pc != pc {
+                    return Err(VirtualMachineError::HintWitnessPcMismatch(pc, entry.pc));
+                }
+                *replayed += 1;
+                self.replay_hint_witness_entry(&entry)?;
+                entry
+            }
+            None if self.hint_witness.is_some() => {
+                self.record_hint_witness_entry(hint_executor, exec_scopes, hint_list, constants)?
+            }
+            None => return self.execute_hints(hint_executor, exec_scopes, hint_list, constants),
+        };
+        if let Some(hint_witness) = self.hint_witness.as_mut() {
+            hint_witness.entries.push(entry);
+        }
+        Ok(())
+    }
+
+    // Signatures of the ecdsa builtin, if the program uses it
+    fn builtin_signatures(&self) -> HashMap<Relocatable, (Felt, Felt)> {
+        self.builtin_runners
+            .iter()
+            .find_map(|(_, builtin)| match builtin {
+                BuiltinRunner::Signature(signature_builtin) => {
+                    Some(signature_builtin.get_signatures())
+                }
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn record_hint_witness_entry(
+        &mut self,
+        hint_executor: &mut dyn HintProcessor,
+        exec_scopes: &mut ExecutionScopes,
+        hint_list: &[Box<dyn Any>],
+        constants: &HashMap<String, Felt>,
+    ) -> Result<HintWitnessEntry, VirtualMachineError> {
+        let pc = self.run_context.pc;
+        let segments = self.segments.num_segments();
+        let temporary_segments = self.segments.num_temp_segments();
+        let relocation_rules_before = self.segments.memory.relocation_rules.clone();
+        let signatures_before = self.builtin_signatures();
+
+        self.segments.memory.hint_writes = Some(Vec::new());
+        let result = self.execute_hints(hint_executor, exec_scopes, hint_list, constants);
+        let writes = self.segments.memory.hint_writes.take().unwrap_or_default();
+        result?;
+
+        // Relocation rules can't be overwritten, so the new ones are those with a new key
+        let mut relocation_rules: Vec<_> = self
+            .segments
+            .memory
+            .relocation_rules
+            .iter()
+            .filter(|(key, _)| !relocation_rules_before.contains_key(key))
+            .map(|(key, dst_ptr)| (*key, *dst_ptr))
+            .collect();
+        relocation_rules.sort_by_key(|(key, _)| *key);
+
+        // Signatures can't be overwritten either
+        let mut signatures: Vec<_> = self
+            .builtin_signatures()
+            .into_iter()
+            .filter(|(addr, _)| !signatures_before.contains_key(addr))
+            .collect();
+        signatures.sort_by_key(|(addr, _)| (addr.segment_index, addr.offset));
+
+        Ok(HintWitnessEntry {
+            pc,
+            segments: self.segments.num_segments() - segments,
+            temporary_segments: self.segments.num_temp_segments() - temporary_segments,
+            writes,
+            relocation_rules: relocation_rules
+                .into_iter()
+                .map(|(key, dst_ptr)| (Relocatable::from((-(key as isize) - 1, 0)), dst_ptr))
+                .collect(),
+            signatures,
+            skip_instruction: self.skip_instruction_execution,
+        })
+    }
+
+    fn replay_hint_witness_entry(
+        &mut self,
+        entry: &HintWitnessEntry,
+    ) -> Result<(), VirtualMachineError> {
+        for _ in 0..entry.segments {
+            self.segments.add();
+        }
+        for _ in 0..entry.temporary_segments {
+            self.segments.add_temporary_segment();
+        }
+        for (addr, signature) in entry.signatures.iter() {
+            self.get_signature_builtin()?
+                .add_signature(*addr, signature)?;
+        }
+        for (addr, value) in entry.writes.iter() {
+            self.segments.memory.insert(addr, value)?;
+        }
+        for (src_ptr, dst_ptr) in entry.relocation_rules.iter() {
+            self.segments
+                .memory
+                .add_relocation_rule(*src_ptr, *dst_ptr)?;
+        }
+        if entry.skip_instruction {
+            self.skip_next_instruction_execution();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        types::program::Program,
+        utils::test_utils::*,
+        vm::{errors::hint_errors::HintError, runners::cairo_runner::CairoRunner},
+    };
+    use assert_matches::assert_matches;
+    use felt::felt_str;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    // Fails on every hint, so a run only succeeds if no hint reaches the hint processor
+    struct NoHintProcessor;
+
+    impl HintProcessor for NoHintProcessor {
+        fn execute_hint(
+            &mut self,
+            _vm: &mut VirtualMachine,
+            _exec_scopes: &mut ExecutionScopes,
+            _hint_data: &Box<dyn Any>,
+            _constants: &HashMap<String, Felt>,
+        ) -> Result<(), HintError> {
+            Err(HintError::UnknownHint(String::from("replayed hint")))
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn replay_find_element_witness() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/find_element.json"),
+            Some("main"),
+        )
+        .unwrap();
+
+        // Record the witness of a regular run
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        vm.record_hint_witness();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+        cairo_runner.relocate(&mut vm, true).unwrap();
+        let witness = vm.get_hint_witness().unwrap().clone();
+        assert!(!witness.entries.is_empty());
+        // `alloc` adds a segment, `find_element` writes the index it picked
+        assert!(witness.entries.iter().any(|entry| entry.segments == 1));
+        assert!(witness.entries.iter().any(|entry| !entry.writes.is_empty()));
+
+        // Replay it with a hint processor that can't run any hint
+        let mut replay_runner = cairo_runner!(program);
+        let mut replay_vm = vm!(true);
+        replay_vm.record_hint_witness();
+        replay_vm.set_hint_witness(witness.clone());
+        let end = replay_runner.initialize(&mut replay_vm).unwrap();
+        replay_runner
+            .run_until_pc(end, &mut replay_vm, &mut NoHintProcessor)
+            .unwrap();
+        replay_runner.relocate(&mut replay_vm, true).unwrap();
+
+        assert_eq!(replay_vm.get_hint_witness(), Some(&witness));
+        assert_eq!(replay_runner.relocated_trace, cairo_runner.relocated_trace);
+        assert_eq!(
+            replay_runner.relocated_memory,
+            cairo_runner.relocated_memory
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn replay_signature_witness() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/common_signature.json"),
+            Some("main"),
+        )
+        .unwrap();
+
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        vm.record_hint_witness();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+        let witness = vm.get_hint_witness().unwrap().clone();
+        // The verify_ecdsa_signature hint only adds a signature to the builtin
+        let signature = (
+            felt_str!(
+                "3086480810278599376317923499561306189851900463386393948998357832163236918254"
+            ),
+            felt_str!(
+                "598673427589502599949712887611119751108407514580626464031881322743364689811"
+            ),
+        );
+        assert_eq!(
+            witness
+                .entries
+                .iter()
+                .flat_map(|entry| entry.signatures.iter())
+                .collect::<Vec<_>>(),
+            vec![&(Relocatable::from((2, 0)), signature)]
+        );
+
+        // Without the signature, the ecdsa builtin would reject the public key written to it
+        let mut replay_runner = cairo_runner!(program);
+        let mut replay_vm = vm!();
+        replay_vm.set_hint_witness(witness);
+        let end = replay_runner.initialize(&mut replay_vm).unwrap();
+        replay_runner
+            .run_until_pc(end, &mut replay_vm, &mut NoHintProcessor)
+            .unwrap();
+        assert_eq!(
+            replay_vm.get_signature_builtin().unwrap().get_signatures(),
+            vm.get_signature_builtin().unwrap().get_signatures()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn hint_witness_not_recorded_by_default() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/find_element.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+        assert_eq!(vm.get_hint_witness(), None);
+        assert!(vm.segments.memory.hint_writes.is_none());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn replay_exhausted_witness() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/find_element.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        vm.set_hint_witness(HintWitness::default());
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        assert_matches!(
+            cairo_runner.run_until_pc(end, &mut vm, &mut NoHintProcessor),
+            Err(VirtualMachineError::HintWitnessExhausted(_))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn replay_witness_at_wrong_pc() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/find_element.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        vm.set_hint_witness(HintWitness {
+            entries: vec![HintWitnessEntry {
+                pc: (0, 1000).into(),
+                ..Default::default()
+            }],
+        });
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        assert_matches!(
+            cairo_runner.run_until_pc(end, &mut vm, &mut NoHintProcessor),
+            Err(VirtualMachineError::HintWitnessPcMismatch(
+                _,
+                Relocatable {
+                    segment_index: 0,
+                    offset: 1000
+                }
+            ))
+        );
+    }
+}
@@ -8,13 +8,18 @@
 //! - before_first_step, executed before entering the execution loop in [run_until_pc](CairoRunner::run_until_pc)
 //! - pre_step_instruction, executed before each instruction_step in [step](VirtualMachine::step)
 //! - post_step_instruction, executed after each instruction_step in [step](VirtualMachine::step)
+//!
+//! A breakpoint can also be set, pausing [run_until_pc_or_breakpoint](CairoRunner::run_until_pc_or_breakpoint)
+//! before the instructions it matches. The before_first_step hook of such a run is executed once,
+//! by [start_run_until_pc_or_breakpoint](CairoRunner::start_run_until_pc_or_breakpoint).
 
 use crate::stdlib::{any::Any, collections::HashMap, prelude::*, sync::Arc};
 
 use felt::Felt;
 
 use crate::{
-    hint_processor::hint_processor_definition::HintProcessor, types::exec_scope::ExecutionScopes,
+    hint_processor::hint_processor_definition::HintProcessor,
+    types::{exec_scope::ExecutionScopes, relocatable::Relocatable},
 };
 
 use super::{
@@ -44,6 +49,8 @@ type StepHookFunc = Arc<
         + Send,
 >;
 
+type BreakpointFunc = Arc<dyn Fn(&VirtualMachine) -> bool + Sync + Send>;
+
 /// The hooks to be executed during the VM run
 ///
 /// They can be individually ignored by setting them to [None]
@@ -52,6 +59,26 @@ pub struct Hooks {
     before_first_step: Option<BeforeFirstStepHookFunc>,
     pre_step_instruction: Option<StepHookFunc>,
     post_step_instruction: Option<StepHookFunc>,
+    breakpoint: Option<BreakpointFunc>,
+}
+
+/// Outcome of [run_until_pc_or_breakpoint](CairoRunner::run_until_pc_or_breakpoint)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunStatus {
+    /// The pc reached the requested address
+    Finished,
+    /// The breakpoint matched the VM state, the instruction at the current pc wasn't executed yet
+    Paused,
+}
+
+/// A run that can be paused by the breakpoint, started by
+/// [start_run_until_pc_or_breakpoint](CairoRunner::start_run_until_pc_or_breakpoint)
+pub struct BreakpointRun {
+    pub(crate) address: Relocatable,
+    pub(crate) hint_data_dictionary: HashMap<usize, Vec<Box<dyn Any>>>,
+    pub(crate) constants: HashMap<String, Felt>,
+    // Set when the run paused, so resuming it doesn't stop at the same instruction again
+    pub(crate) paused: bool,
 }
 
 impl Hooks {
@@ -64,8 +91,15 @@ impl Hooks {
             before_first_step,
             pre_step_instruction,
             post_step_instruction,
+            breakpoint: None,
         }
     }
+
+    /// Sets a breakpoint, evaluated before each instruction: the run pauses whenever it returns true
+    pub fn with_breakpoint(mut self, breakpoint: BreakpointFunc) -> Self {
+        self.breakpoint = Some(breakpoint);
+        self
+    }
 }
 
 impl VirtualMachine {
@@ -81,6 +115,13 @@ impl VirtualMachine {
         Ok(())
     }
 
+    pub fn hit_breakpoint(&self) -> bool {
+        self.hooks
+            .breakpoint
+            .as_ref()
+            .map_or(false, |breakpoint| (breakpoint)(self))
+    }
+
     pub fn execute_pre_step_instruction(
         &mut self,
         hint_executor: &mut dyn HintProcessor,
@@ -127,9 +168,10 @@ mod tests {
     use super::*;
     use crate::{
         hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
-        types::program::Program,
+        types::{program::Program, relocatable::Relocatable},
         utils::test_utils::{cairo_runner, vm},
     };
+    use assert_matches::assert_matches;
 
     #[test]
     fn empty_hooks() {
@@ -270,4 +312,87 @@ mod tests {
             .run_until_pc(end, &mut vm, &mut hint_processor)
             .is_ok());
     }
+
+    #[test]
+    fn breakpoint_pauses_and_resumes() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let fib_pc = Relocatable::from((0, program.identifiers["__main__.fib"].pc.unwrap()));
+
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        let expected_trace = vm.trace.unwrap();
+        let fib_calls: Vec<_> = expected_trace
+            .iter()
+            .filter(|entry| entry.pc == fib_pc)
+            .collect();
+        // fib(1, 1, 10) recurses down to n = 0
+        assert_eq!(fib_calls.len(), 11);
+
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        vm.hooks = Hooks::default().with_breakpoint(Arc::new(move |vm| vm.get_pc() == fib_pc));
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let mut run = cairo_runner
+            .start_run_until_pc_or_breakpoint(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        for fib_call in fib_calls {
+            assert_matches!(
+                cairo_runner.run_until_pc_or_breakpoint(&mut run, &mut vm, &mut hint_processor),
+                Ok(RunStatus::Paused)
+            );
+            assert_eq!(vm.get_pc(), fib_call.pc);
+            assert_eq!(vm.get_ap(), fib_call.ap);
+            assert_eq!(vm.get_fp(), fib_call.fp);
+        }
+        assert_matches!(
+            cairo_runner.run_until_pc_or_breakpoint(&mut run, &mut vm, &mut hint_processor),
+            Ok(RunStatus::Finished)
+        );
+        assert_eq!(vm.trace, Some(expected_trace));
+    }
+
+    #[test]
+    fn breakpoint_at_initial_pc() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let (initial_pc, initial_ap, initial_fp) = (vm.get_pc(), vm.get_ap(), vm.get_fp());
+        vm.hooks = Hooks::default().with_breakpoint(Arc::new(move |vm| vm.get_pc() == initial_pc));
+        let mut run = cairo_runner
+            .start_run_until_pc_or_breakpoint(end, &mut vm, &mut hint_processor)
+            .unwrap();
+
+        // The run stops before executing its first instruction
+        assert_matches!(
+            cairo_runner.run_until_pc_or_breakpoint(&mut run, &mut vm, &mut hint_processor),
+            Ok(RunStatus::Paused)
+        );
+        assert_eq!(vm.get_pc(), initial_pc);
+        assert_eq!(vm.get_ap(), initial_ap);
+        assert_eq!(vm.get_fp(), initial_fp);
+        assert_eq!(vm.get_current_step(), 0);
+
+        // main isn't called again, so the run finishes once resumed
+        assert_matches!(
+            cairo_runner.run_until_pc_or_breakpoint(&mut run, &mut vm, &mut hint_processor),
+            Ok(RunStatus::Finished)
+        );
+        assert_eq!(vm.get_pc(), end);
+    }
 }
@@ -1,6 +1,12 @@
 pub mod context;
 pub mod decoding;
+#[cfg(feature = "deduction_profiling")]
+#[cfg_attr(docsrs, doc(cfg(feature = "deduction_profiling")))]
+pub mod deduction_profiling;
 pub mod errors;
+#[cfg(feature = "hint_witness")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hint_witness")))]
+pub mod hint_witness;
 pub mod runners;
 pub mod security;
 pub mod trace;
@@ -10,3 +16,7 @@ pub mod vm_memory;
 #[cfg(any(feature = "hooks"))]
 #[cfg_attr(docsrs, doc(cfg(feature = "hooks")))]
 pub mod hooks;
+
+#[cfg(feature = "step_sampling")]
+#[cfg_attr(docsrs, doc(cfg(feature = "step_sampling")))]
+pub mod step_sampling;
@@ -1,6 +1,7 @@
 use crate::stdlib::vec::Vec;
 use crate::{
-    math_utils::safe_div_usize,
+    air_private_input::{PrivateInput, PrivateInputPair},
+    math_utils::{div_ceil, safe_div_usize},
     types::{
         instance_definitions::bitwise_instance_def::{
             BitwiseInstanceDef, CELLS_PER_BITWISE, INPUT_CELLS_PER_BITWISE,
@@ -16,7 +17,6 @@ use crate::{
         vm_memory::{memory::Memory, memory_segments::MemorySegmentManager},
     },
 };
-use num_integer::div_ceil;
 
 use super::BITWISE_BUILTIN_NAME;
 
@@ -33,7 +33,7 @@ pub struct BitwiseBuiltinRunner {
 }
 
 impl BitwiseBuiltinRunner {
-    pub(crate) fn new(instance_def: &BitwiseInstanceDef, included: bool) -> Self {
+    pub fn new(instance_def: &BitwiseInstanceDef, included: bool) -> Self {
         BitwiseBuiltinRunner {
             base: 0,
             ratio: instance_def.ratio,
@@ -160,6 +160,10 @@ impl BitwiseBuiltinRunner {
         }
     }
 
+    /// Returns the number of diluted check units used by a single bitwise instance.
+    /// The `total_n_bits` of each input are partitioned into chunks of `diluted_spacing` bits
+    /// every `diluted_spacing * diluted_n_bits` bits, each chunk costing four units, plus an
+    /// extra unit for every chunk that doesn't fit in the remaining bits.
     pub fn get_used_diluted_check_units(&self, diluted_spacing: u32, diluted_n_bits: u32) -> usize {
         let total_n_bits = self.bitwise_builtin.total_n_bits;
         let mut partition = Vec::with_capacity(total_n_bits as usize);
@@ -190,7 +194,7 @@ impl BitwiseBuiltinRunner {
                 .memory
                 .get_relocatable(stop_pointer_addr)
                 .map_err(|_| RunnerError::NoStopPointer(BITWISE_BUILTIN_NAME))?;
-            if self.base as isize != stop_pointer.segment_index {
+            if !stop_pointer.in_segment(self.base) {
                 return Err(RunnerError::InvalidStopPointerIndex(
                     BITWISE_BUILTIN_NAME,
                     stop_pointer,
@@ -221,7 +225,30 @@ impl BitwiseBuiltinRunner {
         segments: &MemorySegmentManager,
     ) -> Result<usize, MemoryError> {
         let used_cells = self.get_used_cells(segments)?;
-        Ok(div_ceil(used_cells, self.cells_per_instance as usize))
+        Ok(div_ceil(used_cells, self.cells_per_instance as usize)?)
+    }
+
+    /// Returns the inputs of each bitwise instance, as expected by the prover.
+    /// Instances missing one of their inputs are skipped rather than reported as an error, like
+    /// the Python VM does: the builtin can't have deduced their outputs, so no instruction used them.
+    pub fn air_private_input(&self, memory: &Memory) -> Vec<PrivateInput> {
+        let cells_per_instance = self.cells_per_instance as usize;
+        let n_instances =
+            div_ceil(memory.segment_len(self.base), cells_per_instance).unwrap_or_default();
+        (0..n_instances)
+            .filter_map(|index| {
+                let input_addr =
+                    Relocatable::from((self.base as isize, index * cells_per_instance));
+                Some(PrivateInput::Pair(PrivateInputPair {
+                    index,
+                    x: memory.get_integer(input_addr).ok()?.into_owned(),
+                    y: memory
+                        .get_integer((input_addr + 1_usize).ok()?)
+                        .ok()?
+                        .into_owned(),
+                }))
+            })
+            .collect()
     }
 }
 
@@ -499,6 +526,68 @@ mod tests {
         assert_eq!(result, Ok(None));
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deduce_memory_cell_bitwise_64_bit_instance() {
+        let builtin = BitwiseBuiltinRunner::new(&BitwiseInstanceDef::new_with_bits(256, 64), true);
+        let max_64_bit = u64::MAX;
+        let memory = memory![((0, 5), max_64_bit), ((0, 6), 12), ((0, 7), 0)];
+        assert_eq!(
+            builtin.deduce_memory_cell(Relocatable::from((0, 7)), &memory),
+            Ok(Some(MaybeRelocatable::from(Felt::new(12))))
+        );
+
+        let too_big = 1_u128 << 64;
+        let memory = memory![((0, 5), too_big), ((0, 6), 12), ((0, 7), 0)];
+        assert_eq!(
+            builtin.deduce_memory_cell(Relocatable::from((0, 7)), &memory),
+            Err(RunnerError::IntegerBiggerThanPowerOfTwo(
+                (0, 5).into(),
+                64,
+                Felt::new(too_big)
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn air_private_input() {
+        let builtin = BitwiseBuiltinRunner::new(&BitwiseInstanceDef::default(), true);
+        let memory = memory![
+            ((0, 0), 12),
+            ((0, 1), 10),
+            ((0, 2), 8),
+            ((0, 3), 14),
+            ((0, 4), 6),
+            ((0, 5), 7),
+            ((0, 7), 0)
+        ];
+        assert_eq!(
+            builtin.air_private_input(&memory),
+            vec![PrivateInput::Pair(PrivateInputPair {
+                index: 0,
+                x: Felt::new(12),
+                y: Felt::new(10),
+            })]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn air_private_input_skips_incomplete_instances() {
+        let builtin = BitwiseBuiltinRunner::new(&BitwiseInstanceDef::default(), true);
+        // Instance 0 lacks its x input, instance 1 its y input, only instance 2 is complete
+        let memory = memory![((0, 1), 10), ((0, 5), 7), ((0, 10), 3), ((0, 11), 5)];
+        assert_eq!(
+            builtin.air_private_input(&memory),
+            vec![PrivateInput::Pair(PrivateInputPair {
+                index: 2,
+                x: Felt::new(3),
+                y: Felt::new(5),
+            })]
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_memory_segment_addresses() {
@@ -626,4 +715,19 @@ mod tests {
         ));
         assert_eq!(builtin.get_used_diluted_check_units(50, 25), 250);
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_used_diluted_check_units_default_instance() {
+        let builtin = BitwiseBuiltinRunner::new(&BitwiseInstanceDef::default(), true);
+        assert_eq!(builtin.get_used_diluted_check_units(4, 16), 68);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_used_diluted_check_units_8_bit_instance() {
+        let builtin = BitwiseBuiltinRunner::new(&BitwiseInstanceDef::new_with_bits(8, 8), true);
+        assert_eq!(builtin.get_used_diluted_check_units(4, 16), 20);
+        assert_eq!(builtin.get_used_diluted_check_units(1, 4), 8);
+    }
 }
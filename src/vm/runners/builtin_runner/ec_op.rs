@@ -1,6 +1,6 @@
 use crate::stdlib::{borrow::Cow, prelude::*};
 
-use crate::math_utils::{ec_add, ec_double, safe_div_usize};
+use crate::math_utils::{div_ceil, ec_add, ec_double, safe_div_usize, STARK_CURVE_ALPHA};
 use crate::types::instance_definitions::ec_op_instance_def::{
     EcOpInstanceDef, CELLS_PER_EC_OP, INPUT_CELLS_PER_EC_OP,
 };
@@ -12,7 +12,7 @@ use crate::vm::vm_memory::memory::Memory;
 use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
 use felt::Felt;
 use num_bigint::{BigInt, ToBigInt};
-use num_integer::{div_ceil, Integer};
+use num_integer::Integer;
 use num_traits::{Num, One, Pow, Zero};
 
 use super::EC_OP_BUILTIN_NAME;
@@ -141,7 +141,7 @@ impl EcOpBuiltinRunner {
         //Constant values declared here
         const EC_POINT_INDICES: [(usize, usize); 3] = [(0, 1), (2, 3), (5, 6)];
         const OUTPUT_INDICES: (usize, usize) = EC_POINT_INDICES[2];
-        let alpha: Felt = Felt::one();
+        let alpha: Felt = Felt::from(STARK_CURVE_ALPHA);
         let beta_low: Felt = Felt::new(0x609ad26c15c915c1f4cdfcb99cee9e89_u128);
         let beta_high: Felt = Felt::new(0x6f21413efbe40de150e596d72f7a8c5_u128);
         let beta: Felt = (beta_high << 128_usize) + beta_low;
@@ -266,7 +266,7 @@ impl EcOpBuiltinRunner {
         segments: &MemorySegmentManager,
     ) -> Result<usize, MemoryError> {
         let used_cells = self.get_used_cells(segments)?;
-        Ok(div_ceil(used_cells, self.cells_per_instance as usize))
+        Ok(div_ceil(used_cells, self.cells_per_instance as usize)?)
     }
 
     pub fn final_stack(
@@ -281,7 +281,7 @@ impl EcOpBuiltinRunner {
                 .memory
                 .get_relocatable(stop_pointer_addr)
                 .map_err(|_| RunnerError::NoStopPointer(EC_OP_BUILTIN_NAME))?;
-            if self.base as isize != stop_pointer.segment_index {
+            if !stop_pointer.in_segment(self.base) {
                 return Err(RunnerError::InvalidStopPointerIndex(
                     EC_OP_BUILTIN_NAME,
                     stop_pointer,
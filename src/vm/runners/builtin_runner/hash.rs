@@ -1,18 +1,18 @@
 use crate::stdlib::{cell::RefCell, prelude::*};
 
-use crate::math_utils::safe_div_usize;
+use crate::air_private_input::PedersenPrivateInput;
+use crate::math_utils::{div_ceil, pedersen_hash, safe_div_usize};
 use crate::types::instance_definitions::pedersen_instance_def::{
     CELLS_PER_HASH, INPUT_CELLS_PER_HASH,
 };
 use crate::types::relocatable::{MaybeRelocatable, Relocatable};
 use crate::vm::errors::memory_errors::{InsufficientAllocatedCellsError, MemoryError};
 use crate::vm::errors::runner_errors::RunnerError;
+use crate::vm::runners::cairo_pie::BuiltinAdditionalData;
 use crate::vm::vm_core::VirtualMachine;
 use crate::vm::vm_memory::memory::Memory;
 use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
-use felt::Felt;
-use num_integer::{div_ceil, Integer};
-use starknet_crypto::{pedersen_hash, FieldElement};
+use num_integer::Integer;
 
 use super::EC_OP_BUILTIN_NAME;
 
@@ -94,21 +94,8 @@ impl HashBuiltinRunner {
         ) {
             self.verified_addresses.borrow_mut().push(address);
 
-            //Convert MaybeRelocatable to FieldElement
-            let a_string = num_a.to_str_radix(10);
-            let b_string = num_b.to_str_radix(10);
-            let (y, x) = match (
-                FieldElement::from_dec_str(&a_string),
-                FieldElement::from_dec_str(&b_string),
-            ) {
-                (Ok(field_element_a), Ok(field_element_b)) => (field_element_a, field_element_b),
-                _ => return Err(RunnerError::FailedStringConversion),
-            };
-            //Compute pedersen Hash
-            let fe_result = pedersen_hash(&x, &y);
-            //Convert result from FieldElement to MaybeRelocatable
-            let r_byte_slice = fe_result.to_bytes_be();
-            let result = Felt::from_bytes_be(&r_byte_slice);
+            // num_b is the first input cell of the instance and num_a the second one
+            let result = pedersen_hash(num_b, num_a);
             return Ok(Some(MaybeRelocatable::from(result)));
         }
         Ok(None)
@@ -124,6 +111,10 @@ impl HashBuiltinRunner {
         (self.base, self.stop_ptr)
     }
 
+    pub fn get_additional_data(&self) -> BuiltinAdditionalData {
+        BuiltinAdditionalData::Hash(self.verified_addresses.borrow().clone())
+    }
+
     pub fn get_used_cells(&self, segments: &MemorySegmentManager) -> Result<usize, MemoryError> {
         segments
             .get_segment_used_size(self.base())
@@ -168,7 +159,28 @@ impl HashBuiltinRunner {
         segments: &MemorySegmentManager,
     ) -> Result<usize, MemoryError> {
         let used_cells = self.get_used_cells(segments)?;
-        Ok(div_ceil(used_cells, self.cells_per_instance as usize))
+        Ok(div_ceil(used_cells, self.cells_per_instance as usize)?)
+    }
+
+    /// Returns the hashed pair of each instance with both of its input cells set
+    pub fn air_private_input(&self, memory: &Memory) -> Vec<PedersenPrivateInput> {
+        let cells_per_instance = self.cells_per_instance as usize;
+        let n_instances =
+            div_ceil(memory.segment_len(self.base), cells_per_instance).unwrap_or_default();
+        (0..n_instances)
+            .filter_map(|index| {
+                let input_addr =
+                    Relocatable::from((self.base as isize, index * cells_per_instance));
+                Some(PedersenPrivateInput {
+                    index,
+                    x: memory.get_integer(input_addr).ok()?.into_owned(),
+                    y: memory
+                        .get_integer((input_addr + 1_usize).ok()?)
+                        .ok()?
+                        .into_owned(),
+                })
+            })
+            .collect()
     }
 
     pub fn final_stack(
@@ -183,7 +195,7 @@ impl HashBuiltinRunner {
                 .memory
                 .get_relocatable(stop_pointer_addr)
                 .map_err(|_| RunnerError::NoStopPointer(EC_OP_BUILTIN_NAME))?;
-            if self.base as isize != stop_pointer.segment_index {
+            if !stop_pointer.in_segment(self.base) {
                 return Err(RunnerError::InvalidStopPointerIndex(
                     EC_OP_BUILTIN_NAME,
                     stop_pointer,
@@ -224,7 +236,8 @@ mod tests {
         errors::memory_errors::MemoryError, runners::builtin_runner::BuiltinRunner,
         vm_core::VirtualMachine,
     };
-    use felt::felt_str;
+    use felt::{felt_str, Felt};
+    use num_traits::Zero;
 
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::*;
@@ -240,6 +253,59 @@ mod tests {
         assert_eq!(builtin.get_used_instances(&vm.segments), Ok(1));
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn air_private_input() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+
+        let builtin = vm
+            .builtin_runners
+            .iter()
+            .find_map(|(_, builtin)| builtin.as_hash())
+            .unwrap();
+        // pedersen_test hashes (0, 0) once
+        assert_eq!(
+            builtin.air_private_input(&vm.segments.memory),
+            vec![PedersenPrivateInput {
+                index: 0,
+                x: Felt::zero(),
+                y: Felt::zero(),
+            }]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn air_private_input_skips_unset_instances() {
+        let builtin = HashBuiltinRunner::new(10, true);
+        let memory = memory![
+            ((0, 0), 1),
+            ((0, 1), 2),
+            ((0, 2), 3),
+            ((0, 3), 4),
+            ((0, 7), 5)
+        ];
+        assert_eq!(
+            builtin.air_private_input(&memory),
+            vec![PedersenPrivateInput {
+                index: 0,
+                x: Felt::new(1),
+                y: Felt::new(2),
+            }]
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn final_stack() {
@@ -449,6 +515,47 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deduce_memory_cell_pedersen_known_vectors() {
+        // h(0, 0) and the test vector of starknet-crypto
+        let vectors = [
+            (
+                Felt::zero(),
+                Felt::zero(),
+                felt_str!(
+                    "49ee3eba8c1600700ee1b87eb599f16716b0b1022947733551fde4050ca6804",
+                    16
+                ),
+            ),
+            (
+                felt_str!(
+                    "3d937c035c878245caf64531a5756109c53068da139362728feb561405371cb",
+                    16
+                ),
+                felt_str!(
+                    "208a0a10250e382e1e4bbe2880906c2791bf6275695e02fbbc6aeff9cd8b31a",
+                    16
+                ),
+                felt_str!(
+                    "30e480bed5fe53fa909cc0f8c4d99b8f9f2c016be4c41e13a4848797979c662",
+                    16
+                ),
+            ),
+        ];
+        for (a, b, expected) in vectors {
+            let mut memory = Memory::new();
+            memory.data.push(Vec::new());
+            memory.insert_value((0, 0).into(), a).unwrap();
+            memory.insert_value((0, 1).into(), b).unwrap();
+            let builtin = HashBuiltinRunner::new(8, true);
+            assert_eq!(
+                builtin.deduce_memory_cell(Relocatable::from((0, 2)), &memory),
+                Ok(Some(MaybeRelocatable::from(expected)))
+            );
+        }
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn deduce_memory_cell_pedersen_for_preset_memory_incorrect_offset() {
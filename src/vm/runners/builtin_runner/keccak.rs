@@ -1,7 +1,8 @@
-use crate::stdlib::prelude::*;
+use crate::stdlib::{cell::RefCell, collections::HashMap, prelude::*};
 
-use crate::hint_processor::builtin_hint_processor::keccak_utils::left_pad_u64;
-use crate::math_utils::safe_div_usize;
+use crate::air_private_input::KeccakPrivateInput;
+
+use crate::math_utils::{div_ceil, left_pad_u64, safe_div_usize};
 use crate::types::instance_definitions::keccak_instance_def::KeccakInstanceDef;
 use crate::types::relocatable::{MaybeRelocatable, Relocatable};
 use crate::vm::errors::memory_errors::{InsufficientAllocatedCellsError, MemoryError};
@@ -10,12 +11,31 @@ use crate::vm::vm_core::VirtualMachine;
 use crate::vm::vm_memory::memory::Memory;
 use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
 use felt::Felt;
-use num_integer::div_ceil;
 use num_traits::{One, ToPrimitive};
 
 use super::KECCAK_BUILTIN_NAME;
 
-const KECCAK_ARRAY_LEN: usize = 25;
+/// A permutation of the keccak family, applied by the builtin to each instance's state
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KeccakPermutation {
+    /// Number of 64-bit lanes of the permuted state
+    pub(crate) lanes: usize,
+    permute: fn(&mut [u64]) -> Result<(), RunnerError>,
+}
+
+/// The standard keccak-f[1600] permutation, over 25 lanes
+pub(crate) const KECCAK_F1600: KeccakPermutation = KeccakPermutation {
+    lanes: 25,
+    permute: keccak_f1600,
+};
+
+fn keccak_f1600(state: &mut [u64]) -> Result<(), RunnerError> {
+    let state: &mut [u64; 25] = state
+        .try_into()
+        .map_err(|_| RunnerError::SliceToArrayError)?;
+    keccak::f1600(state);
+    Ok(())
+}
 
 #[derive(Debug, Clone)]
 pub struct KeccakBuiltinRunner {
@@ -23,11 +43,13 @@ pub struct KeccakBuiltinRunner {
     pub base: usize,
     pub(crate) cells_per_instance: u32,
     pub(crate) n_input_cells: u32,
-    verified_addresses: Vec<Relocatable>,
+    // Permutation outputs, keyed by the address of the first input cell of their instance
+    cache: RefCell<HashMap<Relocatable, Vec<u64>>>,
     pub(crate) stop_ptr: Option<usize>,
     pub(crate) included: bool,
     state_rep: Vec<u32>,
     instances_per_component: u32,
+    permutation: KeccakPermutation,
 }
 
 impl KeccakBuiltinRunner {
@@ -38,10 +60,11 @@ impl KeccakBuiltinRunner {
             n_input_cells: instance_def._state_rep.len() as u32,
             cells_per_instance: instance_def.cells_per_builtin(),
             stop_ptr: None,
-            verified_addresses: Vec::new(),
+            cache: RefCell::new(HashMap::new()),
             included,
             instances_per_component: instance_def._instance_per_component,
             state_rep: instance_def._state_rep.clone(),
+            permutation: KECCAK_F1600,
         }
     }
 
@@ -78,8 +101,10 @@ impl KeccakBuiltinRunner {
         }
 
         let first_input_addr = (address - index).map_err(|_| RunnerError::KeccakNoFirstInput)?;
-        if self.verified_addresses.contains(&first_input_addr) {
-            return Ok(None);
+        // Each output cell of an instance is deduced separately, but the permutation only
+        // needs to run once per instance
+        if let Some(output) = self.cache.borrow().get(&first_input_addr) {
+            return Ok(Self::output_cell(output, address));
         }
 
         let mut input_felts_u64 = vec![];
@@ -108,19 +133,29 @@ impl KeccakBuiltinRunner {
             }
 
             let len = input_felts_u64.len();
-            let mut input_felts_u64 = left_pad_u64(&mut input_felts_u64, KECCAK_ARRAY_LEN - len)
-                .try_into()
-                .map_err(|_| RunnerError::SliceToArrayError)?;
+            let lanes = self.permutation.lanes;
+            if len > lanes {
+                return Err(RunnerError::KeccakInputExceedsLanes(len, lanes));
+            }
+            let mut input_felts_u64 = left_pad_u64(&input_felts_u64, lanes - len);
 
-            keccak::f1600(&mut input_felts_u64);
+            (self.permutation.permute)(&mut input_felts_u64)?;
 
-            return Ok(input_felts_u64
-                .get(address.offset - 1)
-                .map(|x| Felt::from(*x).into()));
+            let output_cell = Self::output_cell(&input_felts_u64, address);
+            self.cache
+                .borrow_mut()
+                .insert(first_input_addr, input_felts_u64);
+            return Ok(output_cell);
         }
         Ok(None)
     }
 
+    fn output_cell(output: &[u64], address: Relocatable) -> Option<MaybeRelocatable> {
+        output
+            .get(address.offset - 1)
+            .map(|x| Felt::from(*x).into())
+    }
+
     pub fn get_allocated_memory_units(&self, vm: &VirtualMachine) -> Result<usize, MemoryError> {
         let value = safe_div_usize(vm.current_step, self.ratio as usize)
             .map_err(|_| MemoryError::ErrorCalculatingMemoryUnits)?;
@@ -175,7 +210,7 @@ impl KeccakBuiltinRunner {
         segments: &MemorySegmentManager,
     ) -> Result<usize, MemoryError> {
         let used_cells = self.get_used_cells(segments)?;
-        Ok(div_ceil(used_cells, self.cells_per_instance as usize))
+        Ok(div_ceil(used_cells, self.cells_per_instance as usize)?)
     }
 
     pub fn final_stack(
@@ -190,7 +225,7 @@ impl KeccakBuiltinRunner {
                 .memory
                 .get_relocatable(stop_pointer_addr)
                 .map_err(|_| RunnerError::NoStopPointer(KECCAK_BUILTIN_NAME))?;
-            if self.base as isize != stop_pointer.segment_index {
+            if !stop_pointer.in_segment(self.base) {
                 return Err(RunnerError::InvalidStopPointerIndex(
                     KECCAK_BUILTIN_NAME,
                     stop_pointer,
@@ -230,6 +265,40 @@ impl KeccakBuiltinRunner {
             .collect())
     }
 
+    /// Returns the input and output states of each instance with all of its input cells set.
+    /// Output cells missing from memory are deduced from the inputs.
+    pub fn air_private_input(&self, memory: &Memory) -> Vec<KeccakPrivateInput> {
+        let cells_per_instance = self.cells_per_instance as usize;
+        let n_input_cells = self.n_input_cells as usize;
+        let n_instances =
+            div_ceil(memory.segment_len(self.base), cells_per_instance).unwrap_or_default();
+        (0..n_instances)
+            .filter_map(|index| {
+                let first_addr =
+                    Relocatable::from((self.base as isize, index * cells_per_instance));
+                let cell = |offset: usize| -> Option<Felt> {
+                    let addr = (first_addr + offset).ok()?;
+                    match memory.get_integer(addr) {
+                        Ok(value) => Some(value.into_owned()),
+                        Err(_) if offset >= n_input_cells => self
+                            .deduce_memory_cell(addr, memory)
+                            .ok()??
+                            .get_int_ref()
+                            .cloned(),
+                        Err(_) => None,
+                    }
+                };
+                Some(KeccakPrivateInput {
+                    index,
+                    input: (0..n_input_cells).map(cell).collect::<Option<_>>()?,
+                    output: (n_input_cells..cells_per_instance)
+                        .map(cell)
+                        .collect::<Option<_>>()?,
+                })
+            })
+            .collect()
+    }
+
     pub fn get_used_diluted_check_units(&self, diluted_n_bits: u32) -> usize {
         // The diluted cells are:
         // state - 25 rounds times 1600 elements.
@@ -605,14 +674,58 @@ mod tests {
             ((0, 35), 0)
         ];
 
-        let mut builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
 
-        builtin.verified_addresses.push(Relocatable::from((0, 16)));
+        // Outputs cached for an instance are returned without running the permutation
+        builtin
+            .cache
+            .borrow_mut()
+            .insert(Relocatable::from((0, 16)), vec![7; KECCAK_F1600.lanes]);
 
         let result = builtin.deduce_memory_cell(Relocatable::from((0, 25)), &memory);
+        assert_eq!(result, Ok(Some(MaybeRelocatable::from(Felt::new(7)))));
+        // Instances at other offsets of the same segment don't share the cached outputs
+        let result = builtin.deduce_memory_cell(Relocatable::from((0, 9)), &memory);
         assert_eq!(result, Ok(None));
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deduce_memory_cell_runs_permutation_once_per_instance() {
+        let memory = memory![
+            ((0, 16), 43),
+            ((0, 17), 199),
+            ((0, 18), 0),
+            ((0, 19), 0),
+            ((0, 20), 0),
+            ((0, 21), 0),
+            ((0, 22), 0),
+            ((0, 23), 1)
+        ];
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+
+        let outputs: Vec<_> = (24..32)
+            .map(|offset| builtin.deduce_memory_cell(Relocatable::from((0, offset)), &memory))
+            .collect();
+        assert_eq!(
+            outputs[1],
+            Ok(Some(MaybeRelocatable::from(Felt::new(
+                3086936446498698982_u64
+            ))))
+        );
+        assert_eq!(builtin.cache.borrow().len(), 1);
+
+        // The inputs aren't read again once the instance's outputs are cached
+        let empty_memory = Memory::new();
+        for (offset, output) in (24..32).zip(outputs) {
+            assert_eq!(
+                builtin.deduce_memory_cell(Relocatable::from((0, offset)), &empty_memory),
+                output
+            );
+        }
+        assert_eq!(builtin.cache.borrow().len(), 1);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn deduce_memory_cell_expected_integer() {
@@ -686,6 +799,80 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn air_private_input_one_entry_per_used_instance() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../../cairo_programs/keccak_builtin.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program, "recursive");
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        cairo_runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+
+        let keccak = vm
+            .builtin_runners
+            .iter()
+            .find_map(|(_, builtin)| builtin.as_keccak())
+            .unwrap();
+        let private_input = keccak.air_private_input(vm.memory());
+        assert_eq!(
+            private_input.len(),
+            keccak.get_used_instances(&vm.segments).unwrap()
+        );
+        assert_eq!(private_input.len(), 2);
+        assert_eq!(
+            private_input[0].input,
+            (1..=8).map(Felt::new).collect::<Vec<_>>()
+        );
+        assert_eq!(private_input[0].output.len(), 8);
+        // The second instance hashes the output of the first one
+        assert_eq!(private_input[1].index, 1);
+        assert_eq!(private_input[1].input, private_input[0].output);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn air_private_input_deduces_missing_outputs() {
+        let memory = memory![
+            ((0, 0), 1),
+            ((0, 1), 2),
+            ((0, 2), 3),
+            ((0, 3), 4),
+            ((0, 4), 5),
+            ((0, 5), 6),
+            ((0, 6), 7),
+            ((0, 7), 8),
+            ((0, 16), 1)
+        ];
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+        let private_input = builtin.air_private_input(&memory);
+        // The second instance is missing most of its inputs
+        assert_eq!(private_input.len(), 1);
+        let expected_output: Vec<Felt> = [
+            10907547067456810327_u64,
+            15097214379932521329,
+            17557593541080799905,
+            12198903854431193736,
+            17540430607158258761,
+            12669889283082562193,
+            15056031927995794062,
+            1224259078621842277,
+        ]
+        .into_iter()
+        .map(Felt::from)
+        .collect();
+        assert_eq!(private_input[0].output, expected_output);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_used_diluted_check_units_result() {
@@ -695,4 +882,31 @@ mod tests {
 
         assert_eq!(result, 16384);
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn permutation_is_f1600() {
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+        assert_eq!(builtin.permutation.lanes, 25);
+
+        let mut state = vec![0; builtin.permutation.lanes];
+        (builtin.permutation.permute)(&mut state).unwrap();
+        let mut expected = [0; 25];
+        keccak::f1600(&mut expected);
+        assert_eq!(state, expected);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deduce_memory_cell_input_exceeds_lanes() {
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::new(2048, vec![64; 26]), true);
+        let mut memory = Memory::new();
+        for offset in 0..26 {
+            memory_inner!(memory, (0, offset), 1);
+        }
+        assert_eq!(
+            builtin.deduce_memory_cell(Relocatable::from((0, 26)), &memory),
+            Err(RunnerError::KeccakInputExceedsLanes(26, 25))
+        );
+    }
 }
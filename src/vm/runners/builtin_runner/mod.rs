@@ -1,8 +1,10 @@
+use crate::air_private_input::PrivateInput;
 use crate::stdlib::prelude::*;
 use crate::types::relocatable::{MaybeRelocatable, Relocatable};
 use crate::vm::errors::memory_errors::{self, MemoryError};
 use crate::vm::errors::runner_errors::RunnerError;
 use crate::vm::errors::vm_errors::VirtualMachineError;
+use crate::vm::runners::cairo_pie::BuiltinAdditionalData;
 use crate::vm::vm_core::VirtualMachine;
 use crate::vm::vm_memory::memory::Memory;
 use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
@@ -14,6 +16,7 @@ mod keccak;
 mod output;
 mod poseidon;
 mod range_check;
+mod segment_arena;
 mod signature;
 
 pub use self::keccak::KeccakBuiltinRunner;
@@ -24,6 +27,7 @@ pub use hash::HashBuiltinRunner;
 use num_integer::div_floor;
 pub use output::OutputBuiltinRunner;
 pub use range_check::RangeCheckBuiltinRunner;
+pub use segment_arena::{ArenaSegmentInfo, SegmentArenaBuiltinRunner};
 pub use signature::SignatureBuiltinRunner;
 
 pub const OUTPUT_BUILTIN_NAME: &str = "output";
@@ -34,6 +38,8 @@ pub const BITWISE_BUILTIN_NAME: &str = "bitwise";
 pub const EC_OP_BUILTIN_NAME: &str = "ec_op";
 pub const KECCAK_BUILTIN_NAME: &str = "keccak";
 pub const POSEIDON_BUILTIN_NAME: &str = "poseidon";
+pub const SEGMENT_ARENA_BUILTIN_NAME: &str = "segment_arena";
+pub const RANGE_CHECK_96_BUILTIN_NAME: &str = "range_check96";
 
 /* NB: this enum is no accident: we may need (and cairo-rs-py *does* need)
  * structs containing this to be `Send`. The only two ways to achieve that
@@ -50,9 +56,11 @@ pub enum BuiltinRunner {
     Hash(HashBuiltinRunner),
     Output(OutputBuiltinRunner),
     RangeCheck(RangeCheckBuiltinRunner),
+    RangeCheck96(RangeCheckBuiltinRunner),
     Keccak(KeccakBuiltinRunner),
     Signature(SignatureBuiltinRunner),
     Poseidon(PoseidonBuiltinRunner),
+    SegmentArena(SegmentArenaBuiltinRunner),
 }
 
 impl BuiltinRunner {
@@ -63,12 +71,16 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref mut ec) => ec.initialize_segments(segments),
             BuiltinRunner::Hash(ref mut hash) => hash.initialize_segments(segments),
             BuiltinRunner::Output(ref mut output) => output.initialize_segments(segments),
-            BuiltinRunner::RangeCheck(ref mut range_check) => {
+            BuiltinRunner::RangeCheck(ref mut range_check)
+            | BuiltinRunner::RangeCheck96(ref mut range_check) => {
                 range_check.initialize_segments(segments)
             }
             BuiltinRunner::Keccak(ref mut keccak) => keccak.initialize_segments(segments),
             BuiltinRunner::Signature(ref mut signature) => signature.initialize_segments(segments),
             BuiltinRunner::Poseidon(ref mut poseidon) => poseidon.initialize_segments(segments),
+            BuiltinRunner::SegmentArena(ref mut segment_arena) => {
+                segment_arena.initialize_segments(segments)
+            }
         }
     }
 
@@ -78,10 +90,12 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref ec) => ec.initial_stack(),
             BuiltinRunner::Hash(ref hash) => hash.initial_stack(),
             BuiltinRunner::Output(ref output) => output.initial_stack(),
-            BuiltinRunner::RangeCheck(ref range_check) => range_check.initial_stack(),
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => range_check.initial_stack(),
             BuiltinRunner::Keccak(ref keccak) => keccak.initial_stack(),
             BuiltinRunner::Signature(ref signature) => signature.initial_stack(),
             BuiltinRunner::Poseidon(ref poseidon) => poseidon.initial_stack(),
+            BuiltinRunner::SegmentArena(ref segment_arena) => segment_arena.initial_stack(),
         }
     }
 
@@ -96,7 +110,8 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref mut ec) => ec.final_stack(segments, stack_pointer),
             BuiltinRunner::Hash(ref mut hash) => hash.final_stack(segments, stack_pointer),
             BuiltinRunner::Output(ref mut output) => output.final_stack(segments, stack_pointer),
-            BuiltinRunner::RangeCheck(ref mut range_check) => {
+            BuiltinRunner::RangeCheck(ref mut range_check)
+            | BuiltinRunner::RangeCheck96(ref mut range_check) => {
                 range_check.final_stack(segments, stack_pointer)
             }
             BuiltinRunner::Keccak(ref mut keccak) => keccak.final_stack(segments, stack_pointer),
@@ -106,6 +121,9 @@ impl BuiltinRunner {
             BuiltinRunner::Poseidon(ref mut poseidon) => {
                 poseidon.final_stack(segments, stack_pointer)
             }
+            BuiltinRunner::SegmentArena(ref mut segment_arena) => {
+                segment_arena.final_stack(segments, stack_pointer)
+            }
         }
     }
 
@@ -119,12 +137,16 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref ec) => ec.get_allocated_memory_units(vm),
             BuiltinRunner::Hash(ref hash) => hash.get_allocated_memory_units(vm),
             BuiltinRunner::Output(ref output) => output.get_allocated_memory_units(vm),
-            BuiltinRunner::RangeCheck(ref range_check) => {
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => {
                 range_check.get_allocated_memory_units(vm)
             }
             BuiltinRunner::Keccak(ref keccak) => keccak.get_allocated_memory_units(vm),
             BuiltinRunner::Signature(ref signature) => signature.get_allocated_memory_units(vm),
             BuiltinRunner::Poseidon(ref poseidon) => poseidon.get_allocated_memory_units(vm),
+            BuiltinRunner::SegmentArena(ref segment_arena) => {
+                segment_arena.get_allocated_memory_units(vm)
+            }
         }
     }
 
@@ -135,10 +157,12 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref ec) => ec.base(),
             BuiltinRunner::Hash(ref hash) => hash.base(),
             BuiltinRunner::Output(ref output) => output.base(),
-            BuiltinRunner::RangeCheck(ref range_check) => range_check.base(),
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => range_check.base(),
             BuiltinRunner::Keccak(ref keccak) => keccak.base(),
             BuiltinRunner::Signature(ref signature) => signature.base(),
             BuiltinRunner::Poseidon(ref poseidon) => poseidon.base(),
+            BuiltinRunner::SegmentArena(ref segment_arena) => segment_arena.base(),
         }
     }
 
@@ -148,10 +172,13 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ec) => Some(ec.ratio()),
             BuiltinRunner::Hash(hash) => Some(hash.ratio()),
             BuiltinRunner::Output(_) => None,
-            BuiltinRunner::RangeCheck(range_check) => Some(range_check.ratio()),
+            BuiltinRunner::RangeCheck(range_check) | BuiltinRunner::RangeCheck96(range_check) => {
+                Some(range_check.ratio())
+            }
             BuiltinRunner::Keccak(keccak) => Some(keccak.ratio()),
             BuiltinRunner::Signature(ref signature) => Some(signature.ratio()),
             BuiltinRunner::Poseidon(poseidon) => Some(poseidon.ratio()),
+            BuiltinRunner::SegmentArena(_) => None,
         }
     }
 
@@ -161,13 +188,21 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref ec) => ec.add_validation_rule(memory),
             BuiltinRunner::Hash(ref hash) => hash.add_validation_rule(memory),
             BuiltinRunner::Output(ref output) => output.add_validation_rule(memory),
-            BuiltinRunner::RangeCheck(ref range_check) => range_check.add_validation_rule(memory),
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => {
+                range_check.add_validation_rule(memory)
+            }
             BuiltinRunner::Keccak(ref keccak) => keccak.add_validation_rule(memory),
             BuiltinRunner::Signature(ref signature) => signature.add_validation_rule(memory),
             BuiltinRunner::Poseidon(ref poseidon) => poseidon.add_validation_rule(memory),
+            BuiltinRunner::SegmentArena(ref segment_arena) => {
+                segment_arena.add_validation_rule(memory)
+            }
         }
     }
 
+    /// Deduces the value of the cell at `address` using the concrete builtin's rules.
+    /// Returns `Ok(None)` if the builtin can't deduce that cell.
     pub fn deduce_memory_cell(
         &self,
         address: Relocatable,
@@ -178,7 +213,8 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref ec) => ec.deduce_memory_cell(address, memory),
             BuiltinRunner::Hash(ref hash) => hash.deduce_memory_cell(address, memory),
             BuiltinRunner::Output(ref output) => output.deduce_memory_cell(address, memory),
-            BuiltinRunner::RangeCheck(ref range_check) => {
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => {
                 range_check.deduce_memory_cell(address, memory)
             }
             BuiltinRunner::Keccak(ref keccak) => keccak.deduce_memory_cell(address, memory),
@@ -186,6 +222,30 @@ impl BuiltinRunner {
                 signature.deduce_memory_cell(address, memory)
             }
             BuiltinRunner::Poseidon(ref poseidon) => poseidon.deduce_memory_cell(address, memory),
+            BuiltinRunner::SegmentArena(ref segment_arena) => {
+                segment_arena.deduce_memory_cell(address, memory)
+            }
+        }
+    }
+
+    /// Returns the private input of the builtin's instances, as expected by the prover.
+    /// Builtins without private input support return an empty list.
+    pub fn air_private_input(&self, memory: &Memory) -> Vec<PrivateInput> {
+        match *self {
+            BuiltinRunner::Bitwise(ref bitwise) => bitwise.air_private_input(memory),
+            BuiltinRunner::Hash(ref hash) => hash
+                .air_private_input(memory)
+                .into_iter()
+                .map(PrivateInput::Pair)
+                .collect(),
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => range_check.air_private_input(memory),
+            BuiltinRunner::Keccak(ref keccak) => keccak
+                .air_private_input(memory)
+                .into_iter()
+                .map(PrivateInput::KeccakState)
+                .collect(),
+            _ => Vec::new(),
         }
     }
 
@@ -210,12 +270,32 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref ec) => ec.get_memory_segment_addresses(),
             BuiltinRunner::Hash(ref hash) => hash.get_memory_segment_addresses(),
             BuiltinRunner::Output(ref output) => output.get_memory_segment_addresses(),
-            BuiltinRunner::RangeCheck(ref range_check) => {
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => {
                 range_check.get_memory_segment_addresses()
             }
             BuiltinRunner::Keccak(ref keccak) => keccak.get_memory_segment_addresses(),
             BuiltinRunner::Signature(ref signature) => signature.get_memory_segment_addresses(),
             BuiltinRunner::Poseidon(ref poseidon) => poseidon.get_memory_segment_addresses(),
+            BuiltinRunner::SegmentArena(ref segment_arena) => {
+                segment_arena.get_memory_segment_addresses()
+            }
+        }
+    }
+
+    /// Returns the builtin specific data included in a Cairo PIE
+    pub fn get_additional_data(&self) -> BuiltinAdditionalData {
+        match self {
+            BuiltinRunner::Hash(ref hash) => hash.get_additional_data(),
+            BuiltinRunner::Output(ref output) => output.get_additional_data(),
+            BuiltinRunner::Signature(ref signature) => signature.get_additional_data(),
+            BuiltinRunner::Bitwise(_)
+            | BuiltinRunner::EcOp(_)
+            | BuiltinRunner::RangeCheck(_)
+            | BuiltinRunner::RangeCheck96(_)
+            | BuiltinRunner::Keccak(_)
+            | BuiltinRunner::Poseidon(_)
+            | BuiltinRunner::SegmentArena(_) => BuiltinAdditionalData::None,
         }
     }
 
@@ -225,10 +305,14 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref ec) => ec.get_used_cells(segments),
             BuiltinRunner::Hash(ref hash) => hash.get_used_cells(segments),
             BuiltinRunner::Output(ref output) => output.get_used_cells(segments),
-            BuiltinRunner::RangeCheck(ref range_check) => range_check.get_used_cells(segments),
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => range_check.get_used_cells(segments),
             BuiltinRunner::Keccak(ref keccak) => keccak.get_used_cells(segments),
             BuiltinRunner::Signature(ref signature) => signature.get_used_cells(segments),
             BuiltinRunner::Poseidon(ref poseidon) => poseidon.get_used_cells(segments),
+            BuiltinRunner::SegmentArena(ref segment_arena) => {
+                segment_arena.get_used_cells(segments)
+            }
         }
     }
 
@@ -241,16 +325,25 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref ec) => ec.get_used_instances(segments),
             BuiltinRunner::Hash(ref hash) => hash.get_used_instances(segments),
             BuiltinRunner::Output(ref output) => output.get_used_instances(segments),
-            BuiltinRunner::RangeCheck(ref range_check) => range_check.get_used_instances(segments),
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => {
+                range_check.get_used_instances(segments)
+            }
             BuiltinRunner::Keccak(ref keccak) => keccak.get_used_instances(segments),
             BuiltinRunner::Signature(ref signature) => signature.get_used_instances(segments),
             BuiltinRunner::Poseidon(ref poseidon) => poseidon.get_used_instances(segments),
+            BuiltinRunner::SegmentArena(ref segment_arena) => {
+                segment_arena.get_used_instances(segments)
+            }
         }
     }
 
     pub fn get_range_check_usage(&self, memory: &Memory) -> Option<(usize, usize)> {
         match self {
-            BuiltinRunner::RangeCheck(ref range_check) => range_check.get_range_check_usage(memory),
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => {
+                range_check.get_range_check_usage(memory)
+            }
             _ => None,
         }
     }
@@ -261,7 +354,7 @@ impl BuiltinRunner {
         vm: &VirtualMachine,
     ) -> Result<usize, MemoryError> {
         match self {
-            BuiltinRunner::RangeCheck(range_check) => {
+            BuiltinRunner::RangeCheck(range_check) | BuiltinRunner::RangeCheck96(range_check) => {
                 range_check.get_used_perm_range_check_units(vm)
             }
             _ => Ok(0),
@@ -285,11 +378,14 @@ impl BuiltinRunner {
             BuiltinRunner::Bitwise(builtin) => builtin.cells_per_instance,
             BuiltinRunner::EcOp(builtin) => builtin.cells_per_instance,
             BuiltinRunner::Hash(builtin) => builtin.cells_per_instance,
-            BuiltinRunner::RangeCheck(builtin) => builtin.cells_per_instance,
+            BuiltinRunner::RangeCheck(builtin) | BuiltinRunner::RangeCheck96(builtin) => {
+                builtin.cells_per_instance
+            }
             BuiltinRunner::Output(_) => 0,
             BuiltinRunner::Keccak(builtin) => builtin.cells_per_instance,
             BuiltinRunner::Signature(builtin) => builtin.cells_per_instance,
             BuiltinRunner::Poseidon(builtin) => builtin.cells_per_instance,
+            BuiltinRunner::SegmentArena(builtin) => builtin.cells_per_instance,
         }
     }
 
@@ -298,11 +394,14 @@ impl BuiltinRunner {
             BuiltinRunner::Bitwise(builtin) => builtin.n_input_cells,
             BuiltinRunner::EcOp(builtin) => builtin.n_input_cells,
             BuiltinRunner::Hash(builtin) => builtin.n_input_cells,
-            BuiltinRunner::RangeCheck(builtin) => builtin.n_input_cells,
+            BuiltinRunner::RangeCheck(builtin) | BuiltinRunner::RangeCheck96(builtin) => {
+                builtin.n_input_cells
+            }
             BuiltinRunner::Output(_) => 0,
             BuiltinRunner::Keccak(builtin) => builtin.n_input_cells,
             BuiltinRunner::Signature(builtin) => builtin.n_input_cells,
             BuiltinRunner::Poseidon(builtin) => builtin.n_input_cells,
+            BuiltinRunner::SegmentArena(builtin) => builtin.n_input_cells,
         }
     }
 
@@ -312,32 +411,120 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(_) => EC_OP_BUILTIN_NAME,
             BuiltinRunner::Hash(_) => HASH_BUILTIN_NAME,
             BuiltinRunner::RangeCheck(_) => RANGE_CHECK_BUILTIN_NAME,
+            BuiltinRunner::RangeCheck96(_) => RANGE_CHECK_96_BUILTIN_NAME,
             BuiltinRunner::Output(_) => OUTPUT_BUILTIN_NAME,
             BuiltinRunner::Keccak(_) => KECCAK_BUILTIN_NAME,
             BuiltinRunner::Signature(_) => SIGNATURE_BUILTIN_NAME,
             BuiltinRunner::Poseidon(_) => POSEIDON_BUILTIN_NAME,
+            BuiltinRunner::SegmentArena(_) => SEGMENT_ARENA_BUILTIN_NAME,
+        }
+    }
+
+    /// Returns the inner [BitwiseBuiltinRunner] if this is a bitwise builtin
+    pub fn as_bitwise(&self) -> Option<&BitwiseBuiltinRunner> {
+        match self {
+            BuiltinRunner::Bitwise(builtin) => Some(builtin),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner [EcOpBuiltinRunner] if this is a ec_op builtin
+    pub fn as_ec_op(&self) -> Option<&EcOpBuiltinRunner> {
+        match self {
+            BuiltinRunner::EcOp(builtin) => Some(builtin),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner [HashBuiltinRunner] if this is a hash builtin
+    pub fn as_hash(&self) -> Option<&HashBuiltinRunner> {
+        match self {
+            BuiltinRunner::Hash(builtin) => Some(builtin),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner [OutputBuiltinRunner] if this is a output builtin
+    pub fn as_output(&self) -> Option<&OutputBuiltinRunner> {
+        match self {
+            BuiltinRunner::Output(builtin) => Some(builtin),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner [RangeCheckBuiltinRunner] if this is a range_check builtin.
+    /// A range_check96 builtin returns None, see [as_range_check96](Self::as_range_check96)
+    pub fn as_range_check(&self) -> Option<&RangeCheckBuiltinRunner> {
+        match self {
+            BuiltinRunner::RangeCheck(builtin) => Some(builtin),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner [RangeCheckBuiltinRunner] if this is a range_check96 builtin
+    pub fn as_range_check96(&self) -> Option<&RangeCheckBuiltinRunner> {
+        match self {
+            BuiltinRunner::RangeCheck96(builtin) => Some(builtin),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner [KeccakBuiltinRunner] if this is a keccak builtin
+    pub fn as_keccak(&self) -> Option<&KeccakBuiltinRunner> {
+        match self {
+            BuiltinRunner::Keccak(builtin) => Some(builtin),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner [SignatureBuiltinRunner] if this is a signature builtin
+    pub fn as_signature(&self) -> Option<&SignatureBuiltinRunner> {
+        match self {
+            BuiltinRunner::Signature(builtin) => Some(builtin),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner [PoseidonBuiltinRunner] if this is a poseidon builtin
+    pub fn as_poseidon(&self) -> Option<&PoseidonBuiltinRunner> {
+        match self {
+            BuiltinRunner::Poseidon(builtin) => Some(builtin),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner [SegmentArenaBuiltinRunner] if this is a segment_arena builtin
+    pub fn as_segment_arena(&self) -> Option<&SegmentArenaBuiltinRunner> {
+        match self {
+            BuiltinRunner::SegmentArena(builtin) => Some(builtin),
+            _ => None,
         }
     }
 
     pub fn run_security_checks(&self, vm: &VirtualMachine) -> Result<(), VirtualMachineError> {
-        if let BuiltinRunner::Output(_) = self {
+        if let BuiltinRunner::Output(_) | BuiltinRunner::SegmentArena(_) = self {
             return Ok(());
         }
         let cells_per_instance = self.cells_per_instance() as usize;
         let n_input_cells = self.n_input_cells() as usize;
         let builtin_segment_index = self.base();
+        let memory = &vm.segments.memory;
+        // The segment may be stored by the sparse backend, so it is only read through the
+        // memory's accessors
+        let segment_len = memory.segment_len(builtin_segment_index);
         // If the builtin's segment is empty, there are no security checks to run
-        let builtin_segment = match vm.segments.memory.data.get(builtin_segment_index) {
-            Some(segment) if !segment.is_empty() => segment,
-            _ => return Ok(()),
-        };
+        if segment_len == 0 {
+            return Ok(());
+        }
+        let is_missing =
+            |offset: usize| memory.segment_cell(builtin_segment_index, offset).is_none();
         // The builtin segment's size - 1 is the maximum offset within the segment's addresses
         // Assumption: The last element is not a None value
         // It is safe to asume this for normal program execution
         // If there are trailing None values at the end, the following security checks will fail
-        let offset_max = builtin_segment.len().saturating_sub(1);
+        let offset_max = segment_len - 1;
         // offset_len is the amount of non-None values in the segment
-        let offset_len = builtin_segment.iter().filter(|x| x.is_some()).count();
+        let offset_len = memory.segment_cells(builtin_segment_index).count();
         let n = match offset_len {
             0 => 0,
             _ => div_floor(offset_max, cells_per_instance) + 1,
@@ -353,7 +540,7 @@ impl BuiltinRunner {
         for i in 0..n {
             for j in 0..n_input_cells {
                 let offset = cells_per_instance * i + j;
-                if let None | Some(None) = builtin_segment.get(offset) {
+                if is_missing(offset) {
                     missing_offsets.push(offset)
                 }
             }
@@ -368,7 +555,7 @@ impl BuiltinRunner {
         for i in 0..n {
             for j in n_input_cells..cells_per_instance {
                 let offset = cells_per_instance * i + j;
-                if let None | Some(None) = builtin_segment.get(offset) {
+                if is_missing(offset) {
                     vm.verify_auto_deductions_for_addr(
                         Relocatable::from((builtin_segment_index as isize, offset)),
                         self,
@@ -388,7 +575,8 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref ec) => ec.get_used_cells_and_allocated_size(vm),
             BuiltinRunner::Hash(ref hash) => hash.get_used_cells_and_allocated_size(vm),
             BuiltinRunner::Output(ref output) => output.get_used_cells_and_allocated_size(vm),
-            BuiltinRunner::RangeCheck(ref range_check) => {
+            BuiltinRunner::RangeCheck(ref range_check)
+            | BuiltinRunner::RangeCheck96(ref range_check) => {
                 range_check.get_used_cells_and_allocated_size(vm)
             }
             BuiltinRunner::Keccak(ref keccak) => keccak.get_used_cells_and_allocated_size(vm),
@@ -396,6 +584,9 @@ impl BuiltinRunner {
                 signature.get_used_cells_and_allocated_size(vm)
             }
             BuiltinRunner::Poseidon(ref poseidon) => poseidon.get_used_cells_and_allocated_size(vm),
+            BuiltinRunner::SegmentArena(ref segment_arena) => {
+                segment_arena.get_used_cells_and_allocated_size(vm)
+            }
         }
     }
 
@@ -406,10 +597,16 @@ impl BuiltinRunner {
             BuiltinRunner::EcOp(ref mut ec) => ec.stop_ptr = Some(stop_ptr),
             BuiltinRunner::Hash(ref mut hash) => hash.stop_ptr = Some(stop_ptr),
             BuiltinRunner::Output(ref mut output) => output.stop_ptr = Some(stop_ptr),
-            BuiltinRunner::RangeCheck(ref mut range_check) => range_check.stop_ptr = Some(stop_ptr),
+            BuiltinRunner::RangeCheck(ref mut range_check)
+            | BuiltinRunner::RangeCheck96(ref mut range_check) => {
+                range_check.stop_ptr = Some(stop_ptr)
+            }
             BuiltinRunner::Keccak(ref mut keccak) => keccak.stop_ptr = Some(stop_ptr),
             BuiltinRunner::Signature(ref mut signature) => signature.stop_ptr = Some(stop_ptr),
             BuiltinRunner::Poseidon(ref mut poseidon) => poseidon.stop_ptr = Some(stop_ptr),
+            BuiltinRunner::SegmentArena(ref mut segment_arena) => {
+                segment_arena.stop_ptr = Some(stop_ptr)
+            }
         }
     }
 }
@@ -462,6 +659,12 @@ impl From<PoseidonBuiltinRunner> for BuiltinRunner {
     }
 }
 
+impl From<SegmentArenaBuiltinRunner> for BuiltinRunner {
+    fn from(runner: SegmentArenaBuiltinRunner) -> Self {
+        BuiltinRunner::SegmentArena(runner)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -476,9 +679,10 @@ mod tests {
             bitwise_instance_def::BitwiseInstanceDef, ec_op_instance_def::EcOpInstanceDef,
         },
         utils::test_utils::*,
-        vm::vm_core::VirtualMachine,
+        vm::{vm_core::VirtualMachine, vm_memory::memory::SPARSE_SEGMENT_GAP},
     };
     use assert_matches::assert_matches;
+    use felt::Felt;
 
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::*;
@@ -1001,6 +1205,62 @@ mod tests {
         assert_eq!(builtin.get_used_diluted_check_units(0, 8), 32768);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn as_keccak_get_used_diluted_check_units() {
+        let builtin = BuiltinRunner::Keccak(KeccakBuiltinRunner::new(
+            &KeccakInstanceDef::default(),
+            true,
+        ));
+        let keccak = builtin.as_keccak().unwrap();
+        assert_eq!(keccak.get_used_diluted_check_units(8), 32768);
+        assert!(builtin.as_bitwise().is_none());
+        assert!(builtin.as_range_check().is_none());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn as_range_check96_only_matches_range_check96() {
+        let builtin =
+            BuiltinRunner::RangeCheck96(RangeCheckBuiltinRunner::new_range_check96(8, true));
+        let range_check96 = builtin.as_range_check96().unwrap();
+        assert_eq!(range_check96._bound, Some(Felt::new(1_u128 << 96)));
+        assert!(builtin.as_range_check().is_none());
+
+        let builtin = BuiltinRunner::RangeCheck(RangeCheckBuiltinRunner::new(8, 8, true));
+        assert!(builtin.as_range_check().is_some());
+        assert!(builtin.as_range_check96().is_none());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn deduce_memory_cell_keccak_matches_direct_call() {
+        let memory = memory![
+            ((0, 16), 43),
+            ((0, 17), 199),
+            ((0, 18), 0),
+            ((0, 19), 0),
+            ((0, 20), 0),
+            ((0, 21), 0),
+            ((0, 22), 0),
+            ((0, 23), 1),
+            ((0, 24), 0),
+            ((0, 25), 0)
+        ];
+        let keccak = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+        let builtin = BuiltinRunner::Keccak(keccak.clone());
+
+        let address = Relocatable::from((0, 25));
+        let deduced = builtin.deduce_memory_cell(address, &memory);
+        assert_eq!(deduced, keccak.deduce_memory_cell(address, &memory));
+        assert_eq!(
+            deduced,
+            Ok(Some(MaybeRelocatable::from(Felt::new(
+                3086936446498698982_u64
+            ))))
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_used_diluted_check_units_ec_op() {
@@ -1108,6 +1368,34 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_security_checks_bitwise_sparse_segment() {
+        let builtin: BuiltinRunner =
+            BitwiseBuiltinRunner::new(&BitwiseInstanceDef::default(), true).into();
+        let mut vm = vm!();
+        vm.segments.memory = memory![((0, 0), 12), ((0, 1), 10)];
+        assert_matches!(builtin.run_security_checks(&vm), Ok(()));
+
+        // The instances between the first one and one written past the gap are missing
+        let far_instance = SPARSE_SEGMENT_GAP * 5;
+        vm.segments
+            .memory
+            .insert_value(relocatable!(0, far_instance), Felt::new(12))
+            .unwrap();
+        vm.segments
+            .memory
+            .insert_value(relocatable!(0, far_instance + 1), Felt::new(10))
+            .unwrap();
+        assert!(vm.segments.memory.sparse_data.contains_key(&0));
+        assert_matches!(
+            builtin.run_security_checks(&vm),
+            Err(VirtualMachineError::Memory(
+                MemoryError::MissingMemoryCells(BITWISE_BUILTIN_NAME)
+            ))
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn run_security_checks_bitwise_missing_memory_cells() {
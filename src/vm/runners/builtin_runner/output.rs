@@ -1,7 +1,10 @@
-use crate::stdlib::prelude::*;
+use crate::stdlib::{collections::HashMap, prelude::*};
 use crate::types::relocatable::{MaybeRelocatable, Relocatable};
 use crate::vm::errors::memory_errors::MemoryError;
 use crate::vm::errors::runner_errors::RunnerError;
+use crate::vm::runners::cairo_pie::{
+    BuiltinAdditionalData, OutputBuiltinAdditionalData, PublicMemoryPage,
+};
 use crate::vm::vm_core::VirtualMachine;
 use crate::vm::vm_memory::memory::Memory;
 use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
@@ -13,6 +16,7 @@ pub struct OutputBuiltinRunner {
     base: usize,
     pub(crate) stop_ptr: Option<usize>,
     pub(crate) included: bool,
+    pages: HashMap<usize, PublicMemoryPage>,
 }
 
 impl OutputBuiltinRunner {
@@ -21,6 +25,7 @@ impl OutputBuiltinRunner {
             base: 0,
             stop_ptr: None,
             included,
+            pages: HashMap::new(),
         }
     }
 
@@ -58,6 +63,61 @@ impl OutputBuiltinRunner {
         (self.base, self.stop_ptr)
     }
 
+    /// Assigns the `page_size` output cells starting at `page_start` to the public memory page
+    /// `page_id`. Cells not covered by any page belong to the main page (page 0).
+    pub fn add_page(
+        &mut self,
+        page_id: usize,
+        page_start: Relocatable,
+        page_size: usize,
+    ) -> Result<(), RunnerError> {
+        if page_id == 0 {
+            return Err(RunnerError::InvalidPublicMemoryPageId(page_id));
+        }
+        if !page_start.in_segment(self.base) {
+            return Err(RunnerError::PublicMemoryPageOutOfSegment(
+                page_start,
+                OUTPUT_BUILTIN_NAME,
+            ));
+        }
+        if self.pages.contains_key(&page_id) {
+            return Err(RunnerError::PublicMemoryPageAlreadyExists(page_id));
+        }
+        self.pages.insert(
+            page_id,
+            PublicMemoryPage {
+                start: page_start.offset,
+                size: page_size,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the public memory of the output segment as (offset, page_id) pairs
+    pub fn get_public_memory(
+        &self,
+        segments: &MemorySegmentManager,
+    ) -> Result<Vec<(usize, usize)>, RunnerError> {
+        let size = self.get_used_cells(segments)?;
+        let mut public_memory: Vec<(usize, usize)> = (0..size).map(|i| (i, 0)).collect();
+        for (page_id, page) in self.pages.iter() {
+            for index in page.start..page.start + page.size {
+                let cell = public_memory
+                    .get_mut(index)
+                    .ok_or(RunnerError::PublicMemoryPageOutOfBounds(*page_id, index))?;
+                cell.1 = *page_id;
+            }
+        }
+        Ok(public_memory)
+    }
+
+    pub fn get_additional_data(&self) -> BuiltinAdditionalData {
+        BuiltinAdditionalData::Output(OutputBuiltinAdditionalData {
+            pages: self.pages.clone(),
+            attributes: HashMap::new(),
+        })
+    }
+
     pub fn get_used_cells(&self, segments: &MemorySegmentManager) -> Result<usize, MemoryError> {
         segments
             .get_segment_used_size(self.base)
@@ -91,7 +151,7 @@ impl OutputBuiltinRunner {
                 .memory
                 .get_relocatable(stop_pointer_addr)
                 .map_err(|_| RunnerError::NoStopPointer(OUTPUT_BUILTIN_NAME))?;
-            if self.base as isize != stop_pointer.segment_index {
+            if !stop_pointer.in_segment(self.base) {
                 return Err(RunnerError::InvalidStopPointerIndex(
                     OUTPUT_BUILTIN_NAME,
                     stop_pointer,
@@ -136,6 +196,7 @@ mod tests {
             vm_core::VirtualMachine,
         },
     };
+    use assert_matches::assert_matches;
 
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::*;
@@ -423,6 +484,58 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn add_page_and_get_public_memory() {
+        let mut builtin = OutputBuiltinRunner::new(true);
+        let mut vm = vm!();
+        builtin.initialize_segments(&mut vm.segments);
+        vm.segments.segment_used_sizes = Some(vec![5]);
+
+        builtin.add_page(1, relocatable!(0, 1), 2).unwrap();
+        builtin.add_page(2, relocatable!(0, 4), 1).unwrap();
+
+        assert_eq!(
+            builtin.get_public_memory(&vm.segments),
+            Ok(vec![(0, 0), (1, 1), (2, 1), (3, 0), (4, 2)])
+        );
+        assert_matches!(
+            builtin.get_additional_data(),
+            BuiltinAdditionalData::Output(OutputBuiltinAdditionalData { pages, .. })
+                if pages.len() == 2 && pages[&1] == PublicMemoryPage { start: 1, size: 2 }
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn add_page_errors() {
+        let mut builtin = OutputBuiltinRunner::new(true);
+        let mut vm = vm!();
+        builtin.initialize_segments(&mut vm.segments);
+        vm.segments.segment_used_sizes = Some(vec![2]);
+
+        assert_eq!(
+            builtin.add_page(0, relocatable!(0, 0), 1),
+            Err(RunnerError::InvalidPublicMemoryPageId(0))
+        );
+        assert_eq!(
+            builtin.add_page(1, relocatable!(1, 0), 1),
+            Err(RunnerError::PublicMemoryPageOutOfSegment(
+                relocatable!(1, 0),
+                OUTPUT_BUILTIN_NAME
+            ))
+        );
+        builtin.add_page(1, relocatable!(0, 1), 2).unwrap();
+        assert_eq!(
+            builtin.add_page(1, relocatable!(0, 0), 1),
+            Err(RunnerError::PublicMemoryPageAlreadyExists(1))
+        );
+        assert_eq!(
+            builtin.get_public_memory(&vm.segments),
+            Err(RunnerError::PublicMemoryPageOutOfBounds(1, 2))
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn test_add_validation_rule() {
@@ -1,7 +1,7 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
 
-use crate::math_utils::safe_div_usize;
+use crate::math_utils::{div_ceil, safe_div_usize};
 use crate::types::instance_definitions::poseidon_instance_def::{
     CELLS_PER_POSEIDON, INPUT_CELLS_PER_POSEIDON,
 };
@@ -12,7 +12,6 @@ use crate::vm::vm_core::VirtualMachine;
 use crate::vm::vm_memory::memory::Memory;
 use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
 use felt::Felt;
-use num_integer::div_ceil;
 use starknet_crypto::{poseidon_permute_comp, FieldElement};
 
 use super::POSEIDON_BUILTIN_NAME;
@@ -63,6 +62,9 @@ impl PoseidonBuiltinRunner {
 
     pub fn add_validation_rule(&self, _memory: &mut Memory) {}
 
+    /// Deduces an output cell of a poseidon instance by running the Hades permutation over its
+    /// three input cells. Returns `Ok(None)` if `address` is an input cell or if any of the
+    /// instance's input cells is missing.
     pub fn deduce_memory_cell(
         &self,
         address: Relocatable,
@@ -83,12 +85,12 @@ impl PoseidonBuiltinRunner {
         for i in 0..self.n_input_cells as usize {
             let val = match memory.get(&(first_input_addr + i)?) {
                 Some(value) => {
-                    let num = value
-                        .get_int_ref()
-                        .ok_or(RunnerError::BuiltinExpectedInteger(
-                            POSEIDON_BUILTIN_NAME,
-                            (first_input_addr + i)?,
-                        ))?;
+                    let num =
+                        value
+                            .get_int_ref()
+                            .ok_or(RunnerError::PoseidonInputCellNotInteger(
+                                (first_input_addr + i)?,
+                            ))?;
                     FieldElement::from_dec_str(&num.to_str_radix(10))
                         .map_err(|_| RunnerError::FailedStringConversion)?
                 }
@@ -163,7 +165,7 @@ impl PoseidonBuiltinRunner {
         segments: &MemorySegmentManager,
     ) -> Result<usize, MemoryError> {
         let used_cells = self.get_used_cells(segments)?;
-        Ok(div_ceil(used_cells, self.cells_per_instance as usize))
+        Ok(div_ceil(used_cells, self.cells_per_instance as usize)?)
     }
 
     pub fn final_stack(
@@ -178,7 +180,7 @@ impl PoseidonBuiltinRunner {
                 .memory
                 .get_relocatable(stop_pointer_addr)
                 .map_err(|_| RunnerError::NoStopPointer(POSEIDON_BUILTIN_NAME))?;
-            if self.base as isize != stop_pointer.segment_index {
+            if !stop_pointer.in_segment(self.base) {
                 return Err(RunnerError::InvalidStopPointerIndex(
                     POSEIDON_BUILTIN_NAME,
                     stop_pointer,
@@ -218,6 +220,7 @@ mod tests {
         errors::memory_errors::MemoryError, runners::builtin_runner::BuiltinRunner,
         vm_core::VirtualMachine,
     };
+    use felt::felt_str;
     use std::collections::HashMap;
 
     #[test]
@@ -253,6 +256,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deduce_memory_cell_outputs() {
+        let builtin = PoseidonBuiltinRunner::new(32, true);
+        let memory = memory![
+            ((0, 0), 1),
+            ((0, 1), 2),
+            ((0, 2), 3),
+            ((0, 6), 0),
+            ((0, 7), 0),
+            ((0, 8), 0)
+        ];
+        let expected_outputs = [
+            (
+                (0, 3),
+                "fa8c9b6742b6176139365833d001e30e932a9bf7456d009b1b174f36d558c5",
+            ),
+            (
+                (0, 4),
+                "4f04deca4cb7f9f2bd16b1d25b817ca2d16fba2151e4252a2e2111cde08bfe6",
+            ),
+            (
+                (0, 5),
+                "58dde0a2a785b395ee2dc7b60b79e9472ab826e9bb5383a8018b59772964892",
+            ),
+            (
+                (0, 9),
+                "79e8d1e78258000a28fc9d49e233bc6852357968577b1e386550ed6a9086133",
+            ),
+            (
+                (0, 10),
+                "3840d003d0f3f96dbb796ff6aa6a63be5b5404b91ccaabca256154cbb6fb984",
+            ),
+            (
+                (0, 11),
+                "1eb39da3f7d3b04142d0ac83d9da00c9325a61fb2ef326e50b70eaa8a3c7cc7",
+            ),
+        ];
+        for (address, output) in expected_outputs {
+            assert_eq!(
+                builtin.deduce_memory_cell(Relocatable::from(address), &memory),
+                Ok(Some(MaybeRelocatable::from(felt_str!(output, 16))))
+            );
+        }
+    }
+
+    #[test]
+    fn deduce_memory_cell_input_cell() {
+        let builtin = PoseidonBuiltinRunner::new(32, true);
+        let memory = memory![((0, 0), 1), ((0, 1), 2), ((0, 2), 3)];
+        for offset in 0..3 {
+            assert_eq!(
+                builtin.deduce_memory_cell(relocatable!(0, offset), &memory),
+                Ok(None)
+            );
+        }
+    }
+
+    #[test]
+    fn deduce_memory_cell_missing_input_cells() {
+        let builtin = PoseidonBuiltinRunner::new(32, true);
+        let memory = memory![((0, 0), 1), ((0, 2), 3), ((0, 6), 0)];
+        assert_eq!(
+            builtin.deduce_memory_cell(relocatable!(0, 3), &memory),
+            Ok(None)
+        );
+        assert_eq!(
+            builtin.deduce_memory_cell(relocatable!(0, 11), &memory),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn deduce_memory_cell_non_integer_input() {
+        let builtin = PoseidonBuiltinRunner::new(32, true);
+        let memory = memory![((0, 0), 1), ((0, 1), (1, 0)), ((0, 2), 3)];
+        assert_eq!(
+            builtin.deduce_memory_cell(relocatable!(0, 4), &memory),
+            Err(RunnerError::PoseidonInputCellNotInteger(relocatable!(0, 1)))
+        );
+    }
+
     #[test]
     fn final_stack_error_stop_pointer() {
         let mut builtin = PoseidonBuiltinRunner::new(10, true);
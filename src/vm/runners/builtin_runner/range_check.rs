@@ -5,9 +5,12 @@ use crate::stdlib::{
 };
 
 use crate::{
+    air_private_input::{PrivateInput, PrivateInputValue},
     math_utils::safe_div_usize,
     types::{
-        instance_definitions::range_check_instance_def::CELLS_PER_RANGE_CHECK,
+        instance_definitions::range_check_instance_def::{
+            CELLS_PER_RANGE_CHECK, RANGE_CHECK_96_N_PARTS,
+        },
         relocatable::{MaybeRelocatable, Relocatable},
     },
     vm::{
@@ -26,7 +29,7 @@ use felt::Felt;
 use num_integer::Integer;
 use num_traits::{One, ToPrimitive, Zero};
 
-use super::RANGE_CHECK_BUILTIN_NAME;
+use super::{RANGE_CHECK_96_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME};
 
 #[derive(Debug, Clone)]
 pub struct RangeCheckBuiltinRunner {
@@ -40,9 +43,12 @@ pub struct RangeCheckBuiltinRunner {
     pub(crate) included: bool,
     n_parts: u32,
     instances_per_component: u32,
+    name: &'static str,
 }
 
 impl RangeCheckBuiltinRunner {
+    /// Creates a range check builtin checking values against `2^(16 * n_parts)`.
+    /// The standard builtin uses 8 parts (128 bits), while the 96-bit variant uses 6.
     pub fn new(ratio: u32, n_parts: u32, included: bool) -> RangeCheckBuiltinRunner {
         let inner_rc_bound = 1_usize << 16;
 
@@ -64,6 +70,15 @@ impl RangeCheckBuiltinRunner {
             included,
             n_parts,
             instances_per_component: 1,
+            name: RANGE_CHECK_BUILTIN_NAME,
+        }
+    }
+
+    /// Creates a `range_check96` builtin, checking values against `2^96`
+    pub fn new_range_check96(ratio: u32, included: bool) -> RangeCheckBuiltinRunner {
+        RangeCheckBuiltinRunner {
+            name: RANGE_CHECK_96_BUILTIN_NAME,
+            ..RangeCheckBuiltinRunner::new(ratio, RANGE_CHECK_96_N_PARTS, included)
         }
     }
 
@@ -87,19 +102,20 @@ impl RangeCheckBuiltinRunner {
         self.ratio
     }
 
+    /// Checks that every value written to the builtin segment is lower than `2^(16 * n_parts)`,
+    /// i.e. `2^128` for the default 8 parts and `2^96` for a 6-part range check.
     pub fn add_validation_rule(&self, memory: &mut Memory) {
+        let bound = self._bound.clone();
         let rule: ValidationRule = ValidationRule(Box::new(
-            |memory: &Memory, address: Relocatable| -> Result<Vec<Relocatable>, MemoryError> {
+            move |memory: &Memory, address: Relocatable| -> Result<Vec<Relocatable>, MemoryError> {
                 let num = memory
                     .get_integer(address)
                     .map_err(|_| MemoryError::RangeCheckFoundNonInt(address))?;
-                if &Felt::zero() <= num.as_ref() && num.as_ref() < &Felt::one().shl(128_usize) {
-                    Ok(vec![address.to_owned()])
-                } else {
-                    Err(MemoryError::RangeCheckNumOutOfBounds(
-                        num.into_owned(),
-                        Felt::one().shl(128_usize),
-                    ))
+                match &bound {
+                    Some(bound) if num.as_ref() >= bound => Err(
+                        MemoryError::RangeCheckNumOutOfBounds(num.into_owned(), bound.clone()),
+                    ),
+                    _ => Ok(vec![address.to_owned()]),
                 }
             },
         ));
@@ -137,42 +153,48 @@ impl RangeCheckBuiltinRunner {
         let ratio = self.ratio as usize;
         let min_step = ratio * self.instances_per_component as usize;
         if vm.current_step < min_step {
-            Err(InsufficientAllocatedCellsError::MinStepNotReached(
-                min_step,
-                RANGE_CHECK_BUILTIN_NAME,
-            )
-            .into())
+            Err(InsufficientAllocatedCellsError::MinStepNotReached(min_step, self.name).into())
         } else {
             let used = self.get_used_cells(&vm.segments)?;
             let size = self.cells_per_instance as usize
                 * safe_div_usize(vm.current_step, ratio).map_err(|_| {
                     InsufficientAllocatedCellsError::CurrentStepNotDivisibleByBuiltinRatio(
-                        RANGE_CHECK_BUILTIN_NAME,
+                        self.name,
                         vm.current_step,
                         ratio,
                     )
                 })?;
             if used > size {
-                return Err(InsufficientAllocatedCellsError::BuiltinCells(
-                    RANGE_CHECK_BUILTIN_NAME,
-                    used,
-                    size,
-                )
-                .into());
+                return Err(
+                    InsufficientAllocatedCellsError::BuiltinCells(self.name, used, size).into(),
+                );
             }
             Ok((used, size))
         }
     }
 
+    /// Returns the value checked by each range check instance, as expected by the prover
+    pub fn air_private_input(&self, memory: &Memory) -> Vec<PrivateInput> {
+        memory
+            .segment_cells(self.base)
+            .filter_map(|(index, cell)| {
+                Some(PrivateInput::Value(PrivateInputValue {
+                    index,
+                    value: cell.get_value().get_int_ref()?.clone(),
+                }))
+            })
+            .collect()
+    }
+
     pub fn get_range_check_usage(&self, memory: &Memory) -> Option<(usize, usize)> {
         let mut rc_bounds: Option<(usize, usize)> = None;
-        let range_check_segment = memory.data.get(self.base)?;
         let inner_rc_bound = Felt::new(self.inner_rc_bound);
-        for value in range_check_segment {
+        // Walks both the dense and the sparse cells of the segment, skipping the gaps like the
+        // Python VM does
+        for (_, cell) in memory.segment_cells(self.base) {
             //Split val into n_parts parts.
             for _ in 0..self.n_parts {
-                let part_val = value
-                    .as_ref()?
+                let part_val = cell
                     .get_value()
                     .get_int_ref()?
                     .mod_floor(&inner_rc_bound)
@@ -205,14 +227,14 @@ impl RangeCheckBuiltinRunner {
     ) -> Result<Relocatable, RunnerError> {
         if self.included {
             let stop_pointer_addr =
-                (pointer - 1).map_err(|_| RunnerError::NoStopPointer(RANGE_CHECK_BUILTIN_NAME))?;
+                (pointer - 1).map_err(|_| RunnerError::NoStopPointer(self.name))?;
             let stop_pointer = segments
                 .memory
                 .get_relocatable(stop_pointer_addr)
-                .map_err(|_| RunnerError::NoStopPointer(RANGE_CHECK_BUILTIN_NAME))?;
-            if self.base as isize != stop_pointer.segment_index {
+                .map_err(|_| RunnerError::NoStopPointer(self.name))?;
+            if !stop_pointer.in_segment(self.base) {
                 return Err(RunnerError::InvalidStopPointerIndex(
-                    RANGE_CHECK_BUILTIN_NAME,
+                    self.name,
                     stop_pointer,
                     self.base,
                 ));
@@ -222,7 +244,7 @@ impl RangeCheckBuiltinRunner {
             let used = num_instances * self.cells_per_instance as usize;
             if stop_ptr != used {
                 return Err(RunnerError::InvalidStopPointer(
-                    RANGE_CHECK_BUILTIN_NAME,
+                    self.name,
                     Relocatable::from((self.base as isize, used)),
                     Relocatable::from((self.base as isize, stop_ptr)),
                 ));
@@ -251,7 +273,8 @@ mod tests {
     use super::*;
     use crate::relocatable;
     use crate::stdlib::collections::HashMap;
-    use crate::vm::vm_memory::memory::Memory;
+    use crate::types::instance_definitions::range_check_instance_def::RangeCheckInstanceDef;
+    use crate::vm::vm_memory::memory::{Memory, SPARSE_SEGMENT_GAP};
     use crate::{
         hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
         types::program::Program,
@@ -488,6 +511,26 @@ mod tests {
         assert_eq!(initial_stack.len(), 1);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn air_private_input() {
+        let builtin = RangeCheckBuiltinRunner::new(10, 8, true);
+        let memory = memory![((0, 0), 5), ((0, 1), (1, 0)), ((0, 2), 0xffff)];
+        assert_eq!(
+            builtin.air_private_input(&memory),
+            vec![
+                PrivateInput::Value(PrivateInputValue {
+                    index: 0,
+                    value: Felt::new(5),
+                }),
+                PrivateInput::Value(PrivateInputValue {
+                    index: 2,
+                    value: Felt::new(0xffff),
+                }),
+            ]
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_memory_segment_addresses() {
@@ -626,6 +669,130 @@ mod tests {
         assert_eq!(builtin.get_range_check_usage(&memory), None);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_range_check_usage_sparse_segment() {
+        let builtin = RangeCheckBuiltinRunner::new(8, 8, true);
+        let mut memory = memory![((0, 0), 1), ((0, 1), 2)];
+        // A cell past the gap moves the segment to the sparse backend
+        memory
+            .insert_value(relocatable!(0, SPARSE_SEGMENT_GAP + 2), Felt::new(7))
+            .unwrap();
+        assert!(memory.sparse_data.contains_key(&0));
+        assert_eq!(builtin.get_range_check_usage(&memory), Some((1, 7)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn validation_rule_96_bit() {
+        let instance_def = RangeCheckInstanceDef::new(8, 6);
+        let mut builtin =
+            RangeCheckBuiltinRunner::new(instance_def.ratio, instance_def.n_parts, true);
+        let mut segments = MemorySegmentManager::new();
+        builtin.initialize_segments(&mut segments);
+        builtin.add_validation_rule(&mut segments.memory);
+        let bound = Felt::one().shl(96_u32);
+
+        segments
+            .memory
+            .insert_value(relocatable!(0, 0), Felt::zero())
+            .unwrap();
+        segments
+            .memory
+            .insert_value(relocatable!(0, 1), &bound - 1_u32)
+            .unwrap();
+        assert_eq!(
+            segments
+                .memory
+                .insert_value(relocatable!(0, 2), bound.clone()),
+            Err(MemoryError::RangeCheckNumOutOfBounds(
+                bound.clone(),
+                bound.clone()
+            ))
+        );
+        assert_eq!(
+            segments
+                .memory
+                .insert_value(relocatable!(0, 3), Felt::one().shl(100_u32)),
+            Err(MemoryError::RangeCheckNumOutOfBounds(
+                Felt::one().shl(100_u32),
+                bound
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn validation_rule_128_bit_accepts_values_above_96_bits() {
+        let mut builtin = RangeCheckBuiltinRunner::new(8, 8, true);
+        let mut segments = MemorySegmentManager::new();
+        builtin.initialize_segments(&mut segments);
+        builtin.add_validation_rule(&mut segments.memory);
+
+        assert_eq!(
+            segments
+                .memory
+                .insert_value(relocatable!(0, 0), Felt::one().shl(100_u32)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn range_check96_validation_rule_boundaries() {
+        let mut builtin: BuiltinRunner = RangeCheckBuiltinRunner::new_range_check96(8, true).into();
+        let mut segments = MemorySegmentManager::new();
+        builtin.initialize_segments(&mut segments);
+        builtin.add_validation_rule(&mut segments.memory);
+        let bound = Felt::one().shl(96_u32);
+
+        assert_eq!(
+            segments
+                .memory
+                .insert_value(relocatable!(0, 0), &bound - 1_u32),
+            Ok(())
+        );
+        assert_eq!(
+            segments
+                .memory
+                .insert_value(relocatable!(0, 1), bound.clone()),
+            Err(MemoryError::RangeCheckNumOutOfBounds(bound.clone(), bound))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn range_check96_final_stack_error_uses_its_name() {
+        let mut builtin = RangeCheckBuiltinRunner::new_range_check96(8, true);
+
+        let mut vm = vm!();
+
+        vm.segments = segments![
+            ((0, 0), (0, 0)),
+            ((0, 1), (0, 1)),
+            ((2, 0), (0, 0)),
+            ((2, 1), 2)
+        ];
+
+        vm.segments.segment_used_sizes = Some(vec![0]);
+
+        assert_eq!(
+            builtin.final_stack(&vm.segments, Relocatable::from((2, 2))),
+            Err(RunnerError::NoStopPointer(RANGE_CHECK_96_BUILTIN_NAME))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_used_perm_range_check_units_96_bit() {
+        let builtin_runner = RangeCheckBuiltinRunner::new(8, 6, true);
+        let mut vm = vm!();
+
+        vm.current_step = 8;
+        vm.segments.segment_used_sizes = Some(vec![1]);
+        assert_eq!(builtin_runner.get_used_perm_range_check_units(&vm), Ok(6));
+    }
+
     /// Test that the method get_used_perm_range_check_units works as intended.
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
@@ -0,0 +1,410 @@
+use crate::math_utils::div_ceil;
+use crate::stdlib::prelude::*;
+use crate::types::errors::math_errors::MathError;
+use crate::types::relocatable::{MaybeRelocatable, Relocatable};
+use crate::vm::errors::memory_errors::MemoryError;
+use crate::vm::errors::runner_errors::RunnerError;
+use crate::vm::vm_core::VirtualMachine;
+use crate::vm::vm_memory::memory::Memory;
+use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
+use felt::Felt;
+use num_traits::{ToPrimitive, Zero};
+
+use super::SEGMENT_ARENA_BUILTIN_NAME;
+
+// Each instance holds the segment arena info: a pointer to the segment infos, the number of
+// segments and the number of finalized segments.
+pub(crate) const ARENA_BUILTIN_SIZE: u32 = 3;
+// The builtin segment starts with the info of an empty arena, written at initialization.
+const INITIAL_SEGMENT_SIZE: usize = ARENA_BUILTIN_SIZE as usize;
+// Each segment info holds the segment's start, its end and its finalization index.
+const SEGMENT_INFO_SIZE: usize = 3;
+
+/// A segment allocated through the segment arena. `end` is only known once the segment is
+/// finalized, at which point it is marked as squashed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArenaSegmentInfo {
+    pub start: Relocatable,
+    pub end: Option<Relocatable>,
+    pub squashed: bool,
+}
+
+/// Runner of the `segment_arena` builtin used by Cairo 1 programs to manage dictionaries and
+/// other dynamically allocated segments. Unlike the other builtins, its base doesn't point to
+/// the start of its segment but right after the initial arena info.
+#[derive(Debug, Clone)]
+pub struct SegmentArenaBuiltinRunner {
+    base: Relocatable,
+    pub(crate) cells_per_instance: u32,
+    pub(crate) n_input_cells: u32,
+    pub(crate) stop_ptr: Option<usize>,
+    pub(crate) included: bool,
+}
+
+impl SegmentArenaBuiltinRunner {
+    pub fn new(included: bool) -> Self {
+        SegmentArenaBuiltinRunner {
+            base: Relocatable::from((0, 0)),
+            cells_per_instance: ARENA_BUILTIN_SIZE,
+            n_input_cells: ARENA_BUILTIN_SIZE,
+            stop_ptr: None,
+            included,
+        }
+    }
+
+    /// Creates the builtin segment and the segment holding the arena's segment infos, and
+    /// writes the info of an empty arena at the start of the builtin segment.
+    pub fn initialize_segments(&mut self, segments: &mut MemorySegmentManager) {
+        let segment_start = segments.add();
+        let info = vec![
+            MaybeRelocatable::from(segments.add()),
+            MaybeRelocatable::from(Felt::zero()),
+            MaybeRelocatable::from(Felt::zero()),
+        ];
+        // Writing to a freshly created segment can't fail
+        self.base = segments
+            .load_data(segment_start, &info)
+            .expect("Failed to write the initial segment arena info");
+    }
+
+    pub fn initial_stack(&self) -> Vec<MaybeRelocatable> {
+        if self.included {
+            vec![MaybeRelocatable::from(self.base)]
+        } else {
+            vec![]
+        }
+    }
+
+    pub fn base(&self) -> usize {
+        self.base.segment_index as usize
+    }
+
+    pub fn add_validation_rule(&self, _memory: &mut Memory) {}
+
+    pub fn deduce_memory_cell(
+        &self,
+        _address: Relocatable,
+        _memory: &Memory,
+    ) -> Result<Option<MaybeRelocatable>, RunnerError> {
+        Ok(None)
+    }
+
+    pub fn get_allocated_memory_units(&self, _vm: &VirtualMachine) -> Result<usize, MemoryError> {
+        Ok(0)
+    }
+
+    pub fn get_memory_segment_addresses(&self) -> (usize, Option<usize>) {
+        (self.base(), self.stop_ptr)
+    }
+
+    /// Returns the number of cells used after the initial arena info
+    pub fn get_used_cells(&self, segments: &MemorySegmentManager) -> Result<usize, MemoryError> {
+        let used = segments
+            .get_segment_used_size(self.base())
+            .ok_or(MemoryError::MissingSegmentUsedSizes)?;
+        used.checked_sub(INITIAL_SEGMENT_SIZE)
+            .ok_or(MemoryError::InvalidUsedSizeSegmentArena(used))
+    }
+
+    pub fn get_used_cells_and_allocated_size(
+        &self,
+        vm: &VirtualMachine,
+    ) -> Result<(usize, usize), MemoryError> {
+        let used = self.get_used_cells(&vm.segments)?;
+        Ok((used, used))
+    }
+
+    pub fn get_used_instances(
+        &self,
+        segments: &MemorySegmentManager,
+    ) -> Result<usize, MemoryError> {
+        Ok(div_ceil(
+            self.get_used_cells(segments)?,
+            self.cells_per_instance as usize,
+        )?)
+    }
+
+    pub fn final_stack(
+        &mut self,
+        segments: &MemorySegmentManager,
+        pointer: Relocatable,
+    ) -> Result<Relocatable, RunnerError> {
+        if self.included {
+            let stop_pointer_addr = (pointer - 1)
+                .map_err(|_| RunnerError::NoStopPointer(SEGMENT_ARENA_BUILTIN_NAME))?;
+            let stop_pointer = segments
+                .memory
+                .get_relocatable(stop_pointer_addr)
+                .map_err(|_| RunnerError::NoStopPointer(SEGMENT_ARENA_BUILTIN_NAME))?;
+            if !stop_pointer.in_segment(self.base()) {
+                return Err(RunnerError::InvalidStopPointerIndex(
+                    SEGMENT_ARENA_BUILTIN_NAME,
+                    stop_pointer,
+                    self.base(),
+                ));
+            }
+            let used = self.get_used_cells(segments).map_err(RunnerError::Memory)?;
+            let expected_stop_pointer = (self.base + used)?;
+            if stop_pointer != expected_stop_pointer {
+                return Err(RunnerError::InvalidStopPointer(
+                    SEGMENT_ARENA_BUILTIN_NAME,
+                    expected_stop_pointer,
+                    stop_pointer,
+                ));
+            }
+            self.stop_ptr = Some(stop_pointer.offset);
+            Ok(stop_pointer_addr)
+        } else {
+            self.stop_ptr = Some(self.base.offset);
+            Ok(pointer)
+        }
+    }
+
+    /// Returns the info of each segment allocated through the arena, read from the last arena
+    /// info written to the builtin segment
+    pub fn get_segment_infos(&self, memory: &Memory) -> Result<Vec<ArenaSegmentInfo>, MemoryError> {
+        let last_info_offset = memory
+            .segment_len(self.base())
+            .saturating_sub(ARENA_BUILTIN_SIZE as usize);
+        let last_info = Relocatable::from((self.base.segment_index, last_info_offset));
+        let infos = memory.get_relocatable(last_info)?;
+        let n_segments = memory.get_integer((last_info + 1_usize)?)?;
+        let n_segments = n_segments
+            .to_usize()
+            .ok_or_else(|| MathError::FeltToUsizeConversion(n_segments.into_owned()))?;
+        (0..n_segments)
+            .map(|i| {
+                let info = (infos + i * SEGMENT_INFO_SIZE)?;
+                let end = (info + 1_usize)?;
+                Ok(ArenaSegmentInfo {
+                    start: memory.get_relocatable(info)?,
+                    end: memory
+                        .get(&end)
+                        .map(|_| memory.get_relocatable(end))
+                        .transpose()?,
+                    squashed: memory.get(&(info + 2_usize)?).is_some(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for SegmentArenaBuiltinRunner {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::relocatable;
+    use crate::stdlib::collections::HashMap;
+    use crate::utils::test_utils::*;
+    use crate::vm::runners::builtin_runner::BuiltinRunner;
+    use assert_matches::assert_matches;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn initialize_segments_writes_empty_arena_info() {
+        let mut builtin = SegmentArenaBuiltinRunner::new(true);
+        let mut segments = MemorySegmentManager::new();
+        builtin.initialize_segments(&mut segments);
+        assert_eq!(builtin.base(), 0);
+        assert_eq!(builtin.initial_stack(), vec![mayberelocatable!(0, 3)]);
+        check_memory!(segments.memory, ((0, 0), (1, 0)), ((0, 1), 0), ((0, 2), 0));
+        assert_eq!(segments.num_segments(), 2);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn initial_stack_not_included() {
+        let mut builtin = SegmentArenaBuiltinRunner::new(false);
+        let mut segments = MemorySegmentManager::new();
+        builtin.initialize_segments(&mut segments);
+        assert!(builtin.initial_stack().is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_used_cells_and_instances() {
+        let builtin = SegmentArenaBuiltinRunner::new(true);
+        let mut vm = vm!();
+        vm.segments.segment_used_sizes = Some(vec![9]);
+        assert_eq!(builtin.get_used_cells(&vm.segments), Ok(6));
+        assert_eq!(builtin.get_used_instances(&vm.segments), Ok(2));
+        assert_eq!(builtin.get_used_cells_and_allocated_size(&vm), Ok((6, 6)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_used_cells_missing_segment_used_sizes() {
+        let builtin = SegmentArenaBuiltinRunner::new(true);
+        let vm = vm!();
+        assert_eq!(
+            builtin.get_used_cells(&vm.segments),
+            Err(MemoryError::MissingSegmentUsedSizes)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_used_cells_smaller_than_initial_info() {
+        let builtin = SegmentArenaBuiltinRunner::new(true);
+        let mut vm = vm!();
+        vm.segments.segment_used_sizes = Some(vec![2]);
+        assert_eq!(
+            builtin.get_used_cells(&vm.segments),
+            Err(MemoryError::InvalidUsedSizeSegmentArena(2))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_segment_infos_empty_arena() {
+        let mut builtin = SegmentArenaBuiltinRunner::new(true);
+        let mut segments = MemorySegmentManager::new();
+        builtin.initialize_segments(&mut segments);
+        assert_eq!(builtin.get_segment_infos(&segments.memory), Ok(vec![]));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_segment_infos_unfinalized_segment() {
+        let mut builtin = SegmentArenaBuiltinRunner::new(true);
+        let mut vm = vm!();
+        builtin.initialize_segments(&mut vm.segments);
+        vm.segments = segments![
+            ((0, 0), (1, 0)),
+            ((0, 1), 0),
+            ((0, 2), 0),
+            ((0, 3), (1, 0)),
+            ((0, 4), 1),
+            ((0, 5), 0),
+            ((1, 0), (2, 0))
+        ];
+        assert_eq!(
+            builtin.get_segment_infos(&vm.segments.memory),
+            Ok(vec![ArenaSegmentInfo {
+                start: relocatable!(2, 0),
+                end: None,
+                squashed: false,
+            }])
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn final_stack() {
+        let mut builtin = SegmentArenaBuiltinRunner::new(true);
+        let mut vm = vm!();
+        builtin.initialize_segments(&mut vm.segments);
+        vm.segments = segments![
+            ((0, 0), (1, 0)),
+            ((0, 1), 0),
+            ((0, 2), 0),
+            ((0, 3), (1, 0)),
+            ((0, 4), 1),
+            ((0, 5), 0),
+            ((2, 0), (0, 6))
+        ];
+        vm.segments.segment_used_sizes = Some(vec![6, 0, 1]);
+        let pointer = Relocatable::from((2, 1));
+        assert_eq!(
+            builtin.final_stack(&vm.segments, pointer),
+            Ok(Relocatable::from((2, 0)))
+        );
+        assert_eq!(builtin.stop_ptr, Some(6));
+        assert_eq!(builtin.get_memory_segment_addresses(), (0, Some(6)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn final_stack_error_stop_pointer() {
+        let mut builtin = SegmentArenaBuiltinRunner::new(true);
+        let mut vm = vm!();
+        builtin.initialize_segments(&mut vm.segments);
+        vm.segments = segments![
+            ((0, 0), (1, 0)),
+            ((0, 1), 0),
+            ((0, 2), 0),
+            ((0, 3), (1, 0)),
+            ((0, 4), 1),
+            ((0, 5), 0),
+            ((2, 0), (0, 3))
+        ];
+        vm.segments.segment_used_sizes = Some(vec![6, 0, 1]);
+        let pointer = Relocatable::from((2, 1));
+        assert_eq!(
+            builtin.final_stack(&vm.segments, pointer),
+            Err(RunnerError::InvalidStopPointer(
+                SEGMENT_ARENA_BUILTIN_NAME,
+                relocatable!(0, 6),
+                relocatable!(0, 3)
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn final_stack_error_stop_pointer_index() {
+        let mut builtin = SegmentArenaBuiltinRunner::new(true);
+        let mut vm = vm!();
+        builtin.initialize_segments(&mut vm.segments);
+        vm.segments = segments![((0, 0), (1, 0)), ((0, 1), 0), ((0, 2), 0), ((2, 0), (1, 3))];
+        vm.segments.segment_used_sizes = Some(vec![3, 0, 1]);
+        let pointer = Relocatable::from((2, 1));
+        assert_eq!(
+            builtin.final_stack(&vm.segments, pointer),
+            Err(RunnerError::InvalidStopPointerIndex(
+                SEGMENT_ARENA_BUILTIN_NAME,
+                relocatable!(1, 3),
+                0
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn final_stack_no_stop_pointer() {
+        let mut builtin = SegmentArenaBuiltinRunner::new(true);
+        let mut vm = vm!();
+        builtin.initialize_segments(&mut vm.segments);
+        vm.segments.segment_used_sizes = Some(vec![3, 0]);
+        assert_eq!(
+            builtin.final_stack(&vm.segments, Relocatable::from((1, 0))),
+            Err(RunnerError::NoStopPointer(SEGMENT_ARENA_BUILTIN_NAME))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn final_stack_not_included() {
+        let mut builtin = SegmentArenaBuiltinRunner::new(false);
+        let mut vm = vm!();
+        builtin.initialize_segments(&mut vm.segments);
+        let pointer = Relocatable::from((2, 1));
+        assert_eq!(builtin.final_stack(&vm.segments, pointer), Ok(pointer));
+        assert_eq!(builtin.stop_ptr, Some(3));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn builtin_runner_dispatch() {
+        let mut builtin: BuiltinRunner = SegmentArenaBuiltinRunner::new(true).into();
+        let mut vm = vm!();
+        builtin.initialize_segments(&mut vm.segments);
+        assert_eq!(builtin.name(), SEGMENT_ARENA_BUILTIN_NAME);
+        assert_eq!(builtin.ratio(), None);
+        assert_eq!(builtin.initial_stack(), vec![mayberelocatable!(0, 3)]);
+        assert_eq!(builtin.get_allocated_memory_units(&vm), Ok(0));
+        assert_matches!(
+            builtin.deduce_memory_cell(relocatable!(0, 3), &vm.segments.memory),
+            Ok(None)
+        );
+        assert_matches!(builtin.run_security_checks(&vm), Ok(()));
+    }
+}
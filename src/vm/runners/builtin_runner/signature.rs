@@ -1,16 +1,18 @@
-use crate::stdlib::{cell::RefCell, collections::HashMap, prelude::*, rc::Rc};
+use crate::stdlib::{collections::HashMap, prelude::*};
 
 use crate::{
-    math_utils::safe_div_usize,
+    math_utils::{div_ceil, safe_div_usize},
     types::{
         instance_definitions::ecdsa_instance_def::EcdsaInstanceDef,
         relocatable::{MaybeRelocatable, Relocatable},
+        shared::Shared,
     },
     vm::{
         errors::{
             memory_errors::{InsufficientAllocatedCellsError, MemoryError},
             runner_errors::RunnerError,
         },
+        runners::cairo_pie::BuiltinAdditionalData,
         vm_core::VirtualMachine,
         vm_memory::{
             memory::{Memory, ValidationRule},
@@ -19,7 +21,6 @@ use crate::{
     },
 };
 use felt::Felt;
-use num_integer::div_ceil;
 use starknet_crypto::{verify, FieldElement, Signature};
 
 use super::SIGNATURE_BUILTIN_NAME;
@@ -34,7 +35,7 @@ pub struct SignatureBuiltinRunner {
     _total_n_bits: u32,
     pub(crate) stop_ptr: Option<usize>,
     instances_per_component: u32,
-    signatures: Rc<RefCell<HashMap<Relocatable, Signature>>>,
+    signatures: Shared<HashMap<Relocatable, Signature>>,
 }
 
 impl SignatureBuiltinRunner {
@@ -48,10 +49,15 @@ impl SignatureBuiltinRunner {
             _total_n_bits: 251,
             stop_ptr: None,
             instances_per_component: 1,
-            signatures: Rc::new(RefCell::new(HashMap::new())),
+            signatures: Shared::new(HashMap::new()),
         }
     }
 
+    /// Records the (r, s) signature of the instance whose public key is stored at `relocatable`.
+    /// The validation rule verifies it against the instance's public key and message once both
+    /// are written to memory, so embedders can provide signatures without hints by calling this
+    /// on the runner from `VirtualMachine::get_builtin_runners_as_mut` before running.
+    /// If the address already has a signature, it is kept.
     pub fn add_signature(
         &mut self,
         relocatable: Relocatable,
@@ -98,7 +104,7 @@ impl SignatureBuiltinRunner {
     }
     pub fn add_validation_rule(&self, memory: &mut Memory) {
         let cells_per_instance = self.cells_per_instance;
-        let signatures = Rc::clone(&self.signatures);
+        let signatures = self.signatures.clone();
         let rule: ValidationRule = ValidationRule(Box::new(
             move |memory: &Memory, addr: Relocatable| -> Result<Vec<Relocatable>, MemoryError> {
                 let cell_index = addr.offset % cells_per_instance as usize;
@@ -169,6 +175,27 @@ impl SignatureBuiltinRunner {
         (self.base, self.stop_ptr)
     }
 
+    pub fn get_additional_data(&self) -> BuiltinAdditionalData {
+        BuiltinAdditionalData::Signature(self.get_signatures())
+    }
+
+    /// Returns the (r, s) signatures added so far, by public key address
+    pub(crate) fn get_signatures(&self) -> HashMap<Relocatable, (Felt, Felt)> {
+        self.signatures
+            .borrow()
+            .iter()
+            .map(|(addr, signature)| {
+                (
+                    *addr,
+                    (
+                        Felt::from_bytes_be(&signature.r.to_bytes_be()),
+                        Felt::from_bytes_be(&signature.s.to_bytes_be()),
+                    ),
+                )
+            })
+            .collect()
+    }
+
     pub fn get_used_cells(&self, segments: &MemorySegmentManager) -> Result<usize, MemoryError> {
         segments
             .get_segment_used_size(self.base)
@@ -214,7 +241,7 @@ impl SignatureBuiltinRunner {
         segments: &MemorySegmentManager,
     ) -> Result<usize, MemoryError> {
         let used_cells = self.get_used_cells(segments)?;
-        Ok(div_ceil(used_cells, self.cells_per_instance as usize))
+        Ok(div_ceil(used_cells, self.cells_per_instance as usize)?)
     }
 
     pub fn final_stack(
@@ -229,7 +256,7 @@ impl SignatureBuiltinRunner {
                 .memory
                 .get_relocatable(stop_pointer_addr)
                 .map_err(|_| RunnerError::NoStopPointer(SIGNATURE_BUILTIN_NAME))?;
-            if self.base as isize != stop_pointer.segment_index {
+            if !stop_pointer.in_segment(self.base) {
                 return Err(RunnerError::InvalidStopPointerIndex(
                     SIGNATURE_BUILTIN_NAME,
                     stop_pointer,
@@ -271,9 +298,85 @@ mod tests {
         },
     };
 
+    use assert_matches::assert_matches;
+    use felt::felt_str;
+
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::*;
 
+    fn vm_with_signature(signature: (Felt, Felt)) -> VirtualMachine {
+        let mut vm = vm!();
+        let mut builtin = SignatureBuiltinRunner::new(&EcdsaInstanceDef::default(), true);
+        builtin.initialize_segments(&mut vm.segments);
+        vm.builtin_runners = vec![(SIGNATURE_BUILTIN_NAME, builtin.into())];
+        match &mut vm.get_builtin_runners_as_mut()[0].1 {
+            BuiltinRunner::Signature(builtin) => builtin
+                .add_signature(relocatable!(0, 0), &signature)
+                .unwrap(),
+            _ => unreachable!(),
+        }
+        vm.builtin_runners[0]
+            .1
+            .add_validation_rule(&mut vm.segments.memory);
+        vm
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn add_signature_valid() {
+        let mut vm = vm_with_signature((
+            felt_str!(
+                "1839793652349538280924927302501143912227271479439798783640887258675143576352"
+            ),
+            felt_str!(
+                "1819432147005223164874083361865404672584671743718628757598322238853218813979"
+            ),
+        ));
+        let pubkey = felt_str!(
+            "874739451078007766457464989774322083649278607533249481151382481072868806602"
+        );
+
+        assert_eq!(
+            vm.segments.memory.insert_value(relocatable!(0, 0), pubkey),
+            Ok(())
+        );
+        assert_eq!(
+            vm.segments
+                .memory
+                .insert_value(relocatable!(0, 1), Felt::new(2)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn add_signature_tampered_s() {
+        let mut vm = vm_with_signature((
+            felt_str!(
+                "1839793652349538280924927302501143912227271479439798783640887258675143576352"
+            ),
+            felt_str!(
+                "1819432147005223164874083361865404672584671743718628757598322238853218813980"
+            ),
+        ));
+        let pubkey = felt_str!(
+            "874739451078007766457464989774322083649278607533249481151382481072868806602"
+        );
+
+        assert_eq!(
+            vm.segments
+                .memory
+                .insert_value(relocatable!(0, 0), pubkey.clone()),
+            Ok(())
+        );
+        assert_matches!(
+            vm.segments
+                .memory
+                .insert_value(relocatable!(0, 1), Felt::new(2)),
+            Err(MemoryError::InvalidSignature(_, key, msg)) if key == pubkey && msg == Felt::new(2)
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_used_cells_and_allocated_size_min_step_not_reached() {
@@ -0,0 +1,511 @@
+use crate::stdlib::{collections::HashMap, prelude::*};
+
+use super::{
+    builtin_runner::{HASH_BUILTIN_NAME, OUTPUT_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME},
+    cairo_runner::{ExecutionResources, SegmentInfo},
+};
+use crate::{
+    serde::deserialize_program::{deserialize_array_of_bigint_hex, BuiltinName},
+    types::{
+        program::Program,
+        relocatable::{MaybeRelocatable, Relocatable},
+    },
+    vm::{errors::runner_errors::RunnerError, vm_memory::memory::Memory},
+};
+use felt::Felt;
+use serde::{
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use starknet_crypto::{pedersen_hash, FieldElement};
+
+#[cfg(feature = "std")]
+use std::io::{Read, Seek, Write};
+
+/// Version of the Cairo PIE format written by [CairoPie::write_zip]
+pub const CAIRO_PIE_VERSION: &str = "1.0";
+
+// Sizes used by the Python VM to serialize the PIE memory
+const ADDR_BYTE_LEN: usize = 8;
+const FIELD_BYTE_LEN: usize = 32;
+const OFFSET_BIT_LEN: usize = 47;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PublicMemoryPage {
+    pub start: usize,
+    pub size: usize,
+}
+
+// Data used by the output builtin to describe the public memory pages and their attributes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OutputBuiltinAdditionalData {
+    pub pages: HashMap<usize, PublicMemoryPage>,
+    pub attributes: HashMap<String, Vec<usize>>,
+}
+
+/// Builtin specific data required to re-execute a Cairo PIE
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuiltinAdditionalData {
+    // Contains verified addresses as contiguous index, value pairs
+    Hash(Vec<Relocatable>),
+    Output(OutputBuiltinAdditionalData),
+    // Signatures are composed of (r, s) tuples
+    Signature(HashMap<Relocatable, (Felt, Felt)>),
+    None,
+}
+
+/// Memory cells of a run, stored as ((segment_index, offset), value) pairs
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct CairoPieMemory(pub Vec<((usize, usize), MaybeRelocatable)>);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StrippedProgram {
+    #[serde(
+        serialize_with = "serialize_program_data",
+        deserialize_with = "deserialize_array_of_bigint_hex"
+    )]
+    pub data: Vec<MaybeRelocatable>,
+    #[serde(deserialize_with = "deserialize_builtin_names")]
+    pub builtins: Vec<&'static str>,
+    pub main: usize,
+    pub prime: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CairoPieMetadata {
+    pub program: StrippedProgram,
+    pub program_segment: SegmentInfo,
+    pub execution_segment: SegmentInfo,
+    pub ret_fp_segment: SegmentInfo,
+    pub ret_pc_segment: SegmentInfo,
+    pub builtin_segments: HashMap<String, SegmentInfo>,
+    pub extra_segments: Vec<SegmentInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CairoPieVersion {
+    pub cairo_pie: String,
+}
+
+impl Default for CairoPieVersion {
+    fn default() -> Self {
+        CairoPieVersion {
+            cairo_pie: CAIRO_PIE_VERSION.to_string(),
+        }
+    }
+}
+
+/// A Cairo PIE (Position Independent Execution) contains all the information needed to
+/// re-execute or prove a run without the original hints
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CairoPie {
+    pub metadata: CairoPieMetadata,
+    pub memory: CairoPieMemory,
+    pub execution_resources: ExecutionResources,
+    pub additional_data: HashMap<String, BuiltinAdditionalData>,
+    pub version: CairoPieVersion,
+}
+
+impl From<&Memory> for CairoPieMemory {
+    fn from(memory: &Memory) -> Self {
+        let mut pie_memory = Vec::new();
+        for segment_index in 0..memory.data.len() {
+            for (offset, cell) in memory.segment_cells(segment_index) {
+                pie_memory.push(((segment_index, offset), cell.get_value().clone()));
+            }
+        }
+        CairoPieMemory(pie_memory)
+    }
+}
+
+impl CairoPieMemory {
+    /// Serializes the memory in the format used by `memory.bin`:
+    /// * address -> 8-byte little endian encoded relocatable
+    /// * value -> 32-byte little endian encoded felt or relocatable
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.0.len() * (ADDR_BYTE_LEN + FIELD_BYTE_LEN));
+        for ((segment_index, offset), value) in self.0.iter() {
+            bytes.extend_from_slice(&encode_relocatable(*segment_index, *offset, ADDR_BYTE_LEN));
+            match value {
+                MaybeRelocatable::Int(num) => {
+                    let mut value_bytes = num.to_biguint().to_bytes_le();
+                    value_bytes.resize(FIELD_BYTE_LEN, 0);
+                    bytes.extend_from_slice(&value_bytes);
+                }
+                MaybeRelocatable::RelocatableValue(rel) => bytes.extend_from_slice(
+                    &encode_relocatable(rel.segment_index as usize, rel.offset, FIELD_BYTE_LEN),
+                ),
+            }
+        }
+        bytes
+    }
+
+    /// Parses the memory from the format used by `memory.bin`, see [CairoPieMemory::to_bytes].
+    /// Returns None if the bytes don't hold a sequence of address-value pairs
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() % (ADDR_BYTE_LEN + FIELD_BYTE_LEN) != 0 {
+            return None;
+        }
+        let mut pie_memory = Vec::with_capacity(bytes.len() / (ADDR_BYTE_LEN + FIELD_BYTE_LEN));
+        for pair in bytes.chunks_exact(ADDR_BYTE_LEN + FIELD_BYTE_LEN) {
+            let (addr_bytes, value_bytes) = pair.split_at(ADDR_BYTE_LEN);
+            let address = decode_relocatable(addr_bytes)?;
+            let value = if value_bytes[FIELD_BYTE_LEN - 1] & 0x80 != 0 {
+                let (segment_index, offset) = decode_relocatable(value_bytes)?;
+                MaybeRelocatable::from((segment_index as isize, offset))
+            } else {
+                let mut be_bytes = value_bytes.to_vec();
+                be_bytes.reverse();
+                MaybeRelocatable::from(Felt::from_bytes_be(&be_bytes))
+            };
+            pie_memory.push((address, value));
+        }
+        Some(CairoPieMemory(pie_memory))
+    }
+}
+
+// Relocatable values are encoded as 2**(8 * n_bytes - 1) + segment_index * 2**47 + offset
+fn encode_relocatable(segment_index: usize, offset: usize, n_bytes: usize) -> Vec<u8> {
+    let mut bytes = (((segment_index as u64) << OFFSET_BIT_LEN) + offset as u64)
+        .to_le_bytes()
+        .to_vec();
+    bytes.resize(n_bytes, 0);
+    bytes[n_bytes - 1] |= 0x80;
+    bytes
+}
+
+// Inverse of encode_relocatable, the encoded value must fit in the lower 8 bytes
+fn decode_relocatable(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut bytes = bytes.to_vec();
+    let last = bytes.last_mut()?;
+    if *last & 0x80 == 0 {
+        return None;
+    }
+    *last &= 0x7f;
+    if bytes[ADDR_BYTE_LEN..].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let value = u64::from_le_bytes(bytes[..ADDR_BYTE_LEN].try_into().ok()?);
+    Some((
+        (value >> OFFSET_BIT_LEN) as usize,
+        (value & ((1 << OFFSET_BIT_LEN) - 1)) as usize,
+    ))
+}
+
+impl Serialize for BuiltinAdditionalData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BuiltinAdditionalData::Hash(verified_addresses) => {
+                let mut verified_addresses = verified_addresses.clone();
+                verified_addresses.sort_by_key(|addr| (addr.segment_index, addr.offset));
+                let mut seq = serializer.serialize_seq(Some(verified_addresses.len()))?;
+                for addr in verified_addresses {
+                    seq.serialize_element(&(addr.segment_index, addr.offset))?;
+                }
+                seq.end()
+            }
+            BuiltinAdditionalData::Output(output) => output.serialize(serializer),
+            BuiltinAdditionalData::Signature(signatures) => {
+                let mut signatures: Vec<_> = signatures.iter().collect();
+                signatures.sort_by_key(|(addr, _)| (addr.segment_index, addr.offset));
+                let mut seq = serializer.serialize_seq(Some(signatures.len()))?;
+                for (addr, (r, s)) in signatures {
+                    seq.serialize_element(&(
+                        (addr.segment_index, addr.offset),
+                        (felt_to_number(r), felt_to_number(s)),
+                    ))?;
+                }
+                seq.end()
+            }
+            BuiltinAdditionalData::None => serializer.serialize_map(Some(0))?.end(),
+        }
+    }
+}
+
+impl BuiltinAdditionalData {
+    /// Parses the additional data of the builtin named `builtin_name`, as found in
+    /// `additional_data.json`
+    pub fn from_json(
+        builtin_name: &str,
+        value: serde_json::Value,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(match builtin_name {
+            HASH_BUILTIN_NAME => {
+                let verified_addresses: Vec<(isize, usize)> = serde_json::from_value(value)?;
+                BuiltinAdditionalData::Hash(
+                    verified_addresses
+                        .into_iter()
+                        .map(Relocatable::from)
+                        .collect(),
+                )
+            }
+            OUTPUT_BUILTIN_NAME => BuiltinAdditionalData::Output(serde_json::from_value(value)?),
+            SIGNATURE_BUILTIN_NAME => {
+                let signatures: Vec<((isize, usize), (serde_json::Number, serde_json::Number))> =
+                    serde_json::from_value(value)?;
+                BuiltinAdditionalData::Signature(
+                    signatures
+                        .into_iter()
+                        .map(|(addr, (r, s))| {
+                            Ok((
+                                Relocatable::from(addr),
+                                (number_to_felt(&r)?, number_to_felt(&s)?),
+                            ))
+                        })
+                        .collect::<Result<_, serde_json::Error>>()?,
+                )
+            }
+            _ => BuiltinAdditionalData::None,
+        })
+    }
+}
+
+// Felts are written as plain (arbitrary precision) json numbers
+fn felt_to_number(felt: &Felt) -> serde_json::Number {
+    felt.to_str_radix(10)
+        .parse()
+        .expect("a decimal string is always a valid json number")
+}
+
+fn number_to_felt(number: &serde_json::Number) -> Result<Felt, serde_json::Error> {
+    Felt::parse_bytes(number.to_string().as_bytes(), 10)
+        .ok_or_else(|| serde::de::Error::custom(format!("Expected a felt, got {number}")))
+}
+
+fn deserialize_builtin_names<'de, D: Deserializer<'de>>(
+    d: D,
+) -> Result<Vec<&'static str>, D::Error> {
+    let builtins: Vec<BuiltinName> = Deserialize::deserialize(d)?;
+    Ok(builtins.iter().map(BuiltinName::name).collect())
+}
+
+fn serialize_program_data<S: Serializer>(
+    data: &[MaybeRelocatable],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(data.len()))?;
+    for value in data {
+        match value {
+            MaybeRelocatable::Int(num) => {
+                seq.serialize_element(&format!("0x{}", num.to_str_radix(16)))?
+            }
+            MaybeRelocatable::RelocatableValue(_) => {
+                return Err(serde::ser::Error::custom(
+                    "Relocatable values can't be part of a stripped program",
+                ))
+            }
+        }
+    }
+    seq.end()
+}
+
+impl CairoPie {
+    /// Writes the Cairo PIE as a zip archive, using the same layout as the Python VM:
+    /// `metadata.json`, `memory.bin`, `additional_data.json`, `execution_resources.json`
+    /// and `version.json`
+    #[cfg(feature = "std")]
+    pub fn write_zip<W: Write + Seek>(&self, writer: W) -> std::io::Result<()> {
+        let mut zip_writer = zip::ZipWriter::new(writer);
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        zip_writer.start_file("version.json", options)?;
+        serde_json::to_writer(&mut zip_writer, &self.version)?;
+        zip_writer.start_file("metadata.json", options)?;
+        serde_json::to_writer(&mut zip_writer, &self.metadata)?;
+        zip_writer.start_file("memory.bin", options)?;
+        zip_writer.write_all(&self.memory.to_bytes())?;
+        zip_writer.start_file("additional_data.json", options)?;
+        serde_json::to_writer(&mut zip_writer, &self.additional_data)?;
+        zip_writer.start_file("execution_resources.json", options)?;
+        serde_json::to_writer(&mut zip_writer, &self.execution_resources)?;
+        zip_writer.finish()?;
+        Ok(())
+    }
+
+    /// Reads a Cairo PIE from a zip archive, such as the ones written by [CairoPie::write_zip]
+    /// or by the Python VM
+    #[cfg(feature = "std")]
+    pub fn from_zip<R: Read + Seek>(reader: R) -> std::io::Result<CairoPie> {
+        let mut zip_reader = zip::ZipArchive::new(reader)?;
+
+        let version = serde_json::from_reader(zip_reader.by_name("version.json")?)?;
+        let metadata = serde_json::from_reader(zip_reader.by_name("metadata.json")?)?;
+        let mut memory_bytes = Vec::new();
+        zip_reader
+            .by_name("memory.bin")?
+            .read_to_end(&mut memory_bytes)?;
+        let memory = CairoPieMemory::from_bytes(&memory_bytes).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid memory.bin")
+        })?;
+        let additional_data: HashMap<String, serde_json::Value> =
+            serde_json::from_reader(zip_reader.by_name("additional_data.json")?)?;
+        let additional_data = additional_data
+            .into_iter()
+            .map(|(name, value)| {
+                let data = BuiltinAdditionalData::from_json(&name, value)?;
+                Ok((name, data))
+            })
+            .collect::<Result<_, serde_json::Error>>()?;
+        let execution_resources =
+            serde_json::from_reader(zip_reader.by_name("execution_resources.json")?)?;
+
+        Ok(CairoPie {
+            metadata,
+            memory,
+            execution_resources,
+            additional_data,
+            version,
+        })
+    }
+}
+
+impl StrippedProgram {
+    /// Computes the program hash the same way the bootloader does: a pedersen hash chain over
+    /// the program header (bootloader version, main, builtins) followed by the program data
+    pub fn hash(&self, bootloader_version: usize) -> Result<Felt, RunnerError> {
+        let mut data_chain = vec![
+            Felt::new(bootloader_version),
+            Felt::new(self.main),
+            Felt::new(self.builtins.len()),
+        ];
+        data_chain.extend(
+            self.builtins
+                .iter()
+                .map(|name| Felt::from_bytes_be(name.as_bytes())),
+        );
+        for value in self.data.iter() {
+            match value {
+                MaybeRelocatable::Int(num) => data_chain.push(num.clone()),
+                MaybeRelocatable::RelocatableValue(_) => return Err(RunnerError::FoundNonInt),
+            }
+        }
+        data_chain.insert(0, Felt::new(data_chain.len()));
+
+        let elements = data_chain
+            .iter()
+            .map(|felt| {
+                FieldElement::from_dec_str(&felt.to_str_radix(10))
+                    .map_err(|_| RunnerError::FailedStringConversion)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Same as reduce(lambda x, y: pedersen(y, x), data_chain[::-1])
+        let hash = elements
+            .into_iter()
+            .rev()
+            .reduce(|acc, element| pedersen_hash(&element, &acc))
+            .unwrap_or(FieldElement::ZERO);
+        Ok(Felt::from_bytes_be(&hash.to_bytes_be()))
+    }
+}
+
+impl From<&StrippedProgram> for Program {
+    fn from(program: &StrippedProgram) -> Self {
+        Program {
+            builtins: program.builtins.clone(),
+            prime: program.prime.clone(),
+            data: program.data.clone(),
+            main: Some(program.main),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn memory_to_bytes() {
+        let memory = CairoPieMemory(vec![
+            ((0, 1), MaybeRelocatable::from(Felt::new(5))),
+            ((1, 0), MaybeRelocatable::from((2, 3))),
+        ]);
+        let bytes = memory.to_bytes();
+        assert_eq!(bytes.len(), 2 * (ADDR_BYTE_LEN + FIELD_BYTE_LEN));
+
+        // First address: 2**63 + 1
+        assert_eq!(&bytes[..8], &[1, 0, 0, 0, 0, 0, 0, 0x80]);
+        // First value: 5
+        assert_eq!(bytes[8], 5);
+        assert!(bytes[9..40].iter().all(|b| *b == 0));
+        // Second address: 2**63 + 2**47
+        assert_eq!(&bytes[40..48], &[0, 0, 0, 0, 0, 0x80, 0, 0x80]);
+        // Second value: 2**255 + 2 * 2**47 + 3
+        assert_eq!(&bytes[48..56], &[3, 0, 0, 0, 0, 0, 1, 0]);
+        assert!(bytes[56..79].iter().all(|b| *b == 0));
+        assert_eq!(bytes[79], 0x80);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn memory_from_bytes_round_trip() {
+        let memory = CairoPieMemory(vec![
+            ((0, 1), MaybeRelocatable::from(Felt::new(5))),
+            ((1, 0), MaybeRelocatable::from((2, 3))),
+            ((3, 7), MaybeRelocatable::from(Felt::new(-1))),
+        ]);
+        assert_eq!(CairoPieMemory::from_bytes(&memory.to_bytes()), Some(memory));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn memory_from_bytes_invalid_length() {
+        assert_eq!(CairoPieMemory::from_bytes(&[0x80; 39]), None);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn additional_data_from_json() {
+        let hash_data = BuiltinAdditionalData::Hash(vec![Relocatable::from((3, 2))]);
+        let signature_data = BuiltinAdditionalData::Signature(HashMap::from([(
+            Relocatable::from((4, 0)),
+            (Felt::new(10), Felt::new(20)),
+        )]));
+        for (name, data) in [
+            (HASH_BUILTIN_NAME, hash_data),
+            (SIGNATURE_BUILTIN_NAME, signature_data),
+            ("bitwise", BuiltinAdditionalData::None),
+        ] {
+            let value = serde_json::to_value(&data).unwrap();
+            assert_eq!(BuiltinAdditionalData::from_json(name, value).unwrap(), data);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn serialize_hash_additional_data() {
+        let data =
+            BuiltinAdditionalData::Hash(vec![Relocatable::from((3, 5)), Relocatable::from((3, 2))]);
+        assert_eq!(
+            serde_json::to_string(&data).unwrap(),
+            "[[3,2],[3,5]]".to_string()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn serialize_signature_additional_data() {
+        let data = BuiltinAdditionalData::Signature(HashMap::from([(
+            Relocatable::from((4, 0)),
+            (Felt::new(10), Felt::new(20)),
+        )]));
+        assert_eq!(
+            serde_json::to_string(&data).unwrap(),
+            "[[[4,0],[10,20]]]".to_string()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn serialize_empty_additional_data() {
+        assert_eq!(
+            serde_json::to_string(&BuiltinAdditionalData::None).unwrap(),
+            "{}".to_string()
+        );
+    }
+}
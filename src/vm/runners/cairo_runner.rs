@@ -1,14 +1,19 @@
 use crate::stdlib::{
     any::Any,
+    borrow::Cow,
     collections::{HashMap, HashSet},
     ops::{Add, Sub},
     prelude::*,
 };
 
 use crate::{
+    air_private_input::AirPrivateInput,
+    air_public_input::{
+        MemorySegmentAddresses, MemorySegments, PublicInput, PublicInputError, PublicMemoryEntry,
+    },
     hint_processor::hint_processor_definition::{HintProcessor, HintReference},
     math_utils::safe_div_usize,
-    serde::deserialize_program::OffsetValue,
+    serde::deserialize_program::{available_entrypoints, OffsetValue},
     types::{
         errors::{math_errors::MathError, program_errors::ProgramError},
         exec_scope::ExecutionScopes,
@@ -29,7 +34,7 @@ use crate::{
             runner_errors::RunnerError,
             trace_errors::TraceError,
             vm_errors::VirtualMachineError,
-            vm_exception::VmException,
+            vm_exception::{get_traceback, VmException},
         },
         security::verify_secure_runner,
         trace::get_perm_range_check_limits,
@@ -38,6 +43,7 @@ use crate::{
                 BitwiseBuiltinRunner, BuiltinRunner, EcOpBuiltinRunner, HashBuiltinRunner,
                 OutputBuiltinRunner, RangeCheckBuiltinRunner, SignatureBuiltinRunner,
             },
+            runners::cairo_pie::{CairoPie, CairoPieMetadata, CairoPieVersion, StrippedProgram},
             trace::trace_entry::{relocate_trace_register, RelocatedTraceEntry},
             vm_core::VirtualMachine,
         },
@@ -45,18 +51,32 @@ use crate::{
 };
 use felt::Felt;
 use num_integer::div_rem;
-use num_traits::Zero;
+use num_traits::{One, ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use crate::cairo_run::{write_encoded_memory, write_encoded_trace};
+#[cfg(feature = "std")]
+use bincode::enc::write::Writer;
+#[cfg(feature = "std")]
+use std::io::Write;
+
+#[cfg(feature = "hooks")]
+use crate::vm::hooks::{BreakpointRun, RunStatus};
 
 use super::builtin_runner::{
-    KeccakBuiltinRunner, PoseidonBuiltinRunner, BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME,
-    HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME, OUTPUT_BUILTIN_NAME, POSEIDON_BUILTIN_NAME,
-    RANGE_CHECK_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
+    ArenaSegmentInfo, KeccakBuiltinRunner, PoseidonBuiltinRunner, SegmentArenaBuiltinRunner,
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+    OUTPUT_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_96_BUILTIN_NAME,
+    RANGE_CHECK_BUILTIN_NAME, SEGMENT_ARENA_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
 };
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum CairoArg {
     Single(MaybeRelocatable),
     Array(Vec<MaybeRelocatable>),
+    /// An array written to a new segment and passed as a (pointer, length) pair
+    Span(Vec<MaybeRelocatable>),
     Composed(Vec<CairoArg>),
 }
 
@@ -76,12 +96,12 @@ impl From<Vec<MaybeRelocatable>> for CairoArg {
 pub struct CairoRunner {
     pub(crate) program: Program,
     layout: CairoLayout,
-    final_pc: Option<Relocatable>,
+    pub(crate) final_pc: Option<Relocatable>,
     pub(crate) program_base: Option<Relocatable>,
     execution_base: Option<Relocatable>,
-    initial_ap: Option<Relocatable>,
-    initial_fp: Option<Relocatable>,
-    initial_pc: Option<Relocatable>,
+    pub(crate) initial_ap: Option<Relocatable>,
+    pub(crate) initial_fp: Option<Relocatable>,
+    pub(crate) initial_pc: Option<Relocatable>,
     run_ended: bool,
     segments_finalized: bool,
     execution_public_memory: Option<Vec<usize>>,
@@ -89,7 +109,11 @@ pub struct CairoRunner {
     pub original_steps: Option<usize>,
     pub relocated_memory: Vec<Option<Felt>>,
     pub relocated_trace: Option<Vec<RelocatedTraceEntry>>,
+    // Segment relocation table, known once the segment sizes are computed by end_run or relocate
+    relocation_table: Option<Vec<usize>>,
     pub exec_scopes: ExecutionScopes,
+    #[cfg(feature = "hint_coverage")]
+    executed_hints: HashSet<String>,
 }
 
 impl CairoRunner {
@@ -122,8 +146,11 @@ impl CairoRunner {
             original_steps: None,
             relocated_memory: Vec::new(),
             relocated_trace: None,
+            relocation_table: None,
             exec_scopes: ExecutionScopes::new(),
             execution_public_memory: if proof_mode { Some(Vec::new()) } else { None },
+            #[cfg(feature = "hint_coverage")]
+            executed_hints: HashSet::new(),
         })
     }
 
@@ -145,6 +172,8 @@ impl CairoRunner {
             EC_OP_BUILTIN_NAME,
             KECCAK_BUILTIN_NAME,
             POSEIDON_BUILTIN_NAME,
+            RANGE_CHECK_96_BUILTIN_NAME,
+            SEGMENT_ARENA_BUILTIN_NAME,
         ];
         if !is_subsequence(&self.program.builtins, &builtin_ordered_list) {
             return Err(RunnerError::DisorderedBuiltins);
@@ -236,6 +265,30 @@ impl CairoRunner {
             }
         }
 
+        // Proof mode doesn't add range_check96 to programs that don't use it, so their public
+        // input on existing layouts stays the same
+        if let Some(instance_def) = self.layout.builtins.range_check96.as_ref() {
+            if self.program.builtins.contains(&RANGE_CHECK_96_BUILTIN_NAME) {
+                builtin_runners.push((
+                    RANGE_CHECK_96_BUILTIN_NAME,
+                    BuiltinRunner::RangeCheck96(RangeCheckBuiltinRunner::new_range_check96(
+                        instance_def.ratio,
+                        true,
+                    )),
+                ));
+            }
+        }
+
+        // The segment arena isn't part of the AIR, so proof mode doesn't need its segment
+        if self.layout.builtins.segment_arena
+            && self.program.builtins.contains(&SEGMENT_ARENA_BUILTIN_NAME)
+        {
+            builtin_runners.push((
+                SEGMENT_ARENA_BUILTIN_NAME,
+                SegmentArenaBuiltinRunner::new(true).into(),
+            ));
+        }
+
         let inserted_builtins = builtin_runners
             .iter()
             .map(|x| &x.0)
@@ -298,6 +351,15 @@ impl CairoRunner {
                     name,
                     EcOpBuiltinRunner::new(&EcOpInstanceDef::new(1), true).into(),
                 )),
+                RANGE_CHECK_96_BUILTIN_NAME => vm.builtin_runners.push((
+                    name,
+                    BuiltinRunner::RangeCheck96(RangeCheckBuiltinRunner::new_range_check96(
+                        1, true,
+                    )),
+                )),
+                SEGMENT_ARENA_BUILTIN_NAME => vm
+                    .builtin_runners
+                    .push((name, SegmentArenaBuiltinRunner::new(true).into())),
                 _ => {}
             }
         }
@@ -314,6 +376,9 @@ impl CairoRunner {
     }
 
     ///Creates the necessary segments for the program, execution, and each builtin on the MemorySegmentManager and stores the first adress of each of this new segments as each owner's base
+    ///Builtin segments follow the order of `vm.builtin_runners`, which is the layout's builtin
+    ///order. As relocation assigns absolute addresses by segment index, this ordering matches the
+    ///one of the Python VM, and determines the relocated address of each builtin segment.
     pub fn initialize_segments(
         &mut self,
         vm: &mut VirtualMachine,
@@ -517,6 +582,22 @@ impl CairoRunner {
         &self.program.constants
     }
 
+    // Constants passed to the hints: the program constants, replaced or extended by the hint
+    // processor's overrides
+    fn get_hint_constants<'a>(
+        program_constants: &'a HashMap<String, Felt>,
+        hint_processor: &dyn HintProcessor,
+    ) -> Cow<'a, HashMap<String, Felt>> {
+        match hint_processor.constant_overrides() {
+            Some(overrides) if !overrides.is_empty() => {
+                let mut constants = program_constants.clone();
+                constants.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+                Cow::Owned(constants)
+            }
+            _ => Cow::Borrowed(program_constants),
+        }
+    }
+
     pub fn get_program_builtins(&self) -> &Vec<&'static str> {
         &self.program.builtins
     }
@@ -526,22 +607,117 @@ impl CairoRunner {
         address: Relocatable,
         vm: &mut VirtualMachine,
         hint_processor: &mut dyn HintProcessor,
+    ) -> Result<(), VirtualMachineError> {
+        self.run_until_pc_with_max_steps(address, vm, hint_processor, usize::MAX)
+    }
+
+    /// Runs the program until the pc reaches `address`, failing with
+    /// `VirtualMachineError::MaxStepsExceeded` if `max_steps` steps were executed before that.
+    pub fn run_until_pc_with_max_steps(
+        &mut self,
+        address: Relocatable,
+        vm: &mut VirtualMachine,
+        hint_processor: &mut dyn HintProcessor,
+        max_steps: usize,
     ) -> Result<(), VirtualMachineError> {
         let references = self.get_reference_list();
         let hint_data_dictionary = self.get_hint_data_dictionary(&references, hint_processor)?;
         #[cfg(feature = "hooks")]
         vm.execute_before_first_step(self, &hint_data_dictionary)?;
+        let constants = Self::get_hint_constants(&self.program.constants, hint_processor);
         while vm.run_context.pc != address {
+            if vm.current_step >= max_steps {
+                return Err(VirtualMachineError::MaxStepsExceeded(max_steps));
+            }
+            #[cfg(feature = "hint_coverage")]
+            let pc = vm.run_context.pc;
             vm.step(
                 hint_processor,
                 &mut self.exec_scopes,
                 &hint_data_dictionary,
-                &self.program.constants,
+                &constants,
             )?;
+            #[cfg(feature = "hint_coverage")]
+            self.record_executed_hints(pc);
         }
         Ok(())
     }
 
+    /// Starts a run until the pc reaches `address`, pausing whenever the breakpoint set in the VM
+    /// hooks matches the state before an instruction.
+    /// The hints are compiled and the before_first_step hook is executed once, here; the run is
+    /// then driven by [run_until_pc_or_breakpoint](Self::run_until_pc_or_breakpoint).
+    #[cfg(feature = "hooks")]
+    pub fn start_run_until_pc_or_breakpoint(
+        &mut self,
+        address: Relocatable,
+        vm: &mut VirtualMachine,
+        hint_processor: &mut dyn HintProcessor,
+    ) -> Result<BreakpointRun, VirtualMachineError> {
+        let references = self.get_reference_list();
+        let hint_data_dictionary = self.get_hint_data_dictionary(&references, hint_processor)?;
+        vm.execute_before_first_step(self, &hint_data_dictionary)?;
+        let constants = Self::get_hint_constants(&self.program.constants, hint_processor);
+        Ok(BreakpointRun {
+            address,
+            hint_data_dictionary,
+            constants,
+            paused: false,
+        })
+    }
+
+    /// Runs `run` until the pc reaches its address, pausing if the breakpoint matches the state
+    /// before an instruction.
+    /// Calling it again after a pause resumes the execution: the breakpoint is evaluated before
+    /// every instruction except the one the run paused at, so the run moves past it.
+    #[cfg(feature = "hooks")]
+    pub fn run_until_pc_or_breakpoint(
+        &mut self,
+        run: &mut BreakpointRun,
+        vm: &mut VirtualMachine,
+        hint_processor: &mut dyn HintProcessor,
+    ) -> Result<RunStatus, VirtualMachineError> {
+        while vm.run_context.pc != run.address {
+            if !run.paused && vm.hit_breakpoint() {
+                run.paused = true;
+                return Ok(RunStatus::Paused);
+            }
+            run.paused = false;
+            #[cfg(feature = "hint_coverage")]
+            let pc = vm.run_context.pc;
+            vm.step(
+                hint_processor,
+                &mut self.exec_scopes,
+                &run.hint_data_dictionary,
+                &run.constants,
+            )?;
+            #[cfg(feature = "hint_coverage")]
+            self.record_executed_hints(pc);
+        }
+        Ok(RunStatus::Finished)
+    }
+
+    /// Returns the code of every hint executed at least once by the runs of this runner, to check
+    /// which hints a test suite exercises
+    #[cfg(feature = "hint_coverage")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "hint_coverage")))]
+    pub fn get_executed_hints(&self) -> HashSet<String> {
+        self.executed_hints.clone()
+    }
+
+    // Records the hints at `pc`, once the step executing them succeeded
+    #[cfg(feature = "hint_coverage")]
+    fn record_executed_hints(&mut self, pc: Relocatable) {
+        if self.program_base.map(|base| base.segment_index) != Some(pc.segment_index) {
+            return;
+        }
+        if let Some(hints) = self.program.hints.get(&pc.offset) {
+            for hint in hints {
+                self.executed_hints.insert(hint.code.clone());
+            }
+        }
+    }
+
     /// Execute an exact number of steps on the program from the actual position.
     pub fn run_for_steps(
         &mut self,
@@ -551,18 +727,23 @@ impl CairoRunner {
     ) -> Result<(), VirtualMachineError> {
         let references = self.get_reference_list();
         let hint_data_dictionary = self.get_hint_data_dictionary(&references, hint_processor)?;
+        let constants = Self::get_hint_constants(&self.program.constants, hint_processor);
 
         for remaining_steps in (1..=steps).rev() {
             if self.final_pc.as_ref() == Some(&vm.run_context.pc) {
                 return Err(VirtualMachineError::EndOfProgram(remaining_steps));
             }
 
+            #[cfg(feature = "hint_coverage")]
+            let pc = vm.run_context.pc;
             vm.step(
                 hint_processor,
                 &mut self.exec_scopes,
                 &hint_data_dictionary,
-                &self.program.constants,
+                &constants,
             )?;
+            #[cfg(feature = "hint_coverage")]
+            self.record_executed_hints(pc);
         }
 
         Ok(())
@@ -727,6 +908,7 @@ impl CairoRunner {
             }
         }
 
+        self.relocation_table = Some(vm.segments.relocate_segments()?);
         self.run_ended = true;
         Ok(())
     }
@@ -761,6 +943,25 @@ impl CairoRunner {
                     None => self.relocated_memory.push(None),
                 }
             }
+            // Sparse segments keep their cells apart from the dense ones
+            for (seg_offset, cell) in vm
+                .segments
+                .memory
+                .sparse_data
+                .get(&index)
+                .into_iter()
+                .flatten()
+            {
+                let relocated_addr = relocate_address(
+                    Relocatable::from((index as isize, *seg_offset)),
+                    relocation_table,
+                )?;
+                let value = relocate_value(cell.get_value().clone(), relocation_table)?;
+                if self.relocated_memory.len() <= relocated_addr {
+                    self.relocated_memory.resize(relocated_addr + 1, None);
+                }
+                self.relocated_memory[relocated_addr] = Some(value);
+            }
         }
         Ok(())
     }
@@ -788,7 +989,13 @@ impl CairoRunner {
         Ok(())
     }
 
-    pub fn relocate(&mut self, vm: &mut VirtualMachine) -> Result<(), TraceError> {
+    /// Relocates the memory and, if `relocate_trace` is set and the trace is enabled, the trace.
+    /// Skipping the trace relocation saves work when only the relocated memory is needed.
+    pub fn relocate(
+        &mut self,
+        vm: &mut VirtualMachine,
+        relocate_trace: bool,
+    ) -> Result<(), TraceError> {
         vm.segments.compute_effective_sizes();
         // relocate_segments can fail if compute_effective_sizes is not called before.
         // The expect should be unreachable.
@@ -799,12 +1006,44 @@ impl CairoRunner {
         if let Err(memory_error) = self.relocate_memory(vm, &relocation_table) {
             return Err(TraceError::MemoryError(memory_error));
         }
-        if vm.trace.is_some() {
+        if relocate_trace && vm.trace.is_some() {
             self.relocate_trace(vm, &relocation_table)?;
         }
+        self.relocation_table = Some(relocation_table);
         Ok(())
     }
 
+    /// Returns the address `addr` gets in the relocated memory, as [relocate](Self::relocate)
+    /// would compute it, without relocating the whole memory.
+    ///     Note: end_run() or relocate() must precede a call to this method, so that the
+    ///     segment relocation table is known.
+    pub fn relocate_value(&self, addr: Relocatable) -> Result<Felt, MemoryError> {
+        let relocation_table = self
+            .relocation_table
+            .as_ref()
+            .ok_or(MemoryError::MissingSegmentUsedSizes)?;
+        Ok(Felt::from(relocate_address(addr, relocation_table)?))
+    }
+
+    /// Returns the relocated value of the memory cell at `addr`, which equals the relocated
+    /// memory's entry for that cell, without relocating the whole memory.
+    ///     Note: end_run() or relocate() must precede a call to this method.
+    pub fn get_relocated_value(
+        &self,
+        vm: &VirtualMachine,
+        addr: Relocatable,
+    ) -> Result<Felt, MemoryError> {
+        let value = vm
+            .segments
+            .memory
+            .get(&addr)
+            .ok_or(MemoryError::UnknownMemoryCell(addr))?;
+        match value.as_ref() {
+            MaybeRelocatable::Int(num) => Ok(num.clone()),
+            MaybeRelocatable::RelocatableValue(rel) => self.relocate_value(*rel),
+        }
+    }
+
     // Returns a map from builtin base's segment index to stop_ptr offset
     // Aka the builtin's segment number and its maximum offset
     pub fn get_builtin_segments_info(
@@ -825,6 +1064,67 @@ impl CairoRunner {
         Ok(builtin_segment_info)
     }
 
+    /// Returns the cells used and allocated, along with the memory holes, of each builtin segment,
+    /// by segment index. The segment sizes must have been computed.
+    pub fn get_builtin_use_counts_by_segment(
+        &self,
+        vm: &VirtualMachine,
+    ) -> Result<HashMap<usize, BuiltinSegmentUsage>, MemoryError> {
+        vm.builtin_runners
+            .iter()
+            .map(|(name, builtin)| {
+                let (used_cells, allocated_cells) =
+                    builtin.get_used_cells_and_allocated_size(vm)?;
+                let usage = BuiltinSegmentUsage {
+                    name: *name,
+                    used_cells,
+                    allocated_cells,
+                    memory_holes: vm.segments.get_segment_memory_holes(builtin.base())?,
+                };
+                Ok((builtin.base(), usage))
+            })
+            .collect()
+    }
+
+    /// Returns the values written to the output builtin's segment, in order.
+    /// Fails if a cell of the segment is missing or holds a relocatable value.
+    pub fn get_output_as_felts(&self, vm: &VirtualMachine) -> Result<Vec<Felt>, RunnerError> {
+        let output = vm
+            .builtin_runners
+            .iter()
+            .find_map(|(_, builtin)| builtin.as_output())
+            .ok_or(RunnerError::NoOutputBuiltin)?;
+        let base = output.base();
+        let size = vm.segments.memory.segment_len(base);
+        Ok(vm
+            .segments
+            .memory
+            .get_integer_range(Relocatable::from((base as isize, 0)), size)?
+            .into_iter()
+            .map(Cow::into_owned)
+            .collect())
+    }
+
+    /// Returns the segments allocated through the segment arena, along with the end of those
+    /// that were finalized
+    pub fn get_segment_arena_info(
+        &self,
+        vm: &VirtualMachine,
+    ) -> Result<Vec<ArenaSegmentInfo>, RunnerError> {
+        let segment_arena = vm
+            .builtin_runners
+            .iter()
+            .find_map(|(_, builtin)| builtin.as_segment_arena())
+            .ok_or(RunnerError::NoSegmentArenaBuiltin)?;
+        Ok(segment_arena.get_segment_infos(&vm.segments.memory)?)
+    }
+
+    /// Returns a Python-style traceback of the call frames leading to the current pc, walking
+    /// the fp chain back from the current frame. Returns `None` if there are no frames to show.
+    pub fn get_traceback(&self, vm: &VirtualMachine) -> Option<String> {
+        get_traceback(vm, self)
+    }
+
     pub fn get_execution_resources(
         &self,
         vm: &VirtualMachine,
@@ -894,13 +1194,23 @@ impl CairoRunner {
             let (_, size) = builtin_runner
                 .get_used_cells_and_allocated_size(vm)
                 .map_err(RunnerError::FinalizeSegements)?;
+            // The output builtin is the only one exposing its cells as public memory
+            let public_memory = match builtin_runner {
+                BuiltinRunner::Output(output) => Some(output.get_public_memory(&vm.segments)?),
+                _ => None,
+            };
             vm.segments
-                .finalize(Some(size), builtin_runner.base(), None)
+                .finalize(Some(size), builtin_runner.base(), public_memory.as_ref())
         }
         self.segments_finalized = true;
         Ok(())
     }
 
+    /// Runs the function at `entrypoint` until it returns, passing `args` in order.
+    /// Arrays are written to new segments and passed as a pointer, followed by their length
+    /// for [CairoArg::Span] arguments.
+    ///     Note: initialize_builtins() and initialize_segments() must precede a call to this
+    ///     method, and the builtin pointers used by the function must be part of `args`.
     pub fn run_from_entrypoint(
         &mut self,
         entrypoint: usize,
@@ -909,10 +1219,10 @@ impl CairoRunner {
         vm: &mut VirtualMachine,
         hint_processor: &mut dyn HintProcessor,
     ) -> Result<(), CairoRunError> {
-        let stack = args
-            .iter()
-            .map(|arg| vm.segments.gen_cairo_arg(arg))
-            .collect::<Result<Vec<MaybeRelocatable>, VirtualMachineError>>()?;
+        let mut stack = Vec::new();
+        for arg in args {
+            stack.extend(vm.segments.gen_cairo_arg_values(arg)?);
+        }
         let return_fp = MaybeRelocatable::from(0);
         let end = self.initialize_function_entrypoint(vm, entrypoint, stack, return_fp)?;
 
@@ -929,6 +1239,89 @@ impl CairoRunner {
         Ok(())
     }
 
+    /// Runs a Cairo 1 function taking the gas builtin as its last implicit argument and returning
+    /// a `PanicResult` (see [get_run_result_starknet](Self::get_run_result_starknet)).
+    /// `args` holds the builtin pointers followed by the explicit arguments; `initial_gas` is
+    /// inserted right after the builtin pointers.
+    /// The runner doesn't meter the execution itself: gas is withdrawn by the function's own
+    /// code (e.g. the range checks emitted by Cairo 1's `withdraw_gas`), and the runner only
+    /// passes `initial_gas` in and reads the gas counter back out.
+    /// While the function runs, `initial_gas` is also made available to hints through
+    /// [VirtualMachine::consume_gas]. Once it returns, the remaining gas is reported in
+    /// [RunResultStarknet::gas_counter] and by [VirtualMachine::get_remaining_gas].
+    /// Returns [VirtualMachineError::OutOfGas], holding the gas that was left, if the function
+    /// panicked because it ran out of gas.
+    pub fn run_from_entrypoint_with_gas(
+        &mut self,
+        entrypoint: usize,
+        args: &[&CairoArg],
+        initial_gas: usize,
+        verify_secure: bool,
+        vm: &mut VirtualMachine,
+        hint_processor: &mut dyn HintProcessor,
+    ) -> Result<RunResultStarknet, CairoRunError> {
+        let gas_arg = CairoArg::from(MaybeRelocatable::from(initial_gas));
+        let n_builtins = self.program.builtins.len().min(args.len());
+        let mut args_with_gas = args[..n_builtins].to_vec();
+        args_with_gas.push(&gas_arg);
+        args_with_gas.extend_from_slice(&args[n_builtins..]);
+
+        vm.set_remaining_gas(initial_gas);
+        self.run_from_entrypoint(
+            entrypoint,
+            &args_with_gas,
+            verify_secure,
+            vm,
+            hint_processor,
+        )?;
+
+        let run_result = self.get_run_result_starknet(vm)?;
+        if let Some(gas_counter) = run_result.gas_counter.to_usize() {
+            vm.set_remaining_gas(gas_counter);
+        }
+        if run_result.result == Err(vec![Felt::from(OUT_OF_GAS_PANIC)]) {
+            let gas_left = run_result.gas_counter.to_usize().ok_or_else(|| {
+                VirtualMachineError::Math(MathError::FeltToUsizeConversion(
+                    run_result.gas_counter.clone(),
+                ))
+            })?;
+            return Err(VirtualMachineError::OutOfGas(gas_left).into());
+        }
+        Ok(run_result)
+    }
+
+    /// Parses the return values of a Cairo 1 function returning a `PanicResult` whose payload is
+    /// a span of felts. The function must take the gas builtin as its last implicit argument, so
+    /// that its return values end with `[gas_counter, variant, span_start, span_end]`.
+    ///     Note: the function must have run to completion, e.g. through run_from_entrypoint().
+    pub fn get_run_result_starknet(
+        &self,
+        vm: &VirtualMachine,
+    ) -> Result<RunResultStarknet, RunnerError> {
+        let ret_addr =
+            (vm.get_ap() - 4).map_err(|_| MemoryError::FailedToGetReturnValues(4, vm.get_ap()))?;
+        let gas_counter = vm.get_integer(ret_addr)?.into_owned();
+        let variant = vm.get_integer((ret_addr + 1)?)?;
+        let span_start = vm.get_relocatable((ret_addr + 2)?)?;
+        let span_end = vm.get_relocatable((ret_addr + 3)?)?;
+        let data = vm
+            .get_integer_range(span_start, (span_end - span_start)?)?
+            .into_iter()
+            .map(|value| value.into_owned())
+            .collect();
+        let result = if variant.is_zero() {
+            Ok(data)
+        } else if variant.is_one() {
+            Err(data)
+        } else {
+            return Err(RunnerError::InvalidPanicResultVariant(variant.into_owned()));
+        };
+        Ok(RunResultStarknet {
+            gas_counter,
+            result,
+        })
+    }
+
     // Returns Ok(()) if there are enough allocated cells for the builtins.
     // If not, the number of steps should be increased or a different layout should be used.
     pub fn check_used_cells(&self, vm: &VirtualMachine) -> Result<(), VirtualMachineError> {
@@ -1009,7 +1402,12 @@ impl CairoRunner {
                 .identifiers
                 .get(&format!("__main__.{new_entrypoint}"))
                 .and_then(|x| x.pc)
-                .ok_or_else(|| ProgramError::EntrypointNotFound(new_entrypoint.to_string()))?,
+                .ok_or_else(|| {
+                    ProgramError::EntrypointNotFound(
+                        new_entrypoint.to_string(),
+                        available_entrypoints(&self.program.identifiers),
+                    )
+                })?,
         );
 
         Ok(())
@@ -1019,11 +1417,8 @@ impl CairoRunner {
         if !self.run_ended {
             return Err(RunnerError::ReadReturnValuesNoEndRun);
         }
-        let mut pointer = vm.get_ap();
-        for (_, builtin_runner) in vm.builtin_runners.iter_mut().rev() {
-            let new_pointer = builtin_runner.final_stack(&vm.segments, pointer)?;
-            pointer = new_pointer;
-        }
+        let ap = vm.get_ap();
+        let pointer = self.finalize_builtins(vm, ap)?;
         if self.segments_finalized {
             return Err(RunnerError::FailedAddingReturnValues);
         }
@@ -1033,7 +1428,6 @@ impl CairoRunner {
                 .as_ref()
                 .ok_or(RunnerError::NoExecBase)?;
             let begin = pointer.offset - exec_base.offset;
-            let ap = vm.get_ap();
             let end = ap.offset - exec_base.offset;
             self.execution_public_memory
                 .as_mut()
@@ -1043,6 +1437,21 @@ impl CairoRunner {
         Ok(())
     }
 
+    /// Calls `final_stack` on every builtin runner in reverse layout order, threading the stack
+    /// pointer through them, and returns the pointer left after the last one.
+    /// Each included builtin pops its stop pointer from the stack and records it as its `stop_ptr`.
+    pub fn finalize_builtins(
+        &mut self,
+        vm: &mut VirtualMachine,
+        pointer: Relocatable,
+    ) -> Result<Relocatable, RunnerError> {
+        let mut pointer = pointer;
+        for (_, builtin_runner) in vm.builtin_runners.iter_mut().rev() {
+            pointer = builtin_runner.final_stack(&vm.segments, pointer)?;
+        }
+        Ok(pointer)
+    }
+
     /// Add (or replace if already present) a custom hash builtin. Returns a Relocatable
     /// with the new builtin base as the segment index.
     pub fn add_additional_hash_builtin(&self, vm: &mut VirtualMachine) -> Relocatable {
@@ -1082,19 +1491,344 @@ impl CairoRunner {
         }
         Ok(stack_ptr)
     }
+
+    /// Builds a Cairo PIE out of the current run.
+    ///     Note: end_run() and read_return_values() must precede a call to this method,
+    ///     so that the segment sizes and the builtins' stop pointers are known.
+    pub fn get_cairo_pie(&self, vm: &VirtualMachine) -> Result<CairoPie, RunnerError> {
+        let program_base = self.program_base.ok_or(RunnerError::NoProgBase)?;
+        let execution_base = self.execution_base.ok_or(RunnerError::NoExecBase)?;
+
+        let mut builtin_segments = HashMap::new();
+        for (name, builtin) in vm.builtin_runners.iter() {
+            let (base, stop_ptr) = builtin.get_memory_segment_addresses();
+            builtin_segments.insert(
+                name.to_string(),
+                SegmentInfo {
+                    index: base as isize,
+                    size: stop_ptr.ok_or(RunnerError::NoStopPointer(*name))?,
+                },
+            );
+        }
+
+        let return_fp_addr = (execution_base + self.program.builtins.len())?;
+        let return_fp = vm.get_relocatable(return_fp_addr)?;
+        let return_pc = vm.get_relocatable((return_fp_addr + 1)?)?;
+        if vm.get_segment_used_size(return_fp.segment_index as usize) != Some(0) {
+            return Err(RunnerError::UnexpectedRetFpSegmentSize);
+        }
+        if vm.get_segment_used_size(return_pc.segment_index as usize) != Some(0) {
+            return Err(RunnerError::UnexpectedRetPcSegmentSize);
+        }
+
+        let mut known_segment_indices: HashSet<isize> = builtin_segments
+            .values()
+            .map(|segment_info| segment_info.index)
+            .collect();
+        for addr in [program_base, execution_base, return_fp, return_pc] {
+            known_segment_indices.insert(addr.segment_index);
+        }
+        let extra_segments = (0..vm.segments.num_segments())
+            .filter(|index| !known_segment_indices.contains(&(*index as isize)))
+            .map(|index| {
+                Ok(SegmentInfo {
+                    index: index as isize,
+                    size: vm
+                        .get_segment_used_size(index)
+                        .ok_or(MemoryError::MissingSegmentUsedSizes)?,
+                })
+            })
+            .collect::<Result<Vec<_>, RunnerError>>()?;
+
+        let metadata = CairoPieMetadata {
+            program: StrippedProgram {
+                data: self.program.data.clone(),
+                builtins: self.program.builtins.clone(),
+                main: self.program.main.ok_or(RunnerError::MissingMain)?,
+                prime: self.program.prime.clone(),
+            },
+            program_segment: SegmentInfo {
+                index: program_base.segment_index,
+                size: self.program.data.len(),
+            },
+            execution_segment: SegmentInfo {
+                index: execution_base.segment_index,
+                size: (vm.get_ap() - execution_base)?,
+            },
+            ret_fp_segment: SegmentInfo {
+                index: return_fp.segment_index,
+                size: 0,
+            },
+            ret_pc_segment: SegmentInfo {
+                index: return_pc.segment_index,
+                size: 0,
+            },
+            builtin_segments,
+            extra_segments,
+        };
+
+        Ok(CairoPie {
+            metadata,
+            memory: (&vm.segments.memory).into(),
+            execution_resources: self
+                .get_execution_resources(vm)
+                .map_err(RunnerError::Trace)?,
+            additional_data: vm
+                .builtin_runners
+                .iter()
+                .map(|(name, builtin)| (name.to_string(), builtin.get_additional_data()))
+                .collect(),
+            version: CairoPieVersion::default(),
+        })
+    }
+
+    /// Builds the public input of the Cairo AIR for a proof-mode run.
+    ///     Note: finalize_segments() and relocate() must precede a call to this method.
+    ///     Output cells are assigned to the pages added through
+    ///     [OutputBuiltinRunner::add_page](super::builtin_runner::OutputBuiltinRunner::add_page),
+    ///     every other public memory cell belongs to the main page (page 0).
+    pub fn get_air_public_input(
+        &self,
+        vm: &VirtualMachine,
+    ) -> Result<PublicInput, PublicInputError> {
+        let n_steps = self
+            .relocated_trace
+            .as_ref()
+            .ok_or(PublicInputError::TraceNotRelocated)?
+            .len();
+        let (rc_min, rc_max) = self
+            .get_perm_range_check_limits(vm)?
+            .ok_or(PublicInputError::NoRangeCheckLimits)?;
+        let relocation_table = vm.segments.relocate_segments()?;
+
+        let segment_addresses = |begin: Relocatable, stop: Relocatable| {
+            Ok::<_, MemoryError>(MemorySegmentAddresses {
+                begin_addr: relocate_address(begin, &relocation_table)?,
+                stop_ptr: relocate_address(stop, &relocation_table)?,
+            })
+        };
+        let mut memory_segments = MemorySegments::default();
+        memory_segments.insert(
+            "program",
+            segment_addresses(
+                self.program_base.ok_or(RunnerError::NoProgBase)?,
+                self.final_pc.ok_or(RunnerError::NoPC)?,
+            )?,
+        );
+        memory_segments.insert(
+            "execution",
+            segment_addresses(
+                self.execution_base.ok_or(RunnerError::NoExecBase)?,
+                vm.get_ap(),
+            )?,
+        );
+        for (name, builtin) in vm.builtin_runners.iter() {
+            let (base, stop_ptr) = builtin.get_memory_segment_addresses();
+            let stop_ptr = stop_ptr.ok_or(RunnerError::NoStopPointer(*name))?;
+            memory_segments.insert(
+                name,
+                segment_addresses(
+                    Relocatable::from((base as isize, 0)),
+                    Relocatable::from((base as isize, stop_ptr)),
+                )?,
+            );
+        }
+
+        let public_memory = vm
+            .segments
+            .get_public_memory_addresses(&relocation_table)?
+            .into_iter()
+            .map(|(address, page)| {
+                let value = self
+                    .relocated_memory
+                    .get(address)
+                    .and_then(|value| value.clone())
+                    .ok_or(PublicInputError::MemoryNotFound(address))?;
+                Ok(PublicMemoryEntry {
+                    address,
+                    value,
+                    page,
+                })
+            })
+            .collect::<Result<Vec<_>, PublicInputError>>()?;
+
+        Ok(PublicInput {
+            layout: self.layout._name.clone(),
+            rc_min,
+            rc_max,
+            n_steps,
+            memory_segments,
+            public_memory,
+        })
+    }
+
+    /// Writes the public input of the Cairo AIR as the JSON file consumed by the prover.
+    /// See [get_air_public_input](Self::get_air_public_input) for the requirements.
+    #[cfg(feature = "std")]
+    pub fn write_air_public_input<W: Write>(
+        &self,
+        vm: &VirtualMachine,
+        writer: W,
+    ) -> Result<(), PublicInputError> {
+        Ok(self.get_air_public_input(vm)?.write_json(writer)?)
+    }
+
+    /// Builds the private input of the Cairo AIR, pointing the prover to the trace and memory
+    /// files written at `trace_path` and `memory_path`
+    pub fn get_air_private_input(
+        &self,
+        vm: &VirtualMachine,
+        trace_path: &str,
+        memory_path: &str,
+    ) -> AirPrivateInput {
+        AirPrivateInput {
+            trace_path: trace_path.to_string(),
+            memory_path: memory_path.to_string(),
+            builtins: vm
+                .builtin_runners
+                .iter()
+                // The output builtin has no private input
+                .filter(|(_, builtin)| !matches!(builtin, BuiltinRunner::Output(_)))
+                .map(|(name, builtin)| {
+                    (
+                        name.to_string(),
+                        builtin.air_private_input(&vm.segments.memory),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Writes the private input of the Cairo AIR as the JSON file consumed by the prover
+    #[cfg(feature = "std")]
+    pub fn write_air_private_input<W: Write>(
+        &self,
+        vm: &VirtualMachine,
+        trace_path: &str,
+        memory_path: &str,
+        writer: W,
+    ) -> std::io::Result<()> {
+        self.get_air_private_input(vm, trace_path, memory_path)
+            .write_json(writer)
+    }
+
+    /// Writes the relocated trace in the binary format of the Python VM's trace file, see
+    /// [write_encoded_trace](crate::cairo_run::write_encoded_trace).
+    ///     Note: the trace must be enabled, and relocate() must precede a call to this method.
+    #[cfg(feature = "std")]
+    pub fn write_binary_trace(&self, writer: &mut impl Write) -> Result<(), TraceError> {
+        let relocated_trace = self
+            .relocated_trace
+            .as_ref()
+            .ok_or(TraceError::TraceNotRelocated)?;
+        write_encoded_trace(relocated_trace, &mut IoWriter::new(writer))
+            .map_err(|err| TraceError::Write(err.to_string()))
+    }
+
+    /// Writes the relocated trace as [write_binary_trace](Self::write_binary_trace) does,
+    /// preceded by a header:
+    /// * [TRACE_MAGIC] -> 4 bytes
+    /// * [TRACE_FORMAT_VERSION] -> 4-byte little endian
+    /// * number of entries -> 8-byte little endian
+    ///
+    ///     Note: the trace must be enabled, and relocate() must precede a call to this method.
+    #[cfg(feature = "std")]
+    pub fn write_binary_trace_v2(&self, writer: &mut impl Write) -> Result<(), TraceError> {
+        let relocated_trace = self
+            .relocated_trace
+            .as_ref()
+            .ok_or(TraceError::TraceNotRelocated)?;
+        let mut header = Vec::with_capacity(TRACE_MAGIC.len() + 4 + 8);
+        header.extend_from_slice(&TRACE_MAGIC);
+        header.extend_from_slice(&TRACE_FORMAT_VERSION.to_le_bytes());
+        header.extend_from_slice(&(relocated_trace.len() as u64).to_le_bytes());
+        writer
+            .write_all(&header)
+            .map_err(|e| TraceError::Write(e.to_string()))?;
+        self.write_binary_trace(writer)
+    }
+
+    /// Writes the relocated memory in the binary format of the Python VM's memory file, see
+    /// [write_encoded_memory](crate::cairo_run::write_encoded_memory).
+    ///     Note: relocate() must precede a call to this method.
+    #[cfg(feature = "std")]
+    pub fn write_binary_memory(&self, writer: &mut impl Write) -> Result<(), MemoryError> {
+        if self.relocated_memory.is_empty() {
+            return Err(MemoryError::MemoryNotRelocated);
+        }
+        write_encoded_memory(&self.relocated_memory, &mut IoWriter::new(writer))
+            .map_err(|err| MemoryError::Write(err.to_string()))
+    }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// Cell usage of a builtin segment, see [CairoRunner::get_builtin_use_counts_by_segment]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BuiltinSegmentUsage {
+    pub name: &'static str,
+    pub used_cells: usize,
+    pub allocated_cells: usize,
+    pub memory_holes: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SegmentInfo {
     pub index: isize,
     pub size: usize,
 }
 
+/// Leading bytes of a trace written by [CairoRunner::write_binary_trace_v2]
+pub const TRACE_MAGIC: [u8; 4] = *b"CTRC";
+/// Version of the trace format written by [CairoRunner::write_binary_trace_v2]. The headerless
+/// format of [CairoRunner::write_binary_trace] is version 1.
+pub const TRACE_FORMAT_VERSION: u32 = 2;
+
+/// Adapts a [Write] to the bincode [Writer] taken by the encoders of [cairo_run](crate::cairo_run)
+#[cfg(feature = "std")]
+struct IoWriter<'a, W: Write> {
+    writer: &'a mut W,
+    bytes_written: usize,
+}
+
+#[cfg(feature = "std")]
+impl<'a, W: Write> IoWriter<'a, W> {
+    fn new(writer: &'a mut W) -> Self {
+        IoWriter {
+            writer,
+            bytes_written: 0,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> Writer for IoWriter<'_, W> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), bincode::error::EncodeError> {
+        self.writer
+            .write_all(bytes)
+            .map_err(|e| bincode::error::EncodeError::Io {
+                inner: e,
+                index: self.bytes_written,
+            })?;
+        self.bytes_written += bytes.len();
+        Ok(())
+    }
+}
+
+/// Panic data of a Cairo 1 function that ran out of gas: the short string 'Out of gas'
+const OUT_OF_GAS_PANIC: u128 = 0x4f7574206f6620676173;
+
+/// Return values of a Cairo 1 function returning a `PanicResult`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunResultStarknet {
+    pub gas_counter: Felt,
+    /// The returned span if the function ended normally, or the panic data if it panicked
+    pub result: Result<Vec<Felt>, Vec<Felt>>,
+}
+
 //* ----------------------
 //*   ExecutionResources
 //* ----------------------
 
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct ExecutionResources {
     pub n_steps: usize,
     pub n_memory_holes: usize,
@@ -1173,15 +1907,25 @@ impl Sub for ExecutionResources {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::air_private_input::{PrivateInput, PrivateInputPair};
+    #[cfg(feature = "std")]
+    use crate::serde::serialize_utils::FELT_BYTES;
     use crate::stdlib::collections::{HashMap, HashSet};
     use crate::vm::vm_memory::memory::MemoryCell;
     use crate::vm::vm_memory::memory_segments::MemorySegmentManager;
     use crate::{
-        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::{
+            BuiltinHintProcessor, HintFunc, HintProcessorData,
+        },
         relocatable,
-        serde::deserialize_program::{Identifier, ReferenceManager},
+        serde::deserialize_program::{
+            ApTracking, FlowTrackingData, HintParams, Identifier, ReferenceManager,
+        },
+        stdlib::sync::Arc,
         types::instance_definitions::bitwise_instance_def::BitwiseInstanceDef,
         utils::test_utils::*,
+        vm::errors::hint_errors::HintError,
+        vm::runners::cairo_pie::{BuiltinAdditionalData, CAIRO_PIE_VERSION},
         vm::{trace::trace_entry::TraceEntry, vm_memory::memory::Memory},
     };
     use assert_matches::assert_matches;
@@ -1280,6 +2024,45 @@ mod tests {
         assert_eq!(vm.segments.num_segments(), 8);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn relocation_table_order_recursive_layout() {
+        let program = program![
+            OUTPUT_BUILTIN_NAME,
+            HASH_BUILTIN_NAME,
+            RANGE_CHECK_BUILTIN_NAME,
+            BITWISE_BUILTIN_NAME,
+            KECCAK_BUILTIN_NAME
+        ];
+        let mut cairo_runner = cairo_runner!(program, "recursive");
+        let mut vm = vm!();
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+
+        assert_eq!(cairo_runner.program_base, Some(Relocatable::from((0, 0))));
+        assert_eq!(cairo_runner.execution_base, Some(Relocatable::from((1, 0))));
+        assert_eq!(
+            vm.builtin_runners
+                .iter()
+                .map(|(name, builtin)| (*name, builtin.base()))
+                .collect::<Vec<_>>(),
+            vec![
+                (OUTPUT_BUILTIN_NAME, 2),
+                (HASH_BUILTIN_NAME, 3),
+                (RANGE_CHECK_BUILTIN_NAME, 4),
+                (BITWISE_BUILTIN_NAME, 5),
+                (KECCAK_BUILTIN_NAME, 6)
+            ]
+        );
+
+        vm.segments.segment_used_sizes = Some(vec![10, 20, 1, 3, 2, 5, 16]);
+        let relocation_table = vm.segments.relocate_segments().unwrap();
+        assert_eq!(relocation_table, vec![1, 11, 31, 32, 35, 37, 42]);
+        // The keccak segment is the last builtin segment of the layout, so it is relocated last
+        let keccak_base = vm.builtin_runners[4].1.base();
+        assert_eq!(relocation_table[keccak_base], 42);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn initialize_segments_no_base() {
@@ -2120,21 +2903,279 @@ mod tests {
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     /*Program used:
-    %builtins output range_check
-
-    from starkware.cairo.common.serialize import serialize_word
-
-    func check_range{range_check_ptr}(num) -> (num : felt):
+    %builtins segment_arena
 
-        # Check that 0 <= num < 2**64.
-        [range_check_ptr] = num
-        assert [range_check_ptr + 1] = 2 ** 64 - 1 - num
-        let range_check_ptr = range_check_ptr + 2
-        return(num)
-    end
+    func main{segment_arena_ptr}() {
+        return ();
+    }
+    */
+    fn initialize_and_run_segment_arena_builtin() {
+        let program = program!(
+            builtins = vec![SEGMENT_ARENA_BUILTIN_NAME],
+            data = vec_data!((5191102247248822272_i64), (2345108766317314046_i64)),
+            main = Some(0),
+        );
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        assert_eq!(vm.builtin_runners[0].0, SEGMENT_ARENA_BUILTIN_NAME);
+        assert_matches!(
+            cairo_runner.run_until_pc(end, &mut vm, &mut hint_processor),
+            Ok(())
+        );
+        assert_matches!(
+            cairo_runner.end_run(false, false, &mut vm, &mut hint_processor),
+            Ok(())
+        );
+        assert_matches!(cairo_runner.read_return_values(&mut vm), Ok(()));
+        let segment_arena = vm.builtin_runners[0].1.as_segment_arena().unwrap();
+        assert_eq!(segment_arena.get_memory_segment_addresses(), (2, Some(3)));
+    }
 
-    func main{output_ptr: felt*, range_check_ptr: felt}():
-        let num: felt = check_range(7)
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_segment_arena_info_two_segments() {
+        let program = program![SEGMENT_ARENA_BUILTIN_NAME];
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+        // Segments: program (0), execution (1), segment arena (2) and arena infos (3)
+        let first = vm.segments.add();
+        let second = vm.segments.add();
+        let arena = vm.builtin_runners[0].1.as_segment_arena().unwrap();
+        assert_eq!(arena.base(), 2);
+        vm.segments
+            .load_data(
+                Relocatable::from((3, 0)),
+                &vec![
+                    first.into(),
+                    (first + 2_usize).unwrap().into(),
+                    Felt::zero().into(),
+                    second.into(),
+                    (second + 5_usize).unwrap().into(),
+                    Felt::one().into(),
+                ],
+            )
+            .unwrap();
+        vm.segments
+            .load_data(
+                Relocatable::from((2, 3)),
+                &vec![
+                    Relocatable::from((3, 0)).into(),
+                    Felt::new(2).into(),
+                    Felt::new(2).into(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            cairo_runner.get_segment_arena_info(&vm),
+            Ok(vec![
+                ArenaSegmentInfo {
+                    start: first,
+                    end: Some((first + 2_usize).unwrap()),
+                    squashed: true,
+                },
+                ArenaSegmentInfo {
+                    start: second,
+                    end: Some((second + 5_usize).unwrap()),
+                    squashed: true,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_segment_arena_info_no_segment_arena_builtin() {
+        let program = program![OUTPUT_BUILTIN_NAME];
+        let cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        assert_eq!(
+            cairo_runner.get_segment_arena_info(&vm),
+            Err(RunnerError::NoSegmentArenaBuiltin)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_casm_program() {
+        let selector = felt_str!(
+            "1d57794b1828b5fb83c8b9ba0212fc9e3b0b24007a3ec9c9ff4040523a04763",
+            16
+        );
+        let program = Program::from_casm_bytes(
+            include_bytes!("../../../cairo_programs/manually_compiled/casm_add_constants.json"),
+            Some(&selector),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let initial_ap = vm.get_ap();
+        let num_segments = vm.segments.num_segments();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+
+        // The hint allocated a segment, skipped by `ap += 1`
+        assert_eq!(vm.segments.num_segments(), num_segments + 1);
+        assert_eq!(
+            vm.get_relocatable(initial_ap),
+            Ok(Relocatable::from((num_segments as isize, 0)))
+        );
+        assert_eq!(
+            vm.get_return_values(1).unwrap(),
+            vec![MaybeRelocatable::from(12)]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_builtin_use_counts_by_segment_proof_mode() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/proof_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program, "recursive", true);
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut vm = vm!(true);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        // Proof mode pads the run until every builtin of the layout has its cells allocated,
+        // which takes a whole keccak component, of 16 instances of 2048 steps
+        cairo_runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        assert!(vm.current_step >= 16 * 2048);
+
+        let usage = cairo_runner.get_builtin_use_counts_by_segment(&vm).unwrap();
+        assert_eq!(usage.len(), vm.builtin_runners.len());
+        let usage_of = |builtin_name: &str| {
+            let (_, builtin) = vm
+                .builtin_runners
+                .iter()
+                .find(|(name, _)| *name == builtin_name)
+                .unwrap();
+            usage[&builtin.base()]
+        };
+
+        // A single bitwise instance was used, out of one per 16 steps
+        let bitwise_usage = usage_of(BITWISE_BUILTIN_NAME);
+        assert_eq!(bitwise_usage.name, BITWISE_BUILTIN_NAME);
+        assert_eq!(bitwise_usage.used_cells, 5);
+        assert_eq!(bitwise_usage.allocated_cells, 5 * (vm.current_step / 16));
+        // The program doesn't use keccak, whose cells are still allocated
+        let keccak_usage = usage_of(KECCAK_BUILTIN_NAME);
+        assert_eq!(keccak_usage.used_cells, 0);
+        assert_eq!(keccak_usage.allocated_cells, 16 * (vm.current_step / 2048));
+        assert_eq!(keccak_usage.memory_holes, 0);
+        let output_usage = usage_of(OUTPUT_BUILTIN_NAME);
+        assert_eq!(output_usage.used_cells, 1);
+
+        let builtin_holes: usize = usage.values().map(|usage| usage.memory_holes).sum();
+        assert!(builtin_holes <= cairo_runner.get_memory_holes(&vm).unwrap());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_traceback_nested_calls() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/bad_programs/bad_range_check.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program, "all", false);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        // The fp chain of main's frame leaves the execution segment right away
+        assert_eq!(cairo_runner.get_traceback(&vm), None);
+
+        assert!(cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .is_err());
+        let traceback = cairo_runner.get_traceback(&vm).unwrap();
+        assert!(traceback.starts_with("Cairo traceback (most recent call last):\n"));
+        let frames: Vec<_> = traceback
+            .lines()
+            .filter_map(|line| line.split(": (pc=").nth(1))
+            .collect();
+        assert_eq!(frames, vec!["0:29)", "0:21)", "0:17)", "0:6)"]);
+        assert!(
+            traceback.contains("cairo_programs/bad_programs/bad_range_check.cairo:11:5: (pc=0:6)")
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn initialize_builtins_segment_arena_missing_from_layout() {
+        let program = program![SEGMENT_ARENA_BUILTIN_NAME];
+        let cairo_runner = cairo_runner!(program, "small");
+        let mut vm = vm!();
+        assert_eq!(
+            cairo_runner.initialize_builtins(&mut vm),
+            Err(RunnerError::NoBuiltinForInstance(
+                HashSet::from([SEGMENT_ARENA_BUILTIN_NAME]),
+                String::from("small")
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn initialize_builtins_range_check96() {
+        let program = program![RANGE_CHECK_BUILTIN_NAME, RANGE_CHECK_96_BUILTIN_NAME];
+        let cairo_runner = cairo_runner!(program, "all");
+        let mut vm = vm!();
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        assert_eq!(vm.builtin_runners.len(), 2);
+        assert_eq!(vm.builtin_runners[0].0, RANGE_CHECK_BUILTIN_NAME);
+        assert_eq!(vm.builtin_runners[1].0, RANGE_CHECK_96_BUILTIN_NAME);
+        assert_matches!(vm.builtin_runners[1].1, BuiltinRunner::RangeCheck96(_));
+        assert_eq!(vm.builtin_runners[1].1.name(), RANGE_CHECK_96_BUILTIN_NAME);
+        assert_eq!(vm.builtin_runners[1].1.ratio(), Some(8));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn initialize_builtins_range_check96_missing_from_layout() {
+        let program = program![RANGE_CHECK_96_BUILTIN_NAME];
+        let cairo_runner = cairo_runner!(program, "recursive");
+        let mut vm = vm!();
+        assert_eq!(
+            cairo_runner.initialize_builtins(&mut vm),
+            Err(RunnerError::NoBuiltinForInstance(
+                HashSet::from([RANGE_CHECK_96_BUILTIN_NAME]),
+                String::from("recursive")
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    /*Program used:
+    %builtins output range_check
+
+    from starkware.cairo.common.serialize import serialize_word
+
+    func check_range{range_check_ptr}(num) -> (num : felt):
+
+        # Check that 0 <= num < 2**64.
+        [range_check_ptr] = num
+        assert [range_check_ptr + 1] = 2 ** 64 - 1 - num
+        let range_check_ptr = range_check_ptr + 2
+        return(num)
+    end
+
+    func main{output_ptr: felt*, range_check_ptr: felt}():
+        let num: felt = check_range(7)
         serialize_word(num)
         return()
     end
@@ -2277,6 +3318,105 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_relocated_value_matches_relocated_memory() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/struct.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        cairo_runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+
+        // The execution segment holds the return fp and pc, which are relocatable
+        let execution_base = cairo_runner.execution_base.unwrap();
+        let return_fp = cairo_runner
+            .get_relocated_value(&vm, execution_base)
+            .unwrap();
+        let return_pc = cairo_runner
+            .get_relocated_value(&vm, (execution_base + 1_usize).unwrap())
+            .unwrap();
+
+        let relocation_table = vm.segments.relocate_segments().unwrap();
+        cairo_runner.relocate(&mut vm, false).unwrap();
+        let execution_start = relocation_table[execution_base.segment_index as usize];
+        assert_eq!(
+            Some(&return_fp),
+            cairo_runner.relocated_memory[execution_start].as_ref()
+        );
+        assert_eq!(
+            Some(&return_pc),
+            cairo_runner.relocated_memory[execution_start + 1].as_ref()
+        );
+
+        for (segment_index, segment) in vm.segments.memory.data.iter().enumerate() {
+            for offset in 0..segment.len() {
+                let addr = Relocatable::from((segment_index as isize, offset));
+                let relocated_addr = relocation_table[segment_index] + offset;
+                assert_eq!(
+                    cairo_runner.get_relocated_value(&vm, addr).ok().as_ref(),
+                    cairo_runner.relocated_memory[relocated_addr].as_ref()
+                );
+            }
+        }
+        assert_eq!(
+            cairo_runner.relocate_value(Relocatable::from((1, 3))),
+            Ok(Felt::new(relocation_table[1] + 3))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_relocated_value_errors() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/struct.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        let execution_base = cairo_runner.execution_base.unwrap();
+        // The relocation table is only known once the run ended
+        assert_eq!(
+            cairo_runner.get_relocated_value(&vm, execution_base),
+            Err(MemoryError::MissingSegmentUsedSizes)
+        );
+        cairo_runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        assert!(cairo_runner
+            .get_relocated_value(&vm, execution_base)
+            .is_ok());
+
+        let unknown_addr = Relocatable::from((0, program.data.len()));
+        assert_eq!(
+            cairo_runner.get_relocated_value(&vm, unknown_addr),
+            Err(MemoryError::UnknownMemoryCell(unknown_addr))
+        );
+        assert_eq!(
+            cairo_runner.relocate_value(Relocatable::from((-1, 0))),
+            Err(MemoryError::TemporarySegmentInRelocation(-1))
+        );
+        assert_eq!(
+            cairo_runner.relocate_value(Relocatable::from((100, 0))),
+            Err(MemoryError::Relocation)
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     /*Memory from this test is taken from a cairo program execution
@@ -2533,6 +3673,36 @@ mod tests {
         assert_eq!(cairo_runner.relocated_memory[28], Some(Felt::new(17)));
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn relocate_memory_skipping_trace() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/struct.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let run = |relocate_trace: bool| {
+            let mut cairo_runner = cairo_runner!(program);
+            let mut vm = vm!(true);
+            let end = cairo_runner.initialize(&mut vm).unwrap();
+            cairo_runner
+                .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+                .unwrap();
+            assert_eq!(cairo_runner.relocate(&mut vm, relocate_trace), Ok(()));
+            cairo_runner
+        };
+
+        let full_relocation = run(true);
+        let memory_relocation = run(false);
+        assert!(full_relocation.relocated_trace.is_some());
+        assert_eq!(memory_relocation.relocated_trace, None);
+        assert!(!memory_relocation.relocated_memory.is_empty());
+        assert_eq!(
+            memory_relocation.relocated_memory,
+            full_relocation.relocated_memory
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     /* Program used:
@@ -2819,6 +3989,65 @@ mod tests {
         assert_eq!(&output_buffer, "<missing>\n2:0\n");
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_output_as_felts() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/output_three_values.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+        assert_eq!(
+            cairo_runner.get_output_as_felts(&vm),
+            Ok(vec![Felt::new(1), Felt::new(-17), Felt::new(1000000)])
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_output_as_felts_no_output_builtin() {
+        let program = program!();
+        let cairo_runner = cairo_runner!(program);
+        let vm = vm!();
+        assert_eq!(
+            cairo_runner.get_output_as_felts(&vm),
+            Err(RunnerError::NoOutputBuiltin)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_output_as_felts_non_integer_cells() {
+        let program = program![OUTPUT_BUILTIN_NAME];
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+        assert_eq!(vm.builtin_runners[0].1.base(), 2);
+
+        vm.segments = segments![((2, 1), 7)];
+        assert_eq!(
+            cairo_runner.get_output_as_felts(&vm),
+            Err(RunnerError::Memory(MemoryError::UnknownMemoryCell(
+                Relocatable::from((2, 0))
+            )))
+        );
+
+        vm.segments = segments![((2, 0), 7), ((2, 1), (1, 0))];
+        assert_eq!(
+            cairo_runner.get_output_as_felts(&vm),
+            Err(RunnerError::Memory(MemoryError::ExpectedInteger(
+                Relocatable::from((2, 1))
+            )))
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_output_from_preset_memory_neg_output() {
@@ -3401,13 +4630,57 @@ mod tests {
 
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
-    fn get_builtin_segments_info_empty() {
-        let program = program!();
+    fn initialize_proof_mode_registers_program_as_public_memory() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/proof_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let program_size = program.data.len();
 
-        let cairo_runner = cairo_runner!(program);
-        let vm = vm!();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut cairo_runner = cairo_runner!(program, "all", true);
+        let mut vm = vm!(true);
 
-        assert_eq!(cairo_runner.get_builtin_segments_info(&vm), Ok(Vec::new()),);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        // Execution starts at __start__ and ends at __end__, with the dummy fp and pc on the stack
+        assert_eq!(
+            vm.get_pc(),
+            Relocatable::from((0, cairo_runner.program.start.unwrap()))
+        );
+        assert_eq!(
+            end,
+            Relocatable::from((0, cairo_runner.program.end.unwrap()))
+        );
+        assert_eq!(cairo_runner.execution_public_memory, Some(vec![0, 1]));
+
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        cairo_runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        cairo_runner.finalize_segments(&mut vm).unwrap();
+
+        assert_eq!(
+            vm.segments.public_memory_offsets.get(&0),
+            Some(&(0..program_size).map(|i| (i, 0)).collect::<Vec<_>>())
+        );
+        assert_eq!(
+            vm.segments.public_memory_offsets.get(&1),
+            Some(&vec![(0, 0), (1, 0)])
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_builtin_segments_info_empty() {
+        let program = program!();
+
+        let cairo_runner = cairo_runner!(program);
+        let vm = vm!();
+
+        assert_eq!(cairo_runner.get_builtin_segments_info(&vm), Ok(Vec::new()),);
     }
 
     #[test]
@@ -4565,6 +5838,50 @@ mod tests {
             .contains_key(RANGE_CHECK_BUILTIN_NAME));
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_from_entrypoint_span_argument() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/array_sum.json"),
+            None,
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+
+        let entrypoint = program
+            .identifiers
+            .get("__main__.array_sum")
+            .unwrap()
+            .pc
+            .unwrap();
+
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+
+        // array_sum(arr, size) doesn't use the output builtin, so no builtin pointer is passed
+        let array = CairoArg::Span(vec![
+            MaybeRelocatable::from(9),
+            MaybeRelocatable::from(16),
+            MaybeRelocatable::from(25),
+        ]);
+        assert_matches!(
+            cairo_runner.run_from_entrypoint(
+                entrypoint,
+                &[&array],
+                true,
+                &mut vm,
+                &mut hint_processor,
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            vm.get_return_values(1).unwrap(),
+            vec![MaybeRelocatable::from(50)]
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn run_from_entrypoint_substitute_error_message_test() {
@@ -4607,6 +5924,58 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn finalize_builtins_recursive_layout() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/proof_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program, "recursive", true);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+        vm.segments.compute_effective_sizes();
+
+        // Proof mode adds every builtin in the layout, only output and bitwise are included
+        assert_eq!(
+            vm.builtin_runners
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>(),
+            [
+                OUTPUT_BUILTIN_NAME,
+                HASH_BUILTIN_NAME,
+                RANGE_CHECK_BUILTIN_NAME,
+                BITWISE_BUILTIN_NAME,
+                KECCAK_BUILTIN_NAME
+            ]
+        );
+        let ap = vm.get_ap();
+        let final_pointer = runner.finalize_builtins(&mut vm, ap).unwrap();
+        assert_eq!(final_pointer, (ap - 2).unwrap());
+
+        for (name, builtin) in vm.builtin_runners.iter() {
+            let (base, stop_ptr) = builtin.get_memory_segment_addresses();
+            // A single word was written to the output and a single bitwise instance was used,
+            // the builtins left out of the program record their base as stop pointer
+            let expected_stop_ptr = match *name {
+                OUTPUT_BUILTIN_NAME => 1,
+                BITWISE_BUILTIN_NAME => 5,
+                _ => base,
+            };
+            assert_eq!(stop_ptr, Some(expected_stop_ptr), "{name}");
+        }
+        // The stop pointers are popped in reverse layout order
+        assert_eq!(
+            vm.segments.memory.get_relocatable(final_pointer).unwrap(),
+            Relocatable::from((vm.builtin_runners[0].1.base() as isize, 1))
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_builtins_final_stack_range_check_builtin() {
@@ -4676,6 +6045,229 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_until_pc_with_max_steps_infinite_loop() {
+        // jmp rel 0
+        let program = program!(
+            data = vec_data!((74168662805676031_i64), (0)),
+            main = Some(0),
+        );
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = runner.initialize(&mut vm).unwrap();
+        assert_matches!(
+            runner.run_until_pc_with_max_steps(
+                end,
+                &mut vm,
+                &mut BuiltinHintProcessor::new_empty(),
+                100
+            ),
+            Err(VirtualMachineError::MaxStepsExceeded(100))
+        );
+        assert_eq!(vm.current_step, 100);
+        assert_eq!(vm.get_pc(), Relocatable::from((0, 0)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_until_pc_with_max_steps_enough_steps() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = runner.initialize(&mut vm).unwrap();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        let n_steps = vm.current_step;
+
+        // The limit is only hit if the program hasn't finished by then
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = runner.initialize(&mut vm).unwrap();
+        assert_matches!(
+            runner.run_until_pc_with_max_steps(end, &mut vm, &mut hint_processor, n_steps),
+            Ok(())
+        );
+
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = runner.initialize(&mut vm).unwrap();
+        assert_matches!(
+            runner.run_until_pc_with_max_steps(end, &mut vm, &mut hint_processor, n_steps - 1),
+            Err(VirtualMachineError::MaxStepsExceeded(max_steps)) if max_steps == n_steps - 1
+        );
+        assert_eq!(vm.current_step, n_steps - 1);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn vm_step_matches_run_for_steps() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+
+        let mut batch_runner = cairo_runner!(program);
+        let mut batch_vm = vm!(true);
+        batch_runner.initialize(&mut batch_vm).unwrap();
+        batch_runner
+            .run_for_steps(20, &mut batch_vm, &mut hint_processor)
+            .unwrap();
+
+        let mut step_runner = cairo_runner!(program);
+        let mut step_vm = vm!(true);
+        step_runner.initialize(&mut step_vm).unwrap();
+        let references = step_runner.get_reference_list();
+        let hint_data_dictionary = step_runner
+            .get_hint_data_dictionary(&references, &mut hint_processor)
+            .unwrap();
+        for _ in 0..20 {
+            step_vm
+                .step(
+                    &mut hint_processor,
+                    &mut step_runner.exec_scopes,
+                    &hint_data_dictionary,
+                    &step_runner.program.constants,
+                )
+                .unwrap();
+        }
+
+        assert_eq!(step_vm.current_step, 20);
+        assert_eq!(step_vm.current_step, batch_vm.current_step);
+        assert_eq!(step_vm.get_pc(), batch_vm.get_pc());
+        assert_eq!(step_vm.get_ap(), batch_vm.get_ap());
+        assert_eq!(step_vm.get_fp(), batch_vm.get_fp());
+        assert_eq!(step_vm.trace, batch_vm.trace);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_cairo_pie_bitwise_output() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.read_return_values(&mut vm).unwrap();
+
+        let cairo_pie = runner.get_cairo_pie(&vm).unwrap();
+        let metadata = &cairo_pie.metadata;
+        assert_eq!(metadata.program.main, program.main.unwrap());
+        assert_eq!(metadata.program.builtins, program.builtins);
+        assert_eq!(
+            metadata.program_segment,
+            SegmentInfo {
+                index: 0,
+                size: program.data.len()
+            }
+        );
+        assert_eq!(metadata.execution_segment.index, 1);
+        assert_eq!(
+            metadata.builtin_segments.get(OUTPUT_BUILTIN_NAME),
+            Some(&SegmentInfo { index: 2, size: 1 })
+        );
+        assert_eq!(
+            metadata.builtin_segments.get(BITWISE_BUILTIN_NAME),
+            Some(&SegmentInfo { index: 3, size: 5 })
+        );
+        assert_eq!(metadata.ret_fp_segment, SegmentInfo { index: 4, size: 0 });
+        assert_eq!(metadata.ret_pc_segment, SegmentInfo { index: 5, size: 0 });
+        assert!(metadata.extra_segments.is_empty());
+        assert_eq!(
+            cairo_pie.additional_data.get(BITWISE_BUILTIN_NAME),
+            Some(&BuiltinAdditionalData::None)
+        );
+        assert_eq!(
+            cairo_pie.execution_resources,
+            runner.get_execution_resources(&vm).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_cairo_pie_execution_resources_error() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.read_return_values(&mut vm).unwrap();
+        // The execution segment now looks smaller than the amount of accessed addresses in it
+        vm.segments.segment_sizes.insert(1, 1);
+
+        assert_matches!(
+            runner.get_cairo_pie(&vm),
+            Err(RunnerError::Trace(TraceError::MemoryError(
+                MemoryError::SegmentHasMoreAccessedAddressesThanSize(1, _, 1)
+            )))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn get_cairo_pie_write_zip() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.read_return_values(&mut vm).unwrap();
+
+        let cairo_pie = runner.get_cairo_pie(&vm).unwrap();
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        cairo_pie.write_zip(&mut buffer).unwrap();
+
+        let mut archive = zip::ZipArchive::new(buffer).unwrap();
+        let metadata: serde_json::Value =
+            serde_json::from_reader(archive.by_name("metadata.json").unwrap()).unwrap();
+        assert_eq!(metadata["program_segment"]["index"], 0);
+        assert_eq!(metadata["ret_fp_segment"]["index"], 4);
+        let version: serde_json::Value =
+            serde_json::from_reader(archive.by_name("version.json").unwrap()).unwrap();
+        assert_eq!(version["cairo_pie"], CAIRO_PIE_VERSION);
+        let memory_len = archive.by_name("memory.bin").unwrap().size() as usize;
+        assert_eq!(memory_len, cairo_pie.memory.to_bytes().len());
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
 
@@ -4699,4 +6291,905 @@ mod tests {
         let rsc = exec.filter_unused_builtins();
         assert_eq!(rsc.builtin_instance_counter.len(), 4);
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn multiple_hints_at_same_pc_run_in_declared_order() {
+        fn set_a(
+            _vm: &mut VirtualMachine,
+            exec_scopes: &mut ExecutionScopes,
+            _ids_data: &HashMap<String, HintReference>,
+            _ap_tracking: &ApTracking,
+            _constants: &HashMap<String, Felt>,
+        ) -> Result<(), HintError> {
+            exec_scopes.insert_value("a", Felt::new(17));
+            Ok(())
+        }
+
+        fn write_a(
+            vm: &mut VirtualMachine,
+            exec_scopes: &mut ExecutionScopes,
+            _ids_data: &HashMap<String, HintReference>,
+            _ap_tracking: &ApTracking,
+            _constants: &HashMap<String, Felt>,
+        ) -> Result<(), HintError> {
+            let a: Felt = exec_scopes.get("a")?;
+            vm.insert_value(vm.get_ap(), a)?;
+            Ok(())
+        }
+
+        let hint = |code: &str| HintParams {
+            code: code.to_string(),
+            accessible_scopes: Vec::new(),
+            flow_tracking_data: FlowTrackingData {
+                ap_tracking: ApTracking::default(),
+                reference_ids: HashMap::new(),
+            },
+        };
+        let program = program!(hints = HashMap::from([(0, vec![hint("set_a"), hint("write_a")])]));
+        let cairo_runner = cairo_runner!(program);
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        hint_processor.add_hint(String::from("set_a"), Arc::new(HintFunc(Box::new(set_a))));
+        hint_processor.add_hint(
+            String::from("write_a"),
+            Arc::new(HintFunc(Box::new(write_a))),
+        );
+
+        let hint_data_dictionary = cairo_runner
+            .get_hint_data_dictionary(&HashMap::new(), &mut hint_processor)
+            .unwrap();
+        let codes: Vec<&str> = hint_data_dictionary[&0]
+            .iter()
+            .map(|hint_data| {
+                hint_data
+                    .downcast_ref::<HintProcessorData>()
+                    .unwrap()
+                    .code
+                    .as_str()
+            })
+            .collect();
+        assert_eq!(codes, ["set_a", "write_a"]);
+
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+        run_context!(vm, 0, 0, 0);
+        assert_matches!(
+            vm.step_hint(
+                &mut hint_processor,
+                exec_scopes_ref!(),
+                &hint_data_dictionary,
+                &HashMap::new()
+            ),
+            Ok(())
+        );
+        check_memory!(vm.segments.memory, ((1, 0), 17));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn hint_constant_override() {
+        fn write_multiple(
+            vm: &mut VirtualMachine,
+            _exec_scopes: &mut ExecutionScopes,
+            _ids_data: &HashMap<String, HintReference>,
+            _ap_tracking: &ApTracking,
+            constants: &HashMap<String, Felt>,
+        ) -> Result<(), HintError> {
+            let multiplier = constants
+                .get("__main__.MULTIPLIER")
+                .ok_or(HintError::MissingConstant("MULTIPLIER"))?;
+            vm.insert_value(vm.get_ap(), multiplier * &Felt::new(10))?;
+            Ok(())
+        }
+
+        let program = program!(
+            // ret
+            data = vec_data!((2345108766317314046_i64)),
+            main = Some(0),
+            constants = HashMap::from([("__main__.MULTIPLIER".to_string(), Felt::new(2))]),
+            hints = HashMap::from([(
+                0,
+                vec![HintParams {
+                    code: "write_multiple".to_string(),
+                    accessible_scopes: Vec::new(),
+                    flow_tracking_data: FlowTrackingData {
+                        ap_tracking: ApTracking::default(),
+                        reference_ids: HashMap::new(),
+                    },
+                }]
+            )]),
+        );
+        let run = |hint_processor: &mut BuiltinHintProcessor| {
+            let mut runner = cairo_runner!(program);
+            let mut vm = vm!();
+            let end = runner.initialize(&mut vm).unwrap();
+            runner.run_until_pc(end, &mut vm, hint_processor).unwrap();
+            vm.get_integer(Relocatable::from((1, 2)))
+                .unwrap()
+                .into_owned()
+        };
+
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        hint_processor.add_hint(
+            String::from("write_multiple"),
+            Arc::new(HintFunc(Box::new(write_multiple))),
+        );
+        assert_eq!(run(&mut hint_processor), Felt::new(20));
+
+        // The override takes precedence over the program constant
+        hint_processor.add_constant_override("__main__.MULTIPLIER".to_string(), Felt::new(3));
+        assert_eq!(run(&mut hint_processor), Felt::new(30));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_air_public_input_assigns_public_memory_pages() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/proof_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program, "all", true);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.read_return_values(&mut vm).unwrap();
+        match &mut vm.builtin_runners[0] {
+            (_, BuiltinRunner::Output(output)) => {
+                let page_start = Relocatable::from((output.base() as isize, 0));
+                output.add_page(1, page_start, 1).unwrap()
+            }
+            _ => unreachable!("the output builtin comes first"),
+        }
+        runner.finalize_segments(&mut vm).unwrap();
+        runner.relocate(&mut vm, true).unwrap();
+
+        let public_input = runner.get_air_public_input(&vm).unwrap();
+        assert_eq!(public_input.layout, "all");
+        assert_eq!(public_input.n_steps, vm.trace.as_ref().unwrap().len());
+
+        let pages = public_input.public_memory_pages();
+        assert_eq!(pages.len(), 2);
+        // The program page holds the bytecode, in order, at the start of the relocated memory
+        let program_base = public_input
+            .memory_segments
+            .get("program")
+            .unwrap()
+            .begin_addr;
+        for (i, word) in program.data.iter().enumerate() {
+            let entry = pages[&0][i];
+            assert_eq!(entry.address, program_base + i);
+            assert_eq!(Some(&entry.value), word.get_int_ref());
+        }
+        // The only output cell lands in its own page
+        let output_begin = public_input
+            .memory_segments
+            .get(OUTPUT_BUILTIN_NAME)
+            .unwrap()
+            .begin_addr;
+        assert_eq!(
+            pages[&1],
+            vec![&PublicMemoryEntry {
+                address: output_begin,
+                value: Felt::zero(),
+                page: 1,
+            }]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_air_public_input_all_layout_segments() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/proof_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program, "all", true);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.read_return_values(&mut vm).unwrap();
+        runner.finalize_segments(&mut vm).unwrap();
+        runner.relocate(&mut vm, true).unwrap();
+
+        // range_check96 is only added to programs that declare it
+        let public_input = runner.get_air_public_input(&vm).unwrap();
+        let segment_names: Vec<&str> = public_input
+            .memory_segments
+            .0
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(
+            segment_names,
+            vec![
+                "program",
+                "execution",
+                OUTPUT_BUILTIN_NAME,
+                HASH_BUILTIN_NAME,
+                RANGE_CHECK_BUILTIN_NAME,
+                SIGNATURE_BUILTIN_NAME,
+                BITWISE_BUILTIN_NAME,
+                EC_OP_BUILTIN_NAME,
+                KECCAK_BUILTIN_NAME,
+                POSEIDON_BUILTIN_NAME,
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_public_memory_pages_program_page_holds_bytecode() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/proof_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program, "all", true);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.read_return_values(&mut vm).unwrap();
+        runner.finalize_segments(&mut vm).unwrap();
+        let relocation_table = vm.segments.relocate_segments().unwrap();
+
+        let pages = vm
+            .segments
+            .get_public_memory_pages(&relocation_table)
+            .unwrap();
+        assert_eq!(pages.len(), 1);
+        let program_base = relocation_table[0];
+        for (i, word) in program.data.iter().enumerate() {
+            let (address, value) = &pages[0][i];
+            assert_eq!(address, &Felt::from(program_base + i));
+            assert_eq!(Some(value), word.get_int_ref());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn end_run_proof_mode_pads_trace_and_builtin_segments() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/proof_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program, "all", true);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        let steps_to_end = vm.get_current_step();
+        runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+
+        // The padding steps run the `__end__` loop
+        assert_eq!(vm.get_pc(), end);
+        let steps = vm.get_current_step();
+        assert!(steps >= steps_to_end);
+        assert!(steps.is_power_of_two());
+        assert_eq!(vm.get_trace().unwrap().len(), steps);
+
+        // Every builtin segment fits in the cells allocated for the padded trace
+        assert!(runner.check_used_cells(&vm).is_ok());
+        for (name, builtin) in vm.get_builtin_runners() {
+            let (used, allocated) = builtin.get_used_cells_and_allocated_size(&vm).unwrap();
+            assert!(used <= allocated, "{name}");
+            if *name == BITWISE_BUILTIN_NAME {
+                let ratio = builtin.ratio().unwrap() as usize;
+                assert_eq!(steps % ratio, 0);
+                assert_eq!(allocated, steps / ratio * 5);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_air_public_input_trace_not_relocated() {
+        let program = program!();
+        let runner = cairo_runner!(program);
+        let vm = vm!();
+        assert_matches!(
+            runner.get_air_public_input(&vm),
+            Err(PublicInputError::TraceNotRelocated)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_binary_trace_pedersen() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.relocate(&mut vm, true).unwrap();
+
+        let mut buffer = Vec::new();
+        runner.write_binary_trace(&mut buffer).unwrap();
+
+        // (ap, fp, pc) of each step, as traced by the Python VM
+        let registers: [(u64, u64, u64); 14] = [
+            (25, 25, 7),
+            (26, 25, 8),
+            (27, 25, 10),
+            (28, 25, 12),
+            (30, 30, 1),
+            (30, 30, 2),
+            (30, 30, 3),
+            (31, 30, 5),
+            (32, 30, 6),
+            (32, 25, 14),
+            (32, 25, 15),
+            (33, 25, 17),
+            (34, 25, 18),
+            (35, 25, 19),
+        ];
+        let mut expected = Vec::new();
+        for (ap, fp, pc) in registers {
+            expected.extend_from_slice(&ap.to_le_bytes());
+            expected.extend_from_slice(&fp.to_le_bytes());
+            expected.extend_from_slice(&pc.to_le_bytes());
+        }
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_binary_trace_not_relocated() {
+        let program = program!();
+        let runner = cairo_runner!(program);
+        let mut buffer = Vec::new();
+        assert_eq!(
+            runner.write_binary_trace(&mut buffer),
+            Err(TraceError::TraceNotRelocated)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_binary_trace_disabled() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.relocate(&mut vm, true).unwrap();
+
+        let mut buffer = Vec::new();
+        assert_eq!(
+            runner.write_binary_trace(&mut buffer),
+            Err(TraceError::TraceNotRelocated)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_binary_trace_write_error() {
+        let program = program!();
+        let mut runner = cairo_runner!(program);
+        runner.relocated_trace = Some(vec![RelocatedTraceEntry {
+            pc: 1,
+            ap: 2,
+            fp: 2,
+        }]);
+        assert_matches!(
+            runner.write_binary_trace(&mut FailingWriter),
+            Err(TraceError::Write(message)) if message.contains("disk full")
+        );
+        assert_matches!(
+            runner.write_binary_trace_v2(&mut FailingWriter),
+            Err(TraceError::Write(message)) if message.contains("disk full")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_binary_trace_v2_round_trip() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.relocate(&mut vm, true).unwrap();
+
+        let mut buffer = Vec::new();
+        runner.write_binary_trace_v2(&mut buffer).unwrap();
+
+        let (header, entries) = buffer.split_at(16);
+        assert_eq!(header[..4], TRACE_MAGIC);
+        assert_eq!(
+            u32::from_le_bytes(header[4..8].try_into().unwrap()),
+            TRACE_FORMAT_VERSION
+        );
+        let n_entries = u64::from_le_bytes(header[8..].try_into().unwrap()) as usize;
+        assert_eq!(entries.len(), n_entries * 3 * 8);
+        let trace: Vec<RelocatedTraceEntry> = entries
+            .chunks_exact(3 * 8)
+            .map(|entry| {
+                let register =
+                    |i: usize| u64::from_le_bytes(entry[i * 8..(i + 1) * 8].try_into().unwrap());
+                RelocatedTraceEntry {
+                    ap: register(0) as usize,
+                    fp: register(1) as usize,
+                    pc: register(2) as usize,
+                }
+            })
+            .collect();
+        assert_eq!(Some(trace), runner.relocated_trace);
+
+        // The entries are laid out as in the headerless format
+        let mut headerless = Vec::new();
+        runner.write_binary_trace(&mut headerless).unwrap();
+        assert_eq!(entries, headerless);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_binary_trace_v2_not_relocated() {
+        let program = program!();
+        let runner = cairo_runner!(program);
+        let mut buffer = Vec::new();
+        assert_eq!(
+            runner.write_binary_trace_v2(&mut buffer),
+            Err(TraceError::TraceNotRelocated)
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_binary_memory_round_trip() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/struct.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.relocate(&mut vm, true).unwrap();
+
+        let mut buffer = Vec::new();
+        runner.write_binary_memory(&mut buffer).unwrap();
+        // Matches the memory file written by the Python VM
+        assert_eq!(
+            buffer,
+            include_bytes!("../../../cairo_programs/trace_memory/cairo_memory_struct")
+        );
+
+        assert_eq!(buffer.len() % (8 + FELT_BYTES), 0);
+        let cells: Vec<(usize, Felt)> = buffer
+            .chunks_exact(8 + FELT_BYTES)
+            .map(|cell| {
+                let address = u64::from_le_bytes(cell[..8].try_into().unwrap()) as usize;
+                let mut value = cell[8..].to_vec();
+                value.reverse();
+                (address, Felt::from_bytes_be(&value))
+            })
+            .collect();
+        let expected: Vec<(usize, Felt)> = runner
+            .relocated_memory
+            .iter()
+            .enumerate()
+            .filter_map(|(address, value)| Some((address, value.clone()?)))
+            .collect();
+        assert_eq!(cells, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_binary_memory_not_relocated() {
+        let program = program!();
+        let runner = cairo_runner!(program);
+        let mut buffer = Vec::new();
+        assert_matches!(
+            runner.write_binary_memory(&mut buffer),
+            Err(MemoryError::MemoryNotRelocated)
+        );
+        assert!(buffer.is_empty());
+    }
+
+    /// A writer failing on every write, as a full disk would
+    #[cfg(feature = "std")]
+    struct FailingWriter;
+
+    #[cfg(feature = "std")]
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "disk full"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_binary_memory_write_error() {
+        let program = program!();
+        let mut runner = cairo_runner!(program);
+        runner.relocated_memory = vec![None, Some(Felt::new(7))];
+        assert_matches!(
+            runner.write_binary_memory(&mut FailingWriter),
+            Err(MemoryError::Write(message)) if message.contains("disk full")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn air_inputs_json_matches_python_vm_format() {
+        let public_input_json =
+            include_str!("../../../cairo_programs/air_inputs/air_public_input.json");
+        let public_input: PublicInput = serde_json::from_str(public_input_json).unwrap();
+        let mut buffer = Vec::new();
+        public_input.write_json(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), public_input_json);
+
+        let private_input_json =
+            include_str!("../../../cairo_programs/air_inputs/air_private_input.json");
+        let private_input: AirPrivateInput = serde_json::from_str(private_input_json).unwrap();
+        let mut buffer = Vec::new();
+        private_input.write_json(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), private_input_json);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_air_inputs_round_trip() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/proof_programs/bitwise_output.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut runner = cairo_runner!(program, "all", true);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner
+            .end_run(false, false, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.read_return_values(&mut vm).unwrap();
+        runner.finalize_segments(&mut vm).unwrap();
+        runner.relocate(&mut vm, true).unwrap();
+
+        let mut buffer = Vec::new();
+        runner.write_air_public_input(&vm, &mut buffer).unwrap();
+        let public_input: PublicInput = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(public_input, runner.get_air_public_input(&vm).unwrap());
+        // Segments are listed in the program's builtin order, after the program and execution
+        let segment_names: Vec<&str> = public_input
+            .memory_segments
+            .0
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let builtin_names: Vec<&str> = vm.builtin_runners.iter().map(|(name, _)| *name).collect();
+        assert_eq!(segment_names[..2], ["program", "execution"]);
+        assert_eq!(segment_names[2..], builtin_names);
+
+        let mut buffer = Vec::new();
+        runner
+            .write_air_private_input(&vm, "trace.bin", "memory.bin", &mut buffer)
+            .unwrap();
+        let private_input: AirPrivateInput = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(
+            private_input,
+            runner.get_air_private_input(&vm, "trace.bin", "memory.bin")
+        );
+        assert!(private_input
+            .builtins
+            .iter()
+            .all(|(name, _)| name != OUTPUT_BUILTIN_NAME));
+        // bitwise_and(1, 2) uses a single bitwise instance
+        assert_eq!(
+            private_input
+                .builtins
+                .iter()
+                .find(|(name, _)| name == BITWISE_BUILTIN_NAME)
+                .map(|(_, inputs)| inputs),
+            Some(&vec![PrivateInput::Pair(PrivateInputPair {
+                index: 0,
+                x: Felt::one(),
+                y: Felt::new(2),
+            })])
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_run_result_starknet_ok() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/panic_result.json"),
+            None,
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let entrypoint = program
+            .identifiers
+            .get("__main__.checked_div_rem")
+            .unwrap()
+            .pc
+            .unwrap();
+
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+        cairo_runner
+            .run_from_entrypoint(
+                entrypoint,
+                &[
+                    &MaybeRelocatable::from((2, 0)).into(), //range_check_ptr
+                    &mayberelocatable!(1000).into(),        //gas
+                    &mayberelocatable!(17).into(),
+                    &mayberelocatable!(5).into(),
+                ],
+                true,
+                &mut vm,
+                &mut hint_processor,
+            )
+            .unwrap();
+        // Each call withdraws 10 gas units, whether it panics or not
+        assert_eq!(
+            cairo_runner.get_run_result_starknet(&vm),
+            Ok(RunResultStarknet {
+                gas_counter: Felt::new(990),
+                result: Ok(vec![Felt::new(3), Felt::new(2)]),
+            })
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_run_result_starknet_panic() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/panic_result.json"),
+            None,
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let entrypoint = program
+            .identifiers
+            .get("__main__.checked_div_rem")
+            .unwrap()
+            .pc
+            .unwrap();
+
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+        cairo_runner
+            .run_from_entrypoint(
+                entrypoint,
+                &[
+                    &MaybeRelocatable::from((2, 0)).into(), //range_check_ptr
+                    &mayberelocatable!(1000).into(),        //gas
+                    &mayberelocatable!(17).into(),
+                    &mayberelocatable!(0).into(),
+                ],
+                true,
+                &mut vm,
+                &mut hint_processor,
+            )
+            .unwrap();
+        // Each call withdraws 10 gas units, whether it panics or not
+        assert_eq!(
+            cairo_runner.get_run_result_starknet(&vm),
+            Ok(RunResultStarknet {
+                gas_counter: Felt::new(990),
+                // 'Division by zero'
+                result: Err(vec![Felt::new(0x4469766973696f6e206279207a65726f_u128)]),
+            })
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_run_result_starknet_invalid_variant() {
+        let program = program!();
+        let runner = cairo_runner!(program);
+        let mut vm = vm!();
+        vm.segments = segments![((1, 0), 7), ((1, 1), 2), ((1, 2), (2, 0)), ((1, 3), (2, 0))];
+        run_context!(vm, 0, 4, 4);
+        assert_eq!(
+            runner.get_run_result_starknet(&vm),
+            Err(RunnerError::InvalidPanicResultVariant(Felt::new(2)))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_from_entrypoint_with_gas_enough_gas() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/gas_withdrawal.json"),
+            None,
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let entrypoint = program
+            .identifiers
+            .get("__main__.sum_with_gas")
+            .unwrap()
+            .pc
+            .unwrap();
+
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+        // Summing [1, 5] withdraws 100 gas units per iteration
+        assert_eq!(
+            cairo_runner
+                .run_from_entrypoint_with_gas(
+                    entrypoint,
+                    &[
+                        &MaybeRelocatable::from((2, 0)).into(), //range_check_ptr
+                        &mayberelocatable!(5).into(),
+                    ],
+                    1000,
+                    true,
+                    &mut vm,
+                    &mut hint_processor,
+                )
+                .unwrap(),
+            RunResultStarknet {
+                gas_counter: Felt::new(500),
+                result: Ok(vec![Felt::new(15)]),
+            }
+        );
+        assert_eq!(vm.get_remaining_gas(), Some(500));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn run_from_entrypoint_with_gas_out_of_gas() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/gas_withdrawal.json"),
+            None,
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let entrypoint = program
+            .identifiers
+            .get("__main__.sum_with_gas")
+            .unwrap()
+            .pc
+            .unwrap();
+
+        cairo_runner.initialize_builtins(&mut vm).unwrap();
+        cairo_runner.initialize_segments(&mut vm, None);
+        // Only two of the five iterations can be paid for
+        assert_matches!(
+            cairo_runner.run_from_entrypoint_with_gas(
+                entrypoint,
+                &[
+                    &MaybeRelocatable::from((2, 0)).into(), //range_check_ptr
+                    &mayberelocatable!(5).into(),
+                ],
+                250,
+                true,
+                &mut vm,
+                &mut hint_processor,
+            ),
+            Err(CairoRunError::VirtualMachine(
+                VirtualMachineError::OutOfGas(50)
+            ))
+        );
+        // The panic data is still available in the returned values
+        assert_eq!(
+            cairo_runner.get_run_result_starknet(&vm),
+            Ok(RunResultStarknet {
+                gas_counter: Felt::new(50),
+                result: Err(vec![Felt::new(OUT_OF_GAS_PANIC)]),
+            })
+        );
+        assert_eq!(vm.get_remaining_gas(), Some(50));
+    }
+
+    #[test]
+    #[cfg(feature = "hint_coverage")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_executed_hints_unsafe_keccak() {
+        use crate::hint_processor::builtin_hint_processor::hint_code;
+
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/unsafe_keccak.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        assert!(cairo_runner.get_executed_hints().is_empty());
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+
+        let executed_hints = cairo_runner.get_executed_hints();
+        assert!(executed_hints.contains(hint_code::ADD_SEGMENT));
+        assert!(executed_hints.contains(hint_code::UNSAFE_KECCAK));
+        assert!(!executed_hints.contains(hint_code::UNSAFE_KECCAK_FINALIZE));
+        // Only the hints of the program are recorded
+        let program_hints: HashSet<_> = program
+            .hints
+            .values()
+            .flatten()
+            .map(|hint| hint.code.clone())
+            .collect();
+        assert!(executed_hints.is_subset(&program_hints));
+    }
+
+    #[test]
+    #[cfg(feature = "hint_coverage")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_executed_hints_failed_step() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/bad_programs/bad_dict_new.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        // The dict_new hint fails, as there is no initial dict
+        assert!(cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .is_err());
+        assert!(cairo_runner.get_executed_hints().is_empty());
+    }
 }
@@ -61,9 +61,10 @@ pub fn verify_secure_runner(
     // This means that every temporary address has been properly relocated to a real address
     // Asumption: If temporary memory is empty, this means no temporary memory addresses were generated and all addresses in memory are real
     if !vm.segments.memory.temp_data.is_empty() {
-        for value in vm.segments.memory.data.iter().flatten() {
-            match value.as_ref().map(|x| x.get_value()) {
-                Some(MaybeRelocatable::RelocatableValue(addr)) if addr.segment_index < 0 => {
+        let memory = &vm.segments.memory;
+        for (_, cell) in (0..memory.data.len()).flat_map(|index| memory.segment_cells(index)) {
+            match cell.get_value() {
+                MaybeRelocatable::RelocatableValue(addr) if addr.segment_index < 0 => {
                     return Err(VirtualMachineError::InvalidMemoryValueTemporaryAddress(
                         *addr,
                     ))
@@ -0,0 +1,191 @@
+//! Step sampling
+//!
+//! Make it possible to measure the step throughput of the VM on targets where no wall clock is
+//! available (e.g. `no_std` bare-metal targets).
+//!
+//! A sampling function registered with [set_step_sampler](VirtualMachine::set_step_sampler) is
+//! called with the VM's current step every `interval` executed instructions. Pairing each sample
+//! with a reading of a platform specific cycle counter is enough to build a custom benchmark:
+//!
+//! ```ignore
+//! let samples = Arc::new(Mutex::new(Vec::new()));
+//! let samples_ref = samples.clone();
+//! vm.set_step_sampler(1000, move |current_step| {
+//!     samples_ref
+//!         .lock()
+//!         .unwrap()
+//!         .push((current_step, read_cycle_counter()));
+//! });
+//! cairo_runner.run_until_pc(end, &mut vm, &mut hint_processor)?;
+//! // Each pair of consecutive samples gives the cycles spent on 1000 steps
+//! ```
+//!
+//! The sampling function has to be `Send`, like the [VirtualMachine] holding it. On `no_std`
+//! targets, where `Mutex` isn't available, the samples can be stored in atomics or a spin lock:
+//!
+//! ```ignore
+//! static LAST_STEP: AtomicUsize = AtomicUsize::new(0);
+//! static LAST_CYCLES: AtomicUsize = AtomicUsize::new(0);
+//! vm.set_step_sampler(1000, |current_step| {
+//!     LAST_STEP.store(current_step, Ordering::Relaxed);
+//!     LAST_CYCLES.store(read_cycle_counter(), Ordering::Relaxed);
+//! });
+//! ```
+//!
+//! The feature is part of `test_utils`, so its tests also run without `std` through
+//! `make test-no_std`.
+
+use crate::stdlib::prelude::*;
+
+use super::vm_core::VirtualMachine;
+
+pub(crate) struct StepSampler {
+    interval: usize,
+    sample: Box<dyn FnMut(usize) + Send>,
+}
+
+impl VirtualMachine {
+    /// Registers a function that will be called with the current step every `interval` steps.
+    /// An interval of 0 is handled as 1 (sampling every step).
+    pub fn set_step_sampler<F: FnMut(usize) + Send + 'static>(
+        &mut self,
+        interval: usize,
+        sample: F,
+    ) {
+        self.step_sampler = Some(StepSampler {
+            interval: interval.max(1),
+            sample: Box::new(sample),
+        });
+    }
+
+    /// Removes the registered sampling function, if any.
+    pub fn clear_step_sampler(&mut self) {
+        self.step_sampler = None;
+    }
+
+    pub(crate) fn sample_step(&mut self) {
+        if let Some(sampler) = self.step_sampler.as_mut() {
+            if self.current_step % sampler.interval == 0 {
+                (sampler.sample)(self.current_step);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::sync::Arc;
+    use crate::{
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        types::program::Program, utils::test_utils::*, vm::runners::cairo_runner::CairoRunner,
+    };
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    // Number, sum and last value of the samples. Atomics keep the sampler Send without a lock
+    #[derive(Default)]
+    struct Samples {
+        count: AtomicUsize,
+        sum: AtomicUsize,
+        last: AtomicUsize,
+    }
+
+    impl Samples {
+        fn record(&self, current_step: usize) {
+            self.count.fetch_add(1, Ordering::Relaxed);
+            self.sum.fetch_add(current_step, Ordering::Relaxed);
+            self.last.store(current_step, Ordering::Relaxed);
+        }
+
+        fn get(&self) -> (usize, usize, usize) {
+            (
+                self.count.load(Ordering::Relaxed),
+                self.sum.load(Ordering::Relaxed),
+                self.last.load(Ordering::Relaxed),
+            )
+        }
+    }
+
+    // Only relies on the step count, no timing API is used so this runs on no_std targets too
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn sample_steps_of_fibonacci_run() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+
+        let samples = Arc::new(Samples::default());
+        let samples_ref = samples.clone();
+        vm.set_step_sampler(10, move |current_step| samples_ref.record(current_step));
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+
+        let total_steps = vm.get_current_step();
+        assert!(total_steps >= 10);
+        let expected_samples: Vec<usize> = (1..=total_steps / 10).map(|i| i * 10).collect();
+        assert_eq!(
+            samples.get(),
+            (
+                expected_samples.len(),
+                expected_samples.iter().sum(),
+                *expected_samples.last().unwrap()
+            )
+        );
+    }
+
+    // Bare-metal style sampler: no allocation nor lock, the samples go to statics
+    #[test]
+    #[cfg(not(feature = "std"))]
+    fn sample_steps_of_fibonacci_run_no_std() {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        static LAST: AtomicUsize = AtomicUsize::new(0);
+
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+
+        vm.set_step_sampler(10, |current_step| {
+            COUNT.fetch_add(1, Ordering::Relaxed);
+            LAST.store(current_step, Ordering::Relaxed);
+        });
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut BuiltinHintProcessor::new_empty())
+            .unwrap();
+
+        let total_steps = vm.get_current_step();
+        assert!(total_steps >= 10);
+        assert_eq!(COUNT.load(Ordering::Relaxed), total_steps / 10);
+        assert_eq!(LAST.load(Ordering::Relaxed), total_steps / 10 * 10);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn zero_interval_samples_every_step() {
+        let mut vm = vm!();
+        let samples = Arc::new(Samples::default());
+        let samples_ref = samples.clone();
+        vm.set_step_sampler(0, move |current_step| samples_ref.record(current_step));
+        vm.current_step = 1;
+        vm.sample_step();
+        vm.current_step = 2;
+        vm.sample_step();
+        vm.clear_step_sampler();
+        vm.current_step = 3;
+        vm.sample_step();
+        assert_eq!(samples.get(), (2, 3, 2));
+    }
+}
@@ -9,6 +9,7 @@ use crate::types::relocatable::{MaybeRelocatable, Relocatable};
 use num_traits::ToPrimitive;
 
 pub mod trace_entry;
+pub mod verify;
 
 /// Return the minimum and maximum values in the perm_range_check component.
 pub fn get_perm_range_check_limits(
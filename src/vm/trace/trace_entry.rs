@@ -13,7 +13,7 @@ pub struct TraceEntry {
     pub fp: Relocatable,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RelocatedTraceEntry {
     pub ap: usize,
     pub fp: usize,
@@ -0,0 +1,377 @@
+//! Replays a relocated trace over a relocated memory, checking that every step is a valid
+//! execution of the instruction it points to. Hints are not run: the memory is expected to
+//! hold every value the execution wrote.
+
+use crate::stdlib::prelude::*;
+use felt::Felt;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::{
+    types::{
+        instruction::{ApUpdate, FpUpdate, Instruction, Op1Addr, Opcode, PcUpdate, Register, Res},
+        program::Program,
+        relocatable::MaybeRelocatable,
+    },
+    vm::{
+        decoding::decoder::decode_instruction, errors::trace_errors::VerifyError,
+        trace::trace_entry::RelocatedTraceEntry,
+    },
+};
+
+/// The program segment is always the first one, and relocated memory starts at address 1
+const PROGRAM_BASE: usize = 1;
+
+/// Verifies that `trace` is consistent with `program` over `memory`, both relocated:
+/// the program's bytecode must be loaded at the start of the memory, the first entry must hold
+/// the `initial` registers, the registers of each entry must follow from executing the
+/// instruction at the previous entry's pc, and the last instruction must lead to `end_pc`.
+pub fn verify_trace(
+    program: &Program,
+    memory: &[Option<Felt>],
+    trace: &[RelocatedTraceEntry],
+    initial: &RelocatedTraceEntry,
+    end_pc: usize,
+) -> Result<(), VerifyError> {
+    let first = trace.first().ok_or(VerifyError::EmptyTrace)?;
+    if first != initial {
+        return Err(VerifyError::InvalidInitialRegisters(Box::new((
+            initial.clone(),
+            first.clone(),
+        ))));
+    }
+    for (i, value) in program.data.iter().enumerate() {
+        let addr = PROGRAM_BASE + i;
+        match value {
+            MaybeRelocatable::Int(value) if get_cell(memory, addr)? == value => (),
+            _ => return Err(VerifyError::ProgramMismatch(addr)),
+        }
+    }
+    for (step, entry) in trace.iter().enumerate() {
+        let next = verify_step(step, entry, memory)?;
+        match trace.get(step + 1) {
+            Some(actual) if &next != actual => {
+                return Err(VerifyError::InvalidTransition(
+                    step,
+                    Box::new((next, actual.clone())),
+                ));
+            }
+            Some(_) => (),
+            None if next.pc != end_pc => {
+                return Err(VerifyError::EndPcNotReached(end_pc, next.pc));
+            }
+            None => (),
+        }
+    }
+    Ok(())
+}
+
+/// Checks the instruction executed at `entry`, and returns the registers it leads to
+fn verify_step(
+    step: usize,
+    entry: &RelocatedTraceEntry,
+    memory: &[Option<Felt>],
+) -> Result<RelocatedTraceEntry, VerifyError> {
+    let instruction = decode_at(step, entry.pc, memory)?;
+    let size = instruction.size();
+
+    let dst_addr = offset_addr(
+        step,
+        register(entry, &instruction.dst_register),
+        instruction.off0,
+    )?;
+    let dst = get_cell(memory, dst_addr)?;
+    let op0_addr = offset_addr(
+        step,
+        register(entry, &instruction.op0_register),
+        instruction.off1,
+    )?;
+    let op0 = get_cell(memory, op0_addr)?;
+    let op1_base = match instruction.op1_addr {
+        Op1Addr::Imm => entry.pc,
+        Op1Addr::AP => entry.ap,
+        Op1Addr::FP => entry.fp,
+        Op1Addr::Op0 => felt_to_addr(step, op0)?,
+    };
+    let op1 = get_cell(memory, offset_addr(step, op1_base, instruction.off2)?)?;
+
+    let res = match instruction.res {
+        Res::Op1 => Some(op1.clone()),
+        Res::Add => Some(op0 + op1),
+        Res::Mul => Some(op0 * op1),
+        Res::Unconstrained => None,
+    };
+
+    match instruction.opcode {
+        Opcode::AssertEq => {
+            let res = res.as_ref().ok_or(VerifyError::UnconstrainedRes(step))?;
+            if dst != res {
+                return Err(VerifyError::DiffAssertValues(
+                    step,
+                    dst.clone(),
+                    res.clone(),
+                ));
+            }
+        }
+        Opcode::Call => {
+            if *dst != Felt::from(entry.fp) {
+                return Err(VerifyError::CantWriteReturnFp(step));
+            }
+            if *op0 != Felt::from(entry.pc + size) {
+                return Err(VerifyError::CantWriteReturnPc(step));
+            }
+        }
+        Opcode::NOp | Opcode::Ret => (),
+    }
+
+    let pc = match instruction.pc_update {
+        PcUpdate::Regular => entry.pc + size,
+        PcUpdate::Jump => felt_to_addr(
+            step,
+            res.as_ref().ok_or(VerifyError::UnconstrainedRes(step))?,
+        )?,
+        PcUpdate::JumpRel => {
+            let res = res.as_ref().ok_or(VerifyError::UnconstrainedRes(step))?;
+            felt_to_addr(step, &(Felt::from(entry.pc) + res))?
+        }
+        PcUpdate::Jnz if dst.is_zero() => entry.pc + size,
+        PcUpdate::Jnz => felt_to_addr(step, &(Felt::from(entry.pc) + op1))?,
+    };
+    let ap = match instruction.ap_update {
+        ApUpdate::Regular => entry.ap,
+        ApUpdate::Add => {
+            let res = res.as_ref().ok_or(VerifyError::UnconstrainedRes(step))?;
+            felt_to_addr(step, &(Felt::from(entry.ap) + res))?
+        }
+        ApUpdate::Add1 => entry.ap + 1,
+        ApUpdate::Add2 => entry.ap + 2,
+    };
+    let fp = match instruction.fp_update {
+        FpUpdate::Regular => entry.fp,
+        FpUpdate::APPlus2 => entry.ap + 2,
+        FpUpdate::Dst => felt_to_addr(step, dst)?,
+    };
+    Ok(RelocatedTraceEntry { ap, fp, pc })
+}
+
+fn decode_at(step: usize, pc: usize, memory: &[Option<Felt>]) -> Result<Instruction, VerifyError> {
+    let encoded = get_cell(memory, pc)?
+        .to_i64()
+        .ok_or(VerifyError::InvalidInstruction(step, pc))?;
+    let imm = memory.get(pc + 1).and_then(Option::as_ref);
+    decode_instruction(encoded, imm).map_err(|_| VerifyError::InvalidInstruction(step, pc))
+}
+
+fn register(entry: &RelocatedTraceEntry, register: &Register) -> usize {
+    match register {
+        Register::AP => entry.ap,
+        Register::FP => entry.fp,
+    }
+}
+
+fn offset_addr(step: usize, base: usize, offset: isize) -> Result<usize, VerifyError> {
+    base.checked_add_signed(offset)
+        .ok_or(VerifyError::InvalidAddress(step))
+}
+
+fn felt_to_addr(step: usize, value: &Felt) -> Result<usize, VerifyError> {
+    value.to_usize().ok_or(VerifyError::InvalidAddress(step))
+}
+
+fn get_cell(memory: &[Option<Felt>], addr: usize) -> Result<&Felt, VerifyError> {
+    memory
+        .get(addr)
+        .and_then(Option::as_ref)
+        .ok_or(VerifyError::UnknownMemoryCell(addr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+        types::relocatable::{relocate_address, Relocatable},
+        utils::test_utils::*,
+        vm::runners::cairo_runner::CairoRunner,
+        vm::vm_core::VirtualMachine,
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    // Runs the program, returning the relocated runner along with its initial registers and end pc
+    fn run_relocated(program: &Program) -> (CairoRunner, RelocatedTraceEntry, usize) {
+        let mut runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        let end = runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+        runner.relocate(&mut vm, true).unwrap();
+
+        let relocation_table = vm.segments.relocate_segments().unwrap();
+        let relocate =
+            |addr: Option<Relocatable>| relocate_address(addr.unwrap(), &relocation_table).unwrap();
+        let initial = RelocatedTraceEntry {
+            ap: relocate(runner.initial_ap),
+            fp: relocate(runner.initial_fp),
+            pc: relocate(runner.initial_pc),
+        };
+        let end_pc = relocate(runner.final_pc);
+        (runner, initial, end_pc)
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn verify_valid_trace() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let (runner, initial, end_pc) = run_relocated(&program);
+        let trace = runner.relocated_trace.as_ref().unwrap();
+        assert_eq!(
+            verify_trace(&program, &runner.relocated_memory, trace, &initial, end_pc),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn verify_trace_with_corrupted_registers() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let (runner, initial, end_pc) = run_relocated(&program);
+        let mut trace = runner.relocated_trace.clone().unwrap();
+        trace[3].ap += 1;
+        assert_eq!(
+            verify_trace(&program, &runner.relocated_memory, &trace, &initial, end_pc),
+            Err(VerifyError::InvalidTransition(
+                2,
+                Box::new((
+                    RelocatedTraceEntry {
+                        ap: 28,
+                        fp: 25,
+                        pc: 12
+                    },
+                    RelocatedTraceEntry {
+                        ap: 29,
+                        fp: 25,
+                        pc: 12
+                    }
+                ))
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn verify_trace_with_corrupted_memory() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let (runner, initial, end_pc) = run_relocated(&program);
+        let trace = runner.relocated_trace.as_ref().unwrap();
+        // The first instruction of fibonacci's main is `[ap] = 1, ap++`
+        let mut memory = runner.relocated_memory.clone();
+        let first_ap = trace[0].ap;
+        memory[first_ap] = Some(Felt::new(2));
+        assert_eq!(
+            verify_trace(&program, &memory, trace, &initial, end_pc),
+            Err(VerifyError::DiffAssertValues(0, Felt::new(2), Felt::new(1)))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn verify_trace_with_wrong_program() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let (runner, initial, end_pc) = run_relocated(&program);
+        let trace = runner.relocated_trace.as_ref().unwrap();
+        let mut memory = runner.relocated_memory.clone();
+        memory[PROGRAM_BASE] = Some(Felt::zero());
+        assert_eq!(
+            verify_trace(&program, &memory, trace, &initial, end_pc),
+            Err(VerifyError::ProgramMismatch(PROGRAM_BASE))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn verify_trace_with_wrong_initial_registers() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let (runner, initial, end_pc) = run_relocated(&program);
+        let trace = runner.relocated_trace.as_ref().unwrap();
+        // A trace starting mid-run is consistent, but doesn't start at the initial registers
+        assert_eq!(
+            verify_trace(
+                &program,
+                &runner.relocated_memory,
+                &trace[1..],
+                &initial,
+                end_pc
+            ),
+            Err(VerifyError::InvalidInitialRegisters(Box::new((
+                initial,
+                trace[1].clone()
+            ))))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn verify_truncated_trace() {
+        let program = Program::from_bytes(
+            include_bytes!("../../../cairo_programs/pedersen_test.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let (runner, initial, end_pc) = run_relocated(&program);
+        let trace = runner.relocated_trace.as_ref().unwrap();
+        let last = trace.len() - 1;
+        assert_eq!(
+            verify_trace(
+                &program,
+                &runner.relocated_memory,
+                &trace[..last],
+                &initial,
+                end_pc
+            ),
+            Err(VerifyError::EndPcNotReached(end_pc, trace[last].pc))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn verify_empty_trace() {
+        let program = program!();
+        assert_eq!(
+            verify_trace(
+                &program,
+                &[],
+                &[],
+                &RelocatedTraceEntry {
+                    ap: 0,
+                    fp: 0,
+                    pc: 0
+                },
+                0
+            ),
+            Err(VerifyError::EmptyTrace)
+        );
+    }
+}
@@ -15,15 +15,17 @@ use crate::{
         context::run_context::RunContext,
         decoding::decoder::decode_instruction,
         errors::{
-            exec_scope_errors::ExecScopeError, memory_errors::MemoryError,
-            vm_errors::VirtualMachineError,
+            exec_scope_errors::ExecScopeError, hint_errors::HintError, memory_errors::MemoryError,
+            runner_errors::RunnerError, vm_errors::VirtualMachineError,
         },
         runners::builtin_runner::{BuiltinRunner, RangeCheckBuiltinRunner, SignatureBuiltinRunner},
         trace::trace_entry::TraceEntry,
-        vm_memory::memory_segments::MemorySegmentManager,
+        vm_memory::{memory::Memory, memory_segments::MemorySegmentManager},
     },
 };
 
+#[cfg(feature = "memory_access_log")]
+use crate::{stdlib::cell::Ref, vm::vm_memory::memory::MemoryAccess};
 use felt::Felt;
 use num_traits::{ToPrimitive, Zero};
 
@@ -87,10 +89,30 @@ pub struct VirtualMachine {
     pub(crate) segments: MemorySegmentManager,
     pub(crate) trace: Option<Vec<TraceEntry>>,
     pub(crate) current_step: usize,
-    skip_instruction_execution: bool,
+    pub(crate) skip_instruction_execution: bool,
     run_finished: bool,
+    // Decoded instructions, indexed by pc segment and offset.
+    // Memory cells can't be overwritten, so entries never need to be invalidated
+    instruction_cache: Option<Vec<Vec<Option<Instruction>>>>,
+    // Number of instructions decoded from memory, to check the cache is effective
+    #[cfg(test)]
+    decoded_instructions: usize,
+    // Gas left for the hints to consume, None if gas isn't tracked
+    remaining_gas: Option<usize>,
     #[cfg(feature = "hooks")]
     pub(crate) hooks: crate::vm::hooks::Hooks,
+    #[cfg(feature = "step_sampling")]
+    pub(crate) step_sampler: Option<crate::vm::step_sampling::StepSampler>,
+    #[cfg(feature = "deduction_profiling")]
+    pub(crate) deduction_profile: crate::stdlib::cell::RefCell<
+        HashMap<&'static str, crate::vm::deduction_profiling::DeductionStats>,
+    >,
+    // Recorded witness, None unless recording was enabled
+    #[cfg(feature = "hint_witness")]
+    pub(crate) hint_witness: Option<crate::vm::hint_witness::HintWitness>,
+    // Witness being replayed, along with the number of entries replayed so far
+    #[cfg(feature = "hint_witness")]
+    pub(crate) hint_witness_replay: Option<(crate::vm::hint_witness::HintWitness, usize)>,
 }
 
 impl HintData {
@@ -129,8 +151,20 @@ impl VirtualMachine {
             skip_instruction_execution: false,
             segments: MemorySegmentManager::new(),
             run_finished: false,
+            instruction_cache: Some(Vec::new()),
+            #[cfg(test)]
+            decoded_instructions: 0,
+            remaining_gas: None,
             #[cfg(feature = "hooks")]
             hooks: Default::default(),
+            #[cfg(feature = "step_sampling")]
+            step_sampler: None,
+            #[cfg(feature = "deduction_profiling")]
+            deduction_profile: Default::default(),
+            #[cfg(feature = "hint_witness")]
+            hint_witness: None,
+            #[cfg(feature = "hint_witness")]
+            hint_witness_replay: None,
         }
     }
 
@@ -298,13 +332,32 @@ impl VirtualMachine {
         Ok((None, None))
     }
 
+    #[cfg(not(feature = "deduction_profiling"))]
+    fn builtin_deduce_memory_cell(
+        &self,
+        builtin: &BuiltinRunner,
+        address: Relocatable,
+    ) -> Result<Option<MaybeRelocatable>, RunnerError> {
+        builtin.deduce_memory_cell(address, &self.segments.memory)
+    }
+
+    /// Records the call, see [deduction_profiling](crate::vm::deduction_profiling)
+    #[cfg(feature = "deduction_profiling")]
+    fn builtin_deduce_memory_cell(
+        &self,
+        builtin: &BuiltinRunner,
+        address: Relocatable,
+    ) -> Result<Option<MaybeRelocatable>, RunnerError> {
+        self.profile_deduction(builtin, address)
+    }
+
     fn deduce_memory_cell(
         &self,
         address: Relocatable,
     ) -> Result<Option<MaybeRelocatable>, VirtualMachineError> {
         for (_, builtin) in self.builtin_runners.iter() {
-            if builtin.base() as isize == address.segment_index {
-                match builtin.deduce_memory_cell(address, &self.segments.memory) {
+            if address.in_segment(builtin.base()) {
+                match self.builtin_deduce_memory_cell(builtin, address) {
                     Ok(maybe_reloc) => return Ok(maybe_reloc),
                     Err(error) => return Err(VirtualMachineError::RunnerError(error)),
                 };
@@ -464,17 +517,78 @@ impl VirtualMachine {
         constants: &HashMap<String, Felt>,
     ) -> Result<(), VirtualMachineError> {
         if let Some(hint_list) = hint_data_dictionary.get(&self.run_context.pc.offset) {
-            for (hint_index, hint_data) in hint_list.iter().enumerate() {
-                hint_executor
-                    .execute_hint(self, exec_scopes, hint_data, constants)
-                    .map_err(|err| VirtualMachineError::Hint(hint_index, Box::new(err)))?
-            }
+            #[cfg(feature = "hint_witness")]
+            self.witness_hints(hint_executor, exec_scopes, hint_list, constants)?;
+            #[cfg(not(feature = "hint_witness"))]
+            self.execute_hints(hint_executor, exec_scopes, hint_list, constants)?;
         }
         Ok(())
     }
 
-    pub fn step_instruction(&mut self) -> Result<(), VirtualMachineError> {
+    /// Runs the hints of a single pc, in order
+    pub(crate) fn execute_hints(
+        &mut self,
+        hint_executor: &mut dyn HintProcessor,
+        exec_scopes: &mut ExecutionScopes,
+        hint_list: &[Box<dyn Any>],
+        constants: &HashMap<String, Felt>,
+    ) -> Result<(), VirtualMachineError> {
+        for (hint_index, hint_data) in hint_list.iter().enumerate() {
+            hint_executor
+                .execute_hint(self, exec_scopes, hint_data, constants)
+                .map_err(|err| match err {
+                    // Running out of gas stops the run, the hint itself didn't fail
+                    HintError::Internal(VirtualMachineError::OutOfGas(gas_left)) => {
+                        VirtualMachineError::OutOfGas(gas_left)
+                    }
+                    err => VirtualMachineError::Hint(hint_index, Box::new(err)),
+                })?
+        }
+        Ok(())
+    }
+
+    /// Decodes the instruction at the current pc, going through the instruction cache when the pc
+    /// is in a real segment. Temporary segments are relocated at the end of the run, so their
+    /// instructions aren't cached.
+    fn fetch_instruction(&mut self) -> Result<Instruction, VirtualMachineError> {
+        let pc = self.run_context.pc;
+        if pc.segment_index < 0 {
+            #[cfg(test)]
+            {
+                self.decoded_instructions += 1;
+            }
+            return self.decode_current_instruction();
+        }
+        let segment_index = pc.segment_index as usize;
+        if let Some(instruction) = self
+            .instruction_cache
+            .as_ref()
+            .and_then(|cache| cache.get(segment_index))
+            .and_then(|segment| segment.get(pc.offset))
+            .and_then(Option::as_ref)
+        {
+            return Ok(instruction.clone());
+        }
+        #[cfg(test)]
+        {
+            self.decoded_instructions += 1;
+        }
         let instruction = self.decode_current_instruction()?;
+        if let Some(cache) = self.instruction_cache.as_mut() {
+            if cache.len() <= segment_index {
+                cache.resize(segment_index + 1, Vec::new());
+            }
+            let segment = &mut cache[segment_index];
+            if segment.len() <= pc.offset {
+                segment.resize(pc.offset + 1, None);
+            }
+            segment[pc.offset] = Some(instruction.clone());
+        }
+        Ok(instruction)
+    }
+
+    pub fn step_instruction(&mut self) -> Result<(), VirtualMachineError> {
+        let instruction = self.fetch_instruction()?;
         if !self.skip_instruction_execution {
             self.run_instruction(instruction)?;
         } else {
@@ -484,6 +598,9 @@ impl VirtualMachine {
         Ok(())
     }
 
+    /// Executes exactly one instruction, running the hints at the current pc first.
+    /// Registers, trace and current_step are updated just like in the runner's loops,
+    /// so this can be used to drive the execution one instruction at a time (e.g. from a debugger).
     pub fn step(
         &mut self,
         hint_executor: &mut dyn HintProcessor,
@@ -508,6 +625,8 @@ impl VirtualMachine {
             hint_data_dictionary,
             constants,
         )?;
+        #[cfg(feature = "step_sampling")]
+        self.sample_step();
 
         Ok(())
     }
@@ -581,15 +700,15 @@ impl VirtualMachine {
     ) -> Result<(Operands, OperandsAddresses, DeducedOperands), VirtualMachineError> {
         //Get operands from memory
         let dst_addr = self.run_context.compute_dst_addr(instruction)?;
-        let dst_op = self.segments.memory.get(&dst_addr).map(Cow::into_owned);
+        let dst_op = self.get_maybe(&dst_addr);
 
         let op0_addr = self.run_context.compute_op0_addr(instruction)?;
-        let op0_op = self.segments.memory.get(&op0_addr).map(Cow::into_owned);
+        let op0_op = self.get_maybe(&op0_addr);
 
         let op1_addr = self
             .run_context
             .compute_op1_addr(instruction, op0_op.as_ref())?;
-        let op1_op = self.segments.memory.get(&op1_addr).map(Cow::into_owned);
+        let op1_op = self.get_maybe(&op1_addr);
 
         let mut res: Option<MaybeRelocatable> = None;
 
@@ -642,20 +761,21 @@ impl VirtualMachine {
     pub fn verify_auto_deductions(&self) -> Result<(), VirtualMachineError> {
         for (name, builtin) in self.builtin_runners.iter() {
             let index: usize = builtin.base();
-            for (offset, value) in self.segments.memory.data[index].iter().enumerate() {
-                if let Some(deduced_memory_cell) = builtin
-                    .deduce_memory_cell(
+            // Walks both the dense and the sparse cells of the segment
+            for (offset, cell) in self.segments.memory.segment_cells(index) {
+                if let Some(deduced_memory_cell) = self
+                    .builtin_deduce_memory_cell(
+                        builtin,
                         Relocatable::from((index as isize, offset)),
-                        &self.segments.memory,
                     )
                     .map_err(VirtualMachineError::RunnerError)?
                 {
-                    let value = value.as_ref().map(|x| x.get_value());
-                    if Some(&deduced_memory_cell) != value && value.is_some() {
+                    let value = cell.get_value();
+                    if &deduced_memory_cell != value {
                         return Err(VirtualMachineError::InconsistentAutoDeduction(
                             name,
                             deduced_memory_cell,
-                            value.cloned(),
+                            Some(value.clone()),
                         ));
                     }
                 }
@@ -670,7 +790,7 @@ impl VirtualMachine {
         addr: Relocatable,
         builtin: &BuiltinRunner,
     ) -> Result<(), VirtualMachineError> {
-        let value = match builtin.deduce_memory_cell(addr, &self.segments.memory)? {
+        let value = match self.builtin_deduce_memory_cell(builtin, addr)? {
             Some(value) => value,
             None => return Ok(()),
         };
@@ -726,12 +846,14 @@ impl VirtualMachine {
                 Some(Ok(opt_pc)) => opt_pc,
                 _ => break,
             };
-            // Get fp traceback
+            // Get fp traceback, stopping once the fp chain leaves the execution segment
             match (fp - 2)
                 .ok()
                 .map(|r| self.segments.memory.get_relocatable(r))
             {
-                Some(Ok(opt_fp)) if opt_fp != fp => fp = opt_fp,
+                Some(Ok(opt_fp)) if opt_fp != fp && opt_fp.segment_index == fp.segment_index => {
+                    fp = opt_fp
+                }
                 _ => break,
             }
             // Try to check if the call instruction is (instruction0, instruction1) or just
@@ -768,11 +890,16 @@ impl VirtualMachine {
         entries
     }
 
-    ///Adds a new segment and to the memory and returns its starting location as a Relocatable value.
+    ///Adds a new segment to the memory and returns its starting location as a Relocatable value.
     pub fn add_memory_segment(&mut self) -> Relocatable {
         self.segments.add()
     }
 
+    /// Returns the amount of instructions executed so far
+    pub fn get_current_step(&self) -> usize {
+        self.current_step
+    }
+
     pub fn get_ap(&self) -> Relocatable {
         self.run_context.get_ap()
     }
@@ -785,17 +912,28 @@ impl VirtualMachine {
         self.run_context.get_pc()
     }
 
-    ///Gets the integer value corresponding to the Relocatable address
+    /// Returns a snapshot of the current registers (pc, ap and fp).
+    /// `RunContext` is `Copy`, so this is as cheap as reading the three registers.
+    pub fn get_run_context(&self) -> RunContext {
+        self.run_context
+    }
+
+    ///Gets the integer value corresponding to the Relocatable address.
+    ///Fails with UnknownMemoryCell if the cell is missing, or ExpectedInteger if it holds a
+    ///relocatable value.
     pub fn get_integer(&self, key: Relocatable) -> Result<Cow<Felt>, MemoryError> {
         self.segments.memory.get_integer(key)
     }
 
-    ///Gets the relocatable value corresponding to the Relocatable address
+    ///Gets the relocatable value corresponding to the Relocatable address.
+    ///Fails with UnknownMemoryCell if the cell is missing, or ExpectedRelocatable if it holds an
+    ///integer value.
     pub fn get_relocatable(&self, key: Relocatable) -> Result<Relocatable, MemoryError> {
         self.segments.memory.get_relocatable(key)
     }
 
-    ///Gets a MaybeRelocatable value from memory indicated by a generic address
+    ///Gets a MaybeRelocatable value from memory indicated by a generic address.
+    ///The value is cloned, so it can be held while the memory is mutably borrowed.
     pub fn get_maybe<'a, 'b: 'a, K: 'a>(&'b self, key: &'a K) -> Option<MaybeRelocatable>
     where
         Relocatable: TryFrom<&'a K>,
@@ -803,6 +941,12 @@ impl VirtualMachine {
         self.segments.memory.get(key).map(|x| x.into_owned())
     }
 
+    /// Returns the ordered log of every memory read and write performed so far
+    #[cfg(feature = "memory_access_log")]
+    pub fn access_log(&self) -> Ref<'_, [MemoryAccess]> {
+        Ref::map(self.segments.memory.access_log.borrow(), Vec::as_slice)
+    }
+
     /// Returns a reference to the vector with all builtins present in the virtual machine
     pub fn get_builtin_runners(&self) -> &Vec<(&'static str, BuiltinRunner)> {
         &self.builtin_runners
@@ -822,6 +966,34 @@ impl VirtualMachine {
         self.segments.memory.insert_value(key, val)
     }
 
+    ///Inserts a builtin output cell into memory so that it doesn't have to be deduced when accessed.
+    ///The value is checked against the builtin's `deduce_memory_cell`.
+    pub fn insert_deduced_cell<T: Into<MaybeRelocatable>>(
+        &mut self,
+        key: Relocatable,
+        val: T,
+    ) -> Result<(), VirtualMachineError> {
+        let val = val.into();
+        for (name, builtin) in self.builtin_runners.iter() {
+            if key.in_segment(builtin.base()) {
+                if let Some(deduced) = self.builtin_deduce_memory_cell(builtin, key)? {
+                    if deduced != val {
+                        return Err(VirtualMachineError::InconsistentAutoDeduction(
+                            *name,
+                            deduced,
+                            Some(val),
+                        ));
+                    }
+                }
+                break;
+            }
+        }
+        self.segments
+            .memory
+            .insert_value(key, val)
+            .map_err(VirtualMachineError::Memory)
+    }
+
     ///Writes data into the memory from address ptr and returns the first address after the data.
     pub fn load_data(
         &mut self,
@@ -898,6 +1070,60 @@ impl VirtualMachine {
         self.trace = None
     }
 
+    /// Returns the registers of each executed step, before their relocation,
+    /// or None if the trace is disabled
+    pub fn get_trace(&self) -> Option<&[TraceEntry]> {
+        self.trace.as_deref()
+    }
+
+    /// Returns the memory of the run
+    pub fn memory(&self) -> &Memory {
+        &self.segments.memory
+    }
+
+    /// Returns the segment manager of the run, holding the memory and the segment sizes
+    pub fn segments(&self) -> &MemorySegmentManager {
+        &self.segments
+    }
+
+    /// Rejects the memory writes at an offset above `max_offset` with
+    /// [MemoryError::OffsetExceedsMaximum], or lifts the limit if `None`
+    pub fn set_max_segment_offset(&mut self, max_offset: Option<usize>) {
+        self.segments.memory.max_offset = max_offset
+    }
+
+    /// Makes every step decode its instruction from memory, instead of reusing the decoded
+    /// instructions of previously visited pcs
+    pub fn disable_instruction_cache(&mut self) {
+        self.instruction_cache = None
+    }
+
+    /// Starts tracking gas, with `gas` units available to [consume_gas](Self::consume_gas)
+    pub fn set_remaining_gas(&mut self, gas: usize) {
+        self.remaining_gas = Some(gas)
+    }
+
+    /// Returns the gas left, or `None` if gas isn't tracked
+    pub fn get_remaining_gas(&self) -> Option<usize> {
+        self.remaining_gas
+    }
+
+    /// Withdraws `amount` units from the remaining gas. Meant to be called by the hints of
+    /// programs that meter their own execution.
+    /// Fails with [VirtualMachineError::OutOfGas], without withdrawing anything, if less than
+    /// `amount` units are left, and does nothing if gas isn't tracked. When returned by a hint,
+    /// this error aborts the run as is, instead of being reported as a failure of the hint.
+    pub fn consume_gas(&mut self, amount: usize) -> Result<(), VirtualMachineError> {
+        if let Some(remaining_gas) = self.remaining_gas {
+            self.remaining_gas = Some(
+                remaining_gas
+                    .checked_sub(amount)
+                    .ok_or(VirtualMachineError::OutOfGas(remaining_gas))?,
+            );
+        }
+        Ok(())
+    }
+
     #[doc(hidden)]
     pub fn skip_next_instruction_execution(&mut self) {
         self.skip_instruction_execution = true;
@@ -990,6 +1216,7 @@ pub struct VirtualMachineBuilder {
     pub(crate) current_step: usize,
     skip_instruction_execution: bool,
     run_finished: bool,
+    instruction_cache: bool,
     #[cfg(feature = "hooks")]
     pub(crate) hooks: crate::vm::hooks::Hooks,
 }
@@ -1010,6 +1237,7 @@ impl Default for VirtualMachineBuilder {
             skip_instruction_execution: false,
             segments: MemorySegmentManager::new(),
             run_finished: false,
+            instruction_cache: true,
             #[cfg(feature = "hooks")]
             hooks: Default::default(),
         }
@@ -1058,6 +1286,11 @@ impl VirtualMachineBuilder {
         self
     }
 
+    pub fn instruction_cache(mut self, instruction_cache: bool) -> VirtualMachineBuilder {
+        self.instruction_cache = instruction_cache;
+        self
+    }
+
     #[cfg(feature = "hooks")]
     pub fn hooks(mut self, hooks: crate::vm::hooks::Hooks) -> VirtualMachineBuilder {
         self.hooks = hooks;
@@ -1073,8 +1306,20 @@ impl VirtualMachineBuilder {
             skip_instruction_execution: self.skip_instruction_execution,
             segments: self.segments,
             run_finished: self.run_finished,
+            instruction_cache: self.instruction_cache.then(Vec::new),
+            #[cfg(test)]
+            decoded_instructions: 0,
+            remaining_gas: None,
             #[cfg(feature = "hooks")]
             hooks: self.hooks,
+            #[cfg(feature = "step_sampling")]
+            step_sampler: None,
+            #[cfg(feature = "deduction_profiling")]
+            deduction_profile: Default::default(),
+            #[cfg(feature = "hint_witness")]
+            hint_witness: None,
+            #[cfg(feature = "hint_witness")]
+            hint_witness_replay: None,
         }
     }
 }
@@ -1082,32 +1327,44 @@ impl VirtualMachineBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::stdlib::collections::HashMap;
+    use crate::stdlib::collections::{HashMap, HashSet};
     use crate::types::program::Program;
     use crate::vm::runners::builtin_runner::{
-        BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME,
+        BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
     };
-    use crate::vm::vm_memory::memory::Memory;
     use crate::{
         any_box,
-        hint_processor::builtin_hint_processor::builtin_hint_processor_definition::{
-            BuiltinHintProcessor, HintProcessorData,
+        hint_processor::{
+            builtin_hint_processor::{
+                builtin_hint_processor_definition::{
+                    BuiltinHintProcessor, HintFunc, HintProcessorData,
+                },
+                hint_utils::get_integer_from_var_name,
+            },
+            hint_processor_definition::HintReference,
         },
         relocatable,
+        stdlib::sync::Arc,
         types::{
             instance_definitions::{
                 bitwise_instance_def::BitwiseInstanceDef, ec_op_instance_def::EcOpInstanceDef,
+                keccak_instance_def::KeccakInstanceDef,
             },
             instruction::{Op1Addr, Register},
             relocatable::Relocatable,
         },
         utils::test_utils::*,
         vm::{
-            errors::memory_errors::MemoryError,
+            errors::{
+                hint_errors::HintError, memory_errors::MemoryError, vm_exception::VmException,
+            },
             runners::{
-                builtin_runner::{BitwiseBuiltinRunner, EcOpBuiltinRunner, HashBuiltinRunner},
+                builtin_runner::{
+                    BitwiseBuiltinRunner, EcOpBuiltinRunner, HashBuiltinRunner, KeccakBuiltinRunner,
+                },
                 cairo_runner::CairoRunner,
             },
+            vm_memory::memory::SPARSE_SEGMENT_GAP,
         },
     };
     use assert_matches::assert_matches;
@@ -2972,6 +3229,49 @@ mod tests {
         assert!(mem[1][1].as_ref().unwrap().is_accessed());
     }
 
+    #[test]
+    #[cfg(feature = "memory_access_log")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn access_log_single_step() {
+        use crate::vm::vm_memory::memory::MemoryAccessKind::{Read, Write};
+
+        let mut vm = vm!();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        run_context!(vm, 0, 1, 1);
+        // [ap] = 5, ap++
+        vm.segments = segments![((0, 0), 0x480680017fff8000_i64), ((0, 1), 5), ((1, 0), 7)];
+
+        assert_matches!(
+            vm.step(
+                &mut hint_processor,
+                exec_scopes_ref!(),
+                &HashMap::new(),
+                &HashMap::new()
+            ),
+            Ok(())
+        );
+
+        let expected_log = [
+            ((0, 0), mayberelocatable!(0x480680017fff8000_i64), Write),
+            ((0, 1), mayberelocatable!(5), Write),
+            ((1, 0), mayberelocatable!(7), Write),
+            // Instruction decoding
+            ((0, 0), mayberelocatable!(0x480680017fff8000_i64), Read),
+            ((0, 1), mayberelocatable!(5), Read),
+            // Operands
+            ((1, 0), mayberelocatable!(7), Read),
+            ((0, 1), mayberelocatable!(5), Read),
+            // Deduced dst
+            ((1, 1), mayberelocatable!(5), Write),
+        ]
+        .map(|(addr, value, kind)| MemoryAccess {
+            addr: Relocatable::from(addr),
+            value,
+            kind,
+        });
+        assert_eq!(&*vm.access_log(), &expected_log);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     /*
@@ -3573,6 +3873,73 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn insert_deduced_cell_keccak_output() {
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+        let mut vm = vm!();
+        vm.builtin_runners
+            .push((KECCAK_BUILTIN_NAME, builtin.into()));
+        vm.segments = segments![
+            ((0, 16), 43),
+            ((0, 17), 199),
+            ((0, 18), 0),
+            ((0, 19), 0),
+            ((0, 20), 0),
+            ((0, 21), 0),
+            ((0, 22), 0),
+            ((0, 23), 1)
+        ];
+
+        assert_matches!(
+            vm.insert_deduced_cell(
+                Relocatable::from((0, 25)),
+                Felt::new(3086936446498698982_u64)
+            ),
+            Ok(())
+        );
+        assert_matches!(vm.verify_auto_deductions(), Ok(()));
+        assert_eq!(
+            vm.get_integer(Relocatable::from((0, 25)))
+                .unwrap()
+                .into_owned(),
+            Felt::new(3086936446498698982_u64)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn insert_deduced_cell_keccak_output_inconsistent() {
+        let builtin = KeccakBuiltinRunner::new(&KeccakInstanceDef::default(), true);
+        let mut vm = vm!();
+        vm.builtin_runners
+            .push((KECCAK_BUILTIN_NAME, builtin.into()));
+        vm.segments = segments![
+            ((0, 16), 43),
+            ((0, 17), 199),
+            ((0, 18), 0),
+            ((0, 19), 0),
+            ((0, 20), 0),
+            ((0, 21), 0),
+            ((0, 22), 0),
+            ((0, 23), 1)
+        ];
+
+        assert_matches!(
+            vm.insert_deduced_cell(Relocatable::from((0, 25)), Felt::new(1)),
+            Err(VirtualMachineError::InconsistentAutoDeduction(
+                KECCAK_BUILTIN_NAME,
+                MaybeRelocatable::Int(x),
+                Some(MaybeRelocatable::Int(y))
+            )) if x == Felt::new(3086936446498698982_u64) && y == Felt::new(1)
+        );
+        assert!(vm
+            .segments
+            .memory
+            .get(&Relocatable::from((0, 25)))
+            .is_none());
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     /* Program used:
@@ -3597,6 +3964,37 @@ mod tests {
         assert_matches!(vm.verify_auto_deductions(), Ok(()));
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn verify_auto_deductions_bitwise_sparse_segment() {
+        let mut builtin = BitwiseBuiltinRunner::new(&BitwiseInstanceDef::default(), true);
+        builtin.base = 2;
+        let mut vm = vm!();
+        vm.builtin_runners
+            .push((BITWISE_BUILTIN_NAME, builtin.into()));
+        vm.segments = segments![((2, 0), 12), ((2, 1), 10)];
+        // An instance written past the gap moves the segment to the sparse backend
+        let far_instance = SPARSE_SEGMENT_GAP * 5;
+        vm.insert_value(relocatable!(2, far_instance), Felt::new(12))
+            .unwrap();
+        vm.insert_value(relocatable!(2, far_instance + 1), Felt::new(10))
+            .unwrap();
+        assert!(vm.segments.memory.sparse_data.contains_key(&2));
+        assert_matches!(vm.verify_auto_deductions(), Ok(()));
+
+        // 12 & 10 is 8
+        vm.insert_value(relocatable!(2, far_instance + 2), Felt::new(9))
+            .unwrap();
+        assert_matches!(
+            vm.verify_auto_deductions(),
+            Err(VirtualMachineError::InconsistentAutoDeduction(
+                BITWISE_BUILTIN_NAME,
+                MaybeRelocatable::Int(deduced),
+                Some(MaybeRelocatable::Int(value))
+            )) if deduced == Felt::new(8) && value == Felt::new(9)
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     /* Program used:
@@ -3931,6 +4329,51 @@ mod tests {
         )
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_integer_and_relocatable() {
+        let mut vm = vm!();
+        vm.segments = segments![((1, 0), 5), ((1, 1), (2, 3))];
+        assert_eq!(
+            vm.get_integer(relocatable!(1, 0)).unwrap().as_ref(),
+            &Felt::new(5)
+        );
+        assert_eq!(
+            vm.get_relocatable(relocatable!(1, 1)),
+            Ok(relocatable!(2, 3))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_integer_and_relocatable_missing_cell() {
+        let mut vm = vm!();
+        vm.segments = segments![((1, 0), 5)];
+        assert_eq!(
+            vm.get_integer(relocatable!(1, 1)),
+            Err(MemoryError::UnknownMemoryCell(relocatable!(1, 1)))
+        );
+        assert_eq!(
+            vm.get_relocatable(relocatable!(1, 1)),
+            Err(MemoryError::UnknownMemoryCell(relocatable!(1, 1)))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_integer_and_relocatable_wrong_type() {
+        let mut vm = vm!();
+        vm.segments = segments![((1, 0), 5), ((1, 1), (2, 3))];
+        assert_eq!(
+            vm.get_integer(relocatable!(1, 1)),
+            Err(MemoryError::ExpectedInteger(relocatable!(1, 1)))
+        );
+        assert_eq!(
+            vm.get_relocatable(relocatable!(1, 0)),
+            Err(MemoryError::ExpectedRelocatable(relocatable!(1, 0)))
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_maybe_key_not_in_memory() {
@@ -3944,6 +4387,19 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_maybe_returns_owned_value() {
+        let mut vm = vm!();
+        vm.segments = segments![((1, 0), (2, 3))];
+        let value = vm.get_maybe(&relocatable!(1, 0));
+        // The value doesn't borrow the memory, so it can be written back right away
+        vm.insert_value(relocatable!(1, 1), value.clone().unwrap())
+            .unwrap();
+        assert_eq!(value, Some(mayberelocatable!(2, 3)));
+        assert_eq!(vm.get_maybe(&relocatable!(1, 1)), value);
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_maybe_error() {
@@ -3966,6 +4422,20 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn add_memory_segments() {
+        let mut vm = vm!();
+        let first = vm.add_memory_segment();
+        let second = vm.add_memory_segment();
+        assert_eq!(first, relocatable!(0, 0));
+        assert_eq!(second, relocatable!(1, 0));
+        assert_eq!(vm.segments.num_segments(), 2);
+        // The new segments are writable right away
+        vm.insert_value(second, Felt::new(7)).unwrap();
+        assert_eq!(vm.get_integer(second).unwrap().as_ref(), &Felt::new(7));
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn add_temporary_segments() {
@@ -4228,6 +4698,7 @@ mod tests {
                 segment_used_sizes: Some(vec![1]),
                 public_memory_offsets: HashMap::new(),
                 memory: Memory::new(),
+                accessed_addresses_cache: crate::stdlib::cell::RefCell::new(None),
             })
             .skip_instruction_execution(true)
             .trace(Some(vec![TraceEntry {
@@ -4296,4 +4767,361 @@ mod tests {
                 .is_err());
         }
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_run_context_before_and_after_step() {
+        let mut vm = vm!();
+        vm.segments = segments![
+            ((0, 0), 0x400680017fff8000_i64),
+            ((0, 1), 0x4),
+            ((1, 0), (2, 0)),
+            ((1, 1), (3, 0))
+        ];
+        run_context!(vm, 0, 2, 2);
+
+        let before = vm.get_run_context();
+        assert_eq!(before.get_pc(), Relocatable::from((0, 0)));
+        assert_eq!(before.get_ap(), Relocatable::from((1, 2)));
+        assert_eq!(before.get_fp(), Relocatable::from((1, 2)));
+
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        assert_matches!(
+            vm.step(
+                &mut hint_processor,
+                exec_scopes_ref!(),
+                &HashMap::new(),
+                &HashMap::new()
+            ),
+            Ok(())
+        );
+
+        let after = vm.get_run_context();
+        assert_eq!(after.get_pc(), Relocatable::from((0, 2)));
+        assert_eq!(after.get_ap(), before.get_ap());
+        assert_eq!(after.get_fp(), before.get_fp());
+        assert_ne!(after, before);
+        // The snapshot isn't affected by later steps
+        assert_eq!(before.get_pc(), Relocatable::from((0, 0)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn step_program_one_instruction_at_a_time() {
+        let mut vm = vm!(true);
+        vm.segments = segments![
+            // [ap] = 5, ap++
+            ((0, 0), 0x480680017fff8000_i64),
+            ((0, 1), 5),
+            // [ap] = [ap - 1] + [ap - 1], ap++
+            ((0, 2), 0x48307fff7fff8000_i64),
+            // jmp rel 0
+            ((0, 3), 0x10780017fff7fff_i64),
+            ((0, 4), 0),
+            ((1, 0), (2, 0)),
+            ((1, 1), (3, 0))
+        ];
+        run_context!(vm, 0, 2, 2);
+
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        let mut exec_scopes = ExecutionScopes::new();
+        let expected_registers = [((0, 2), (1, 3)), ((0, 3), (1, 4)), ((0, 3), (1, 4))];
+        for (step, (pc, ap)) in expected_registers.into_iter().enumerate() {
+            assert_matches!(
+                vm.step(
+                    &mut hint_processor,
+                    &mut exec_scopes,
+                    &HashMap::new(),
+                    &HashMap::new()
+                ),
+                Ok(())
+            );
+            assert_eq!(vm.current_step, step + 1);
+            assert_eq!(vm.get_pc(), Relocatable::from(pc));
+            assert_eq!(vm.get_ap(), Relocatable::from(ap));
+            assert_eq!(vm.get_fp(), Relocatable::from((1, 2)));
+        }
+
+        check_memory!(vm.segments.memory, ((1, 2), 5), ((1, 3), 10));
+        let trace = vm.trace.unwrap();
+        assert_eq!(trace.len(), 3);
+        trace_check!(
+            trace,
+            [
+                ((0, 0), (1, 2), (1, 2)),
+                ((0, 2), (1, 3), (1, 2)),
+                ((0, 3), (1, 4), (1, 2))
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn instruction_cache_matches_fresh_decoding() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+
+        let mut cached_runner = cairo_runner!(program);
+        let mut cached_vm = vm!(true);
+        let end = cached_runner.initialize(&mut cached_vm).unwrap();
+        cached_runner
+            .run_until_pc(end, &mut cached_vm, &mut hint_processor)
+            .unwrap();
+
+        let mut uncached_runner = cairo_runner!(program);
+        let mut uncached_vm = vm!(true);
+        uncached_vm.disable_instruction_cache();
+        let end = uncached_runner.initialize(&mut uncached_vm).unwrap();
+        uncached_runner
+            .run_until_pc(end, &mut uncached_vm, &mut hint_processor)
+            .unwrap();
+
+        assert_eq!(cached_vm.trace, uncached_vm.trace);
+        assert!(uncached_vm.instruction_cache.is_none());
+
+        let cache = cached_vm.instruction_cache.take().unwrap();
+        assert!(cache.iter().flatten().flatten().count() > 0);
+        for (segment_index, segment) in cache.into_iter().enumerate() {
+            for (offset, cached_instruction) in segment.into_iter().enumerate() {
+                if let Some(cached_instruction) = cached_instruction {
+                    cached_vm.set_pc(Relocatable::from((segment_index as isize, offset)));
+                    assert_eq!(
+                        cached_vm.decode_current_instruction().unwrap(),
+                        cached_instruction
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn instruction_cache_decodes_each_pc_once() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/fibonacci_1000.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+
+        let mut cached_runner = cairo_runner!(program);
+        let mut cached_vm = vm!(true);
+        let end = cached_runner.initialize(&mut cached_vm).unwrap();
+        cached_runner
+            .run_until_pc(end, &mut cached_vm, &mut hint_processor)
+            .unwrap();
+
+        let mut uncached_runner = cairo_runner!(program);
+        let mut uncached_vm = vm!(true);
+        uncached_vm.disable_instruction_cache();
+        let end = uncached_runner.initialize(&mut uncached_vm).unwrap();
+        uncached_runner
+            .run_until_pc(end, &mut uncached_vm, &mut hint_processor)
+            .unwrap();
+
+        assert_eq!(cached_vm.trace, uncached_vm.trace);
+        assert_eq!(cached_vm.current_step, uncached_vm.current_step);
+        // Without the cache every step decodes its instruction, with it each visited pc is
+        // decoded once, however many times the loop goes through it
+        assert_eq!(uncached_vm.decoded_instructions, uncached_vm.current_step);
+        let visited_pcs = cached_vm
+            .trace
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.pc)
+            .collect::<HashSet<_>>()
+            .len();
+        assert_eq!(cached_vm.decoded_instructions, visited_pcs);
+        assert!(cached_vm.decoded_instructions * 100 < cached_vm.current_step);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn instruction_cache_keyed_by_segment_and_offset() {
+        let mut vm = vm!();
+        vm.segments = segments![
+            // [ap] = 5, ap++
+            ((0, 1), 0x480680017fff8000_i64),
+            ((0, 2), 5),
+            // [ap] = 7, ap++, at the same offset of another segment
+            ((2, 1), 0x480680017fff8000_i64),
+            ((2, 2), 7),
+            ((1, 0), (3, 0)),
+            ((1, 1), (4, 0))
+        ];
+        run_context!(vm, 1, 2, 2);
+        let program_instruction = vm.decode_current_instruction().unwrap();
+        assert_matches!(vm.step_instruction(), Ok(()));
+        assert_eq!(
+            vm.instruction_cache,
+            Some(vec![vec![None, Some(program_instruction.clone())]])
+        );
+
+        vm.run_context.pc = Relocatable::from((2, 1));
+        let other_instruction = vm.decode_current_instruction().unwrap();
+        assert_ne!(program_instruction, other_instruction);
+        assert_matches!(vm.step_instruction(), Ok(()));
+        // Running both pcs again goes through the cache
+        vm.run_context.pc = Relocatable::from((0, 1));
+        assert_matches!(vm.step_instruction(), Ok(()));
+        vm.run_context.pc = Relocatable::from((2, 1));
+        assert_matches!(vm.step_instruction(), Ok(()));
+        assert_eq!(
+            vm.instruction_cache,
+            Some(vec![
+                vec![None, Some(program_instruction)],
+                vec![],
+                vec![None, Some(other_instruction)]
+            ])
+        );
+        check_memory!(
+            vm.segments.memory,
+            ((1, 2), 5),
+            ((1, 3), 7),
+            ((1, 4), 5),
+            ((1, 5), 7)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_trace_has_an_entry_per_step() {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/fibonacci.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!(true);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        cairo_runner
+            .run_until_pc(end, &mut vm, &mut hint_processor)
+            .unwrap();
+
+        let trace = vm.get_trace().unwrap();
+        assert_eq!(trace.len(), vm.get_current_step());
+        assert_eq!(
+            trace[0],
+            TraceEntry {
+                pc: Relocatable::from((0, program.main.unwrap())),
+                ap: cairo_runner.get_initial_fp().unwrap(),
+                fp: cairo_runner.get_initial_fp().unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_trace_disabled() {
+        let mut vm = vm!(true);
+        assert_eq!(vm.get_trace(), Some(&[][..]));
+        vm.disable_trace();
+        assert_eq!(vm.get_trace(), None);
+        assert_eq!(vm!().get_trace(), None);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn read_cell_through_memory_accessor() {
+        let mut vm = vm!();
+        vm.segments = segments![((0, 0), 7), ((1, 2), (0, 0))];
+        assert_eq!(
+            vm.memory()
+                .get_integer(relocatable!(0, 0))
+                .unwrap()
+                .as_ref(),
+            &Felt::new(7)
+        );
+        assert_eq!(
+            vm.memory().get_relocatable(relocatable!(1, 2)),
+            Ok(relocatable!(0, 0))
+        );
+        assert_eq!(vm.segments().num_segments(), 2);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn consume_gas_untracked() {
+        let mut vm = vm!();
+        assert_eq!(vm.get_remaining_gas(), None);
+        assert_matches!(vm.consume_gas(usize::MAX), Ok(()));
+        assert_eq!(vm.get_remaining_gas(), None);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn consume_gas_tracked() {
+        let mut vm = vm!();
+        vm.set_remaining_gas(10);
+        assert_matches!(vm.consume_gas(4), Ok(()));
+        assert_eq!(vm.get_remaining_gas(), Some(6));
+        assert_matches!(vm.consume_gas(7), Err(VirtualMachineError::OutOfGas(6)));
+        assert_eq!(vm.get_remaining_gas(), Some(6));
+        assert_matches!(vm.consume_gas(6), Ok(()));
+        assert_eq!(vm.get_remaining_gas(), Some(0));
+    }
+
+    fn consume_gas_hint(
+        vm: &mut VirtualMachine,
+        _exec_scopes: &mut ExecutionScopes,
+        ids_data: &HashMap<String, HintReference>,
+        ap_tracking: &ApTracking,
+        _constants: &HashMap<String, Felt>,
+    ) -> Result<(), HintError> {
+        let cost = get_integer_from_var_name("cost", vm, ids_data, ap_tracking)?;
+        let cost = cost.to_usize().ok_or(HintError::BigintToUsizeFail)?;
+        Ok(vm.consume_gas(cost)?)
+    }
+
+    fn run_gas_loop(
+        initial_gas: usize,
+    ) -> (CairoRunner, VirtualMachine, Result<(), VirtualMachineError>) {
+        let program = Program::from_bytes(
+            include_bytes!("../../cairo_programs/gas_loop.json"),
+            Some("main"),
+        )
+        .unwrap();
+        let mut hint_processor = BuiltinHintProcessor::new_empty();
+        hint_processor.add_hint(
+            String::from("consume_gas(ids.cost)"),
+            Arc::new(HintFunc(Box::new(consume_gas_hint))),
+        );
+        let mut cairo_runner = cairo_runner!(program);
+        let mut vm = vm!();
+        vm.set_remaining_gas(initial_gas);
+        let end = cairo_runner.initialize(&mut vm).unwrap();
+        let result = cairo_runner.run_until_pc(end, &mut vm, &mut hint_processor);
+        (cairo_runner, vm, result)
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn gas_loop_enough_gas() {
+        let (_, vm, result) = run_gas_loop(100);
+        assert_matches!(result, Ok(()));
+        // 10 iterations of 7 gas units
+        assert_eq!(vm.get_remaining_gas(), Some(30));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn gas_loop_out_of_gas() {
+        let (cairo_runner, vm, result) = run_gas_loop(30);
+        // The fifth iteration is aborted, as only 2 gas units are left
+        let error = result.unwrap_err();
+        assert_matches!(error, VirtualMachineError::OutOfGas(2));
+        assert_eq!(vm.get_remaining_gas(), Some(2));
+
+        let exception = VmException::from_vm_error(&cairo_runner, &vm, error);
+        assert_matches!(exception.inner_exc, VirtualMachineError::OutOfGas(2));
+        assert!(exception
+            .to_string()
+            .contains("Ran out of gas, only 2 units were left"));
+    }
 }
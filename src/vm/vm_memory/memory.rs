@@ -1,7 +1,11 @@
+#[cfg(feature = "memory_access_log")]
+use crate::stdlib::cell::RefCell;
 use crate::stdlib::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
-    fmt,
+    cell::Cell,
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt, mem,
     prelude::*,
 };
 
@@ -11,12 +15,20 @@ use crate::{
     vm::errors::memory_errors::MemoryError,
 };
 use felt::Felt;
-use num_traits::ToPrimitive;
+use num_traits::{ToPrimitive, Zero};
 
-pub struct ValidationRule(
-    #[allow(clippy::type_complexity)]
-    pub  Box<dyn Fn(&Memory, Relocatable) -> Result<Vec<Relocatable>, MemoryError>>,
-);
+/// Writing this many cells past the end of a segment switches it to the sparse backend
+pub const SPARSE_SEGMENT_GAP: usize = 1 << 16;
+
+#[cfg(feature = "std")]
+type ValidationRuleFn =
+    dyn Fn(&Memory, Relocatable) -> Result<Vec<Relocatable>, MemoryError> + Send + Sync;
+#[cfg(not(feature = "std"))]
+type ValidationRuleFn = dyn Fn(&Memory, Relocatable) -> Result<Vec<Relocatable>, MemoryError>;
+
+///A rule run on each cell written to a segment, returning the addresses it validated.
+///Under `std` it has to be `Send + Sync`, so that a `VirtualMachine` can be moved to another thread.
+pub struct ValidationRule(pub Box<ValidationRuleFn>);
 
 #[derive(Clone, PartialEq, Debug)]
 pub(crate) struct MemoryCell(MaybeRelocatable, bool);
@@ -43,30 +55,68 @@ impl MemoryCell {
     }
 }
 
+/// Kind of operation recorded in the memory access log
+#[cfg(feature = "memory_access_log")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryAccessKind {
+    Read,
+    Write,
+}
+
+/// A single entry of the memory access log
+#[cfg(feature = "memory_access_log")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryAccess {
+    pub addr: Relocatable,
+    pub value: MaybeRelocatable,
+    pub kind: MemoryAccessKind,
+}
+
 pub struct Memory {
     pub(crate) data: Vec<Vec<Option<MemoryCell>>>,
+    // Segments written at sparse, high offsets are stored here, keyed by segment index
+    // Their entry in `data` is kept empty
+    pub(crate) sparse_data: HashMap<usize, BTreeMap<usize, MemoryCell>>,
     pub(crate) temp_data: Vec<Vec<Option<MemoryCell>>>,
     // relocation_rules's keys map to temp_data's indices and therefore begin at
     // zero; that is, segment_index = -1 maps to key 0, -2 to key 1...
     pub(crate) relocation_rules: HashMap<usize, Relocatable>,
     pub validated_addresses: HashSet<Relocatable>,
     validation_rules: HashMap<usize, ValidationRule>,
+    // Set whenever a cell is written or marked as accessed, cleared once the
+    // segment manager has refreshed the caches derived from the memory
+    pub(crate) dirty: Cell<bool>,
+    // Writes past this offset are rejected, to bound the memory used by runaway programs
+    pub(crate) max_offset: Option<usize>,
+    #[cfg(feature = "memory_access_log")]
+    pub(crate) access_log: RefCell<Vec<MemoryAccess>>,
+    // Cells written while recording the effects of the hints, see `vm::hint_witness`
+    #[cfg(feature = "hint_witness")]
+    pub(crate) hint_writes: Option<Vec<(Relocatable, MaybeRelocatable)>>,
 }
 
 impl Memory {
     pub fn new() -> Memory {
         Memory {
             data: Vec::<Vec<Option<MemoryCell>>>::new(),
+            sparse_data: HashMap::new(),
             temp_data: Vec::<Vec<Option<MemoryCell>>>::new(),
             relocation_rules: HashMap::new(),
             validated_addresses: HashSet::<Relocatable>::new(),
             validation_rules: HashMap::new(),
+            max_offset: None,
+            dirty: Cell::new(true),
+            #[cfg(feature = "memory_access_log")]
+            access_log: RefCell::new(Vec::new()),
+            #[cfg(feature = "hint_witness")]
+            hint_writes: None,
         }
     }
     /// Inserts a value into a memory address
     /// Will return an Error if the segment index given by the address corresponds to a non-allocated segment,
     /// or if the inserted value is inconsistent with the current value at the memory cell
-    /// If the address isnt contiguous with previously inserted data, memory gaps will be represented by None values
+    /// If the address isnt contiguous with previously inserted data, memory gaps will be represented by None values,
+    /// unless the gap is larger than SPARSE_SEGMENT_GAP, in which case the segment is moved to the sparse backend
     pub fn insert<'a, K: 'a, V: 'a>(&mut self, key: &'a K, val: &'a V) -> Result<(), MemoryError>
     where
         Relocatable: TryFrom<&'a K>,
@@ -75,8 +125,18 @@ impl Memory {
         let relocatable: Relocatable = key
             .try_into()
             .map_err(|_| MemoryError::AddressNotRelocatable)?;
+        if let Some(max_offset) = self.max_offset {
+            if relocatable.offset > max_offset {
+                return Err(MemoryError::OffsetExceedsMaximum(relocatable, max_offset));
+            }
+        }
         let val = MaybeRelocatable::from(val);
         let (value_index, value_offset) = from_relocatable_to_indexes(relocatable);
+        self.dirty.set(true);
+        #[cfg(feature = "hint_witness")]
+        if let Some(hint_writes) = self.hint_writes.as_mut() {
+            hint_writes.push((relocatable, val.clone()));
+        }
 
         let data = if relocatable.segment_index.is_negative() {
             &mut self.temp_data
@@ -89,6 +149,44 @@ impl Memory {
             .get_mut(value_index)
             .ok_or(MemoryError::UnallocatedSegment(value_index, data_len))?;
 
+        if !relocatable.segment_index.is_negative()
+            && (self.sparse_data.contains_key(&value_index)
+                || value_offset >= segment.len() + SPARSE_SEGMENT_GAP)
+        {
+            // Move the cells written so far into the sparse segment
+            let sparse_segment = self.sparse_data.entry(value_index).or_insert_with(|| {
+                mem::take(segment)
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(offset, cell)| Some((offset, cell?)))
+                    .collect()
+            });
+            match sparse_segment.get(&value_offset) {
+                None => {
+                    sparse_segment.insert(value_offset, MemoryCell::new(val));
+                }
+                Some(current_cell) => {
+                    if current_cell.get_value() != &val {
+                        //Existing memory cannot be changed
+                        return Err(MemoryError::InconsistentMemory(
+                            relocatable.into(),
+                            current_cell.get_value().clone(),
+                            val,
+                        ));
+                    }
+                }
+            };
+            #[cfg(feature = "memory_access_log")]
+            if let Some(cell) = sparse_segment.get(&value_offset) {
+                self.access_log.get_mut().push(MemoryAccess {
+                    addr: relocatable,
+                    value: cell.get_value().clone(),
+                    kind: MemoryAccessKind::Write,
+                });
+            }
+            return self.validate_memory_cell(relocatable);
+        }
+
         //Check if the element is inserted next to the last one on the segment
         //Forgoing this check would allow data to be inserted in a different index
         if segment.len() <= value_offset {
@@ -109,6 +207,14 @@ impl Memory {
                 }
             }
         };
+        #[cfg(feature = "memory_access_log")]
+        if let Some(cell) = &segment[value_offset] {
+            self.access_log.get_mut().push(MemoryAccess {
+                addr: relocatable,
+                value: cell.get_value().clone(),
+                kind: MemoryAccessKind::Write,
+            });
+        }
         self.validate_memory_cell(relocatable)
     }
 
@@ -125,7 +231,20 @@ impl Memory {
             &self.data
         };
         let (i, j) = from_relocatable_to_indexes(relocatable);
-        Some(self.relocate_value(data.get(i)?.get(j)?.as_ref()?.get_value()))
+        let cell = match self.sparse_data.get(&i) {
+            Some(sparse_segment) if !relocatable.segment_index.is_negative() => {
+                sparse_segment.get(&j)?
+            }
+            _ => data.get(i)?.get(j)?.as_ref()?,
+        };
+        let value = self.relocate_value(cell.get_value());
+        #[cfg(feature = "memory_access_log")]
+        self.access_log.borrow_mut().push(MemoryAccess {
+            addr: relocatable,
+            value: value.as_ref().clone(),
+            kind: MemoryAccessKind::Read,
+        });
+        Some(value)
     }
 
     // Version of Memory.relocate_value() that doesn't require a self reference
@@ -151,15 +270,23 @@ impl Memory {
             return Ok(());
         }
         // Relocate temporary addresses in memory
-        for segment in self.data.iter_mut().chain(self.temp_data.iter_mut()) {
-            for cell in segment.iter_mut().flatten() {
-                let value = cell.get_value_mut();
-                match value {
-                    MaybeRelocatable::RelocatableValue(addr) if addr.segment_index < 0 => {
-                        *value = Memory::relocate_address(*addr, &self.relocation_rules);
-                    }
-                    _ => {}
+        let cells = self
+            .data
+            .iter_mut()
+            .chain(self.temp_data.iter_mut())
+            .flat_map(|segment| segment.iter_mut().flatten())
+            .chain(
+                self.sparse_data
+                    .values_mut()
+                    .flat_map(|segment| segment.values_mut()),
+            );
+        for cell in cells {
+            let value = cell.get_value_mut();
+            match value {
+                MaybeRelocatable::RelocatableValue(addr) if addr.segment_index < 0 => {
+                    *value = Memory::relocate_address(*addr, &self.relocation_rules);
                 }
+                _ => {}
             }
         }
         // Move relocated temporary memory into the real memory
@@ -273,6 +400,14 @@ impl Memory {
                     }
                 }
             }
+            if let Some(sparse_segment) = self.sparse_data.get(index) {
+                for offset in sparse_segment.keys() {
+                    let addr = Relocatable::from((*index as isize, *offset));
+                    if !self.validated_addresses.contains(&addr) {
+                        self.validated_addresses.extend(rule.0(self, addr)?);
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -326,7 +461,18 @@ impl Memory {
     }
 
     pub fn mark_as_accessed(&mut self, addr: Relocatable) {
+        self.dirty.set(true);
         let (i, j) = from_relocatable_to_indexes(addr);
+        if addr.segment_index >= 0 {
+            if let Some(cell) = self
+                .sparse_data
+                .get_mut(&i)
+                .and_then(|segment| segment.get_mut(&j))
+            {
+                cell.mark_accessed();
+                return;
+            }
+        }
         let data = if addr.segment_index < 0 {
             &mut self.temp_data
         } else {
@@ -342,6 +488,14 @@ impl Memory {
         &self,
         segment_index: usize,
     ) -> Option<usize> {
+        if let Some(sparse_segment) = self.sparse_data.get(&segment_index) {
+            return Some(
+                sparse_segment
+                    .values()
+                    .filter(|cell| cell.is_accessed())
+                    .count(),
+            );
+        }
         let segment = self.data.get(segment_index)?;
         Some(
             segment
@@ -356,6 +510,127 @@ impl Memory {
                 .count(),
         )
     }
+
+    /// Returns the size of a (non-temporary) segment, including the gaps between its cells
+    pub(crate) fn segment_len(&self, segment_index: usize) -> usize {
+        match self.sparse_data.get(&segment_index) {
+            Some(sparse_segment) => sparse_segment
+                .keys()
+                .next_back()
+                .map_or(0, |offset| offset + 1),
+            None => self.data.get(segment_index).map_or(0, Vec::len),
+        }
+    }
+
+    /// Returns the cell at `offset` of a (non-temporary) segment, without logging the access
+    pub(crate) fn segment_cell(&self, segment_index: usize, offset: usize) -> Option<&MemoryCell> {
+        match self.sparse_data.get(&segment_index) {
+            Some(sparse_segment) => sparse_segment.get(&offset),
+            None => self.data.get(segment_index)?.get(offset)?.as_ref(),
+        }
+    }
+
+    /// Iterates over the cells of a (non-temporary) segment in order, along with their offsets
+    pub(crate) fn segment_cells(
+        &self,
+        segment_index: usize,
+    ) -> impl Iterator<Item = (usize, &MemoryCell)> {
+        let dense_segment = self
+            .data
+            .get(segment_index)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        dense_segment
+            .iter()
+            .enumerate()
+            .filter_map(|(offset, cell)| Some((offset, cell.as_ref()?)))
+            .chain(
+                self.sparse_data
+                    .get(&segment_index)
+                    .into_iter()
+                    .flat_map(|segment| segment.iter().map(|(offset, cell)| (*offset, cell))),
+            )
+    }
+
+    /// Iterates over the cells of a temporary segment in order, along with their offsets.
+    /// `index` is the position of the segment in `temp_data`, i.e. segment -1 has index 0.
+    fn temp_segment_cells(&self, index: usize) -> impl Iterator<Item = (usize, &MemoryCell)> {
+        self.temp_data
+            .get(index)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .filter_map(|(offset, cell)| Some((offset, cell.as_ref()?)))
+    }
+
+    /// Compares the values stored in both memories, ignoring validation rules and accessed cells.
+    /// If `treat_hole_as_zero` is set, a cell holding zero in one memory and left unset in the
+    /// other is considered equal; otherwise both memories must have the same cells set.
+    /// Temporary segments are compared as they are, without applying relocation rules.
+    pub fn eq_with(&self, other: &Memory, treat_hole_as_zero: bool) -> bool {
+        let num_segments = self.data.len().max(other.data.len());
+        let num_temp_segments = self.temp_data.len().max(other.temp_data.len());
+        (0..num_segments).all(|i| {
+            segment_cells_eq(
+                self.segment_cells(i),
+                other.segment_cells(i),
+                treat_hole_as_zero,
+            )
+        }) && (0..num_temp_segments).all(|i| {
+            segment_cells_eq(
+                self.temp_segment_cells(i),
+                other.temp_segment_cells(i),
+                treat_hole_as_zero,
+            )
+        })
+    }
+}
+
+// Compares the cells of two segments, both sorted by offset
+fn segment_cells_eq<'a>(
+    lhs: impl Iterator<Item = (usize, &'a MemoryCell)>,
+    rhs: impl Iterator<Item = (usize, &'a MemoryCell)>,
+    treat_hole_as_zero: bool,
+) -> bool {
+    let is_hole_equivalent = |cell: &MemoryCell| {
+        treat_hole_as_zero
+            && matches!(cell.get_value(), MaybeRelocatable::Int(value) if value.is_zero())
+    };
+    let (mut lhs, mut rhs) = (lhs.peekable(), rhs.peekable());
+    loop {
+        // Once a segment is exhausted, the remaining cells of the other one are compared to holes
+        let order = match (lhs.peek(), rhs.peek()) {
+            (None, None) => return true,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((lhs_offset, _)), Some((rhs_offset, _))) => lhs_offset.cmp(rhs_offset),
+        };
+        let cells_eq = match order {
+            Ordering::Equal => match (lhs.next(), rhs.next()) {
+                (Some((_, lhs_cell)), Some((_, rhs_cell))) => {
+                    lhs_cell.get_value() == rhs_cell.get_value()
+                }
+                _ => false,
+            },
+            Ordering::Less => lhs
+                .next()
+                .map_or(false, |(_, cell)| is_hole_equivalent(cell)),
+            Ordering::Greater => rhs
+                .next()
+                .map_or(false, |(_, cell)| is_hole_equivalent(cell)),
+        };
+        if !cells_eq {
+            return false;
+        }
+    }
+}
+
+/// Two memories are equal if they hold the same values in the same cells, see [Memory::eq_with]
+impl PartialEq for Memory {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_with(other, false)
+    }
 }
 
 impl fmt::Display for Memory {
@@ -369,12 +644,10 @@ impl fmt::Display for Memory {
                 }
             }
         }
-        for (i, segment) in self.data.iter().enumerate() {
-            for (j, cell) in segment.iter().enumerate() {
-                if let Some(cell) = cell {
-                    let elem = cell.get_value();
-                    writeln!(f, "({i},{j}) : {elem}")?;
-                }
+        for i in 0..self.data.len() {
+            for (j, cell) in self.segment_cells(i) {
+                let elem = cell.get_value();
+                writeln!(f, "({i},{j}) : {elem}")?;
             }
         }
         writeln!(f, "}}")
@@ -479,6 +752,113 @@ mod memory_tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn insert_at_high_offset_uses_sparse_segment() {
+        let mut segments = MemorySegmentManager::new();
+        segments.add();
+        segments.add();
+        segments
+            .memory
+            .insert_value(relocatable!(1, 0), Felt::new(1))
+            .unwrap();
+        segments
+            .memory
+            .insert_value(relocatable!(1, 1_000_000), Felt::new(2))
+            .unwrap();
+
+        // The dense segment stays empty instead of holding a million gaps
+        assert!(segments.memory.data[1].is_empty());
+        assert_eq!(segments.memory.sparse_data[&1].len(), 2);
+        assert_eq!(
+            segments
+                .memory
+                .get_integer(relocatable!(1, 0))
+                .unwrap()
+                .as_ref(),
+            &Felt::new(1)
+        );
+        assert_eq!(
+            segments
+                .memory
+                .get_integer(relocatable!(1, 1_000_000))
+                .unwrap()
+                .as_ref(),
+            &Felt::new(2)
+        );
+        assert!(segments.memory.get(&relocatable!(1, 500)).is_none());
+        assert_matches!(
+            segments
+                .memory
+                .insert_value(relocatable!(1, 1_000_000), Felt::new(3)),
+            Err(MemoryError::InconsistentMemory(..))
+        );
+
+        // Segments without sparse writes keep the dense layout
+        segments
+            .memory
+            .insert_value(relocatable!(0, 2), Felt::new(4))
+            .unwrap();
+        assert_eq!(segments.memory.data[0].len(), 3);
+        assert!(!segments.memory.sparse_data.contains_key(&0));
+
+        assert_eq!(segments.compute_effective_sizes(), &vec![3, 1_000_001]);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn dense_and_sparse_segments_agree_on_memory_fixture() {
+        // Cells of the memory file written by the Python VM for struct.cairo
+        let cells: Vec<(usize, Felt)> =
+            include_bytes!("../../../cairo_programs/trace_memory/cairo_memory_struct")
+                .chunks_exact(8 + 32)
+                .map(|cell| {
+                    let address = u64::from_le_bytes(cell[..8].try_into().unwrap()) as usize;
+                    let mut value = cell[8..].to_vec();
+                    value.reverse();
+                    (address, Felt::from_bytes_be(&value))
+                })
+                .collect();
+        let end = cells.last().unwrap().0 + 1;
+
+        let mut dense = Memory::new();
+        dense.data.push(Vec::new());
+        let mut sparse = Memory::new();
+        sparse.data.push(Vec::new());
+        // A write far past the end of the segment moves it to the sparse backend
+        let far = relocatable!(0, end + SPARSE_SEGMENT_GAP);
+        sparse.insert_value(far, Felt::zero()).unwrap();
+        assert!(sparse.sparse_data.contains_key(&0));
+
+        for (address, value) in cells.iter() {
+            dense
+                .insert_value(relocatable!(0, *address), value.clone())
+                .unwrap();
+            sparse
+                .insert_value(relocatable!(0, *address), value.clone())
+                .unwrap();
+        }
+        assert!(!dense.sparse_data.contains_key(&0));
+
+        // Reads agree on every offset, holes and out of range offsets included
+        for offset in 0..end + 2 {
+            let addr = relocatable!(0, offset);
+            assert_eq!(dense.get(&addr), sparse.get(&addr));
+            assert_eq!(dense.get_integer(addr).ok(), sparse.get_integer(addr).ok());
+        }
+        assert!(dense.get(&relocatable!(0, end)).is_none());
+        assert!(sparse.get(&relocatable!(0, end)).is_none());
+
+        // Rewriting a cell with a different value fails on both backends
+        let (address, value) = &cells[0];
+        for memory in [&mut dense, &mut sparse] {
+            assert_matches!(
+                memory.insert_value(relocatable!(0, *address), value.clone() + 1_u32),
+                Err(MemoryError::InconsistentMemory(..))
+            );
+        }
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_valuef_from_temp_segment() {
@@ -590,6 +970,56 @@ mod memory_tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn insert_beyond_max_offset() {
+        let mut memory = Memory::new();
+        memory.data.push(Vec::new());
+        memory.temp_data.push(Vec::new());
+        memory.max_offset = Some(10);
+        assert_eq!(
+            memory.insert_value(relocatable!(0, 10), Felt::new(1)),
+            Ok(())
+        );
+        assert_eq!(
+            memory.insert_value(relocatable!(0, 11), Felt::new(1)),
+            Err(MemoryError::OffsetExceedsMaximum(relocatable!(0, 11), 10))
+        );
+        assert_eq!(
+            memory.insert_value(relocatable!(-1, 1 << 40), Felt::new(1)),
+            Err(MemoryError::OffsetExceedsMaximum(
+                relocatable!(-1, 1 << 40),
+                10
+            ))
+        );
+        assert_eq!(memory.get(&relocatable!(0, 11)), None);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn insert_inconsistent_memory_error_shows_both_values() {
+        let mut memory = Memory::new();
+        memory.data.push(Vec::new());
+        memory
+            .insert_value(relocatable!(0, 3), Felt::new(7))
+            .unwrap();
+        let error = memory
+            .insert_value(relocatable!(0, 3), relocatable!(1, 2))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            MemoryError::InconsistentMemory(
+                mayberelocatable!(0, 3),
+                mayberelocatable!(7),
+                mayberelocatable!(1, 2)
+            )
+        );
+        assert_eq!(
+            error.to_string(),
+            "Inconsistent memory assignment at address 0:3: the cell already holds 7, attempted to write 1:2"
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn insert_address_not_relocatable() {
@@ -1437,4 +1867,57 @@ mod memory_tests {
         *cell_value = mayberelocatable!(2);
         assert_eq!(cell.get_value(), &mayberelocatable!(2));
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn eq_with_hole_vs_zero() {
+        let with_zeros = memory![((0, 0), 1), ((0, 1), 0), ((0, 2), 3), ((0, 3), 0)];
+        let with_holes = memory![((0, 0), 1), ((0, 2), 3)];
+
+        assert!(!with_zeros.eq_with(&with_holes, false));
+        assert!(!with_holes.eq_with(&with_zeros, false));
+        assert!(with_zeros.eq_with(&with_holes, true));
+        assert!(with_holes.eq_with(&with_zeros, true));
+        // The default comparison is strict
+        assert!(with_zeros != with_holes);
+        assert!(with_zeros == with_zeros);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn eq_with_hole_vs_zero_in_temp_segment() {
+        let with_zeros = memory![((0, 0), 1), ((-1, 0), 0), ((-1, 1), 5)];
+        let with_holes = memory![((0, 0), 1), ((-1, 1), 5)];
+
+        assert!(!with_zeros.eq_with(&with_holes, false));
+        assert!(with_zeros.eq_with(&with_holes, true));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn eq_with_different_values() {
+        let lhs = memory![((0, 0), 1), ((1, 0), (0, 0))];
+        let hole_vs_value = memory![((0, 0), 1), ((1, 1), 0)];
+        let relocatable_vs_zero = memory![((0, 0), 1), ((1, 0), 0)];
+
+        for treat_hole_as_zero in [false, true] {
+            assert!(!lhs.eq_with(&hole_vs_value, treat_hole_as_zero));
+            assert!(!lhs.eq_with(&relocatable_vs_zero, treat_hole_as_zero));
+            assert!(!relocatable_vs_zero.eq_with(&lhs, treat_hole_as_zero));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn eq_with_sparse_segment() {
+        let mut lhs = memory![((0, 0), 1)];
+        lhs.insert_value(relocatable!(0, 1_000_000), Felt::new(2))
+            .unwrap();
+        let mut rhs = memory![((0, 0), 1), ((0, 1), 0)];
+        rhs.insert_value(relocatable!(0, 1_000_000), Felt::new(2))
+            .unwrap();
+
+        assert!(!lhs.eq_with(&rhs, false));
+        assert!(lhs.eq_with(&rhs, true));
+    }
 }
@@ -1,6 +1,7 @@
 use crate::stdlib::prelude::*;
-use crate::stdlib::{any::Any, collections::HashMap};
+use crate::stdlib::{any::Any, cell::RefCell, collections::HashMap};
 use crate::vm::runners::cairo_runner::CairoArg;
+use felt::Felt;
 
 use crate::{
     types::relocatable::{MaybeRelocatable, Relocatable},
@@ -17,6 +18,8 @@ pub struct MemorySegmentManager {
     // A map from segment index to a list of pairs (offset, page_id) that constitute the
     // public memory. Note that the offset is absolute (not based on the page_id).
     pub public_memory_offsets: HashMap<usize, Vec<(usize, usize)>>,
+    // Amount of accessed addresses of each segment, valid as long as the memory isn't dirty
+    pub(crate) accessed_addresses_cache: RefCell<Option<Vec<usize>>>,
 }
 
 impl MemorySegmentManager {
@@ -67,13 +70,18 @@ impl MemorySegmentManager {
             segment_used_sizes: None,
             public_memory_offsets: HashMap::new(),
             memory: Memory::new(),
+            accessed_addresses_cache: RefCell::new(None),
         }
     }
 
     /// Calculates the size of each memory segment.
     pub fn compute_effective_sizes(&mut self) -> &Vec<usize> {
-        self.segment_used_sizes
-            .get_or_insert_with(|| self.memory.data.iter().map(Vec::len).collect())
+        let memory = &self.memory;
+        self.segment_used_sizes.get_or_insert_with(|| {
+            (0..memory.data.len())
+                .map(|index| memory.segment_len(index))
+                .collect()
+        })
     }
 
     ///Returns the number of used segments if they have been computed.
@@ -90,6 +98,9 @@ impl MemorySegmentManager {
     }
 
     ///Returns a vector containing the first relocated address of each memory segment
+    ///Segments are laid out contiguously by increasing segment index, starting at address 1, so
+    ///the relocated addresses follow the order in which segments were added: program, execution,
+    ///then builtins (see CairoRunner::initialize_segments)
     pub fn relocate_segments(&self) -> Result<Vec<usize>, MemoryError> {
         let first_addr = 1;
         let mut relocation_table = vec![first_addr];
@@ -132,16 +143,16 @@ impl MemorySegmentManager {
     ) -> Result<MaybeRelocatable, VirtualMachineError> {
         match arg {
             CairoArg::Single(value) => Ok(value.clone()),
-            CairoArg::Array(values) => {
+            CairoArg::Array(values) | CairoArg::Span(values) => {
                 let base = self.add();
                 self.load_data(base, values)?;
                 Ok(base.into())
             }
             CairoArg::Composed(cairo_args) => {
-                let args = cairo_args
-                    .iter()
-                    .map(|cairo_arg| self.gen_cairo_arg(cairo_arg))
-                    .collect::<Result<Vec<MaybeRelocatable>, VirtualMachineError>>()?;
+                let mut args = Vec::new();
+                for cairo_arg in cairo_args {
+                    args.extend(self.gen_cairo_arg_values(cairo_arg)?);
+                }
                 let base = self.add();
                 self.load_data(base, &args)?;
                 Ok(base.into())
@@ -149,6 +160,19 @@ impl MemorySegmentManager {
         }
     }
 
+    /// Returns the values an argument takes when passed to a function: its value or pointer,
+    /// followed by its length for spans.
+    pub fn gen_cairo_arg_values(
+        &mut self,
+        arg: &CairoArg,
+    ) -> Result<Vec<MaybeRelocatable>, VirtualMachineError> {
+        let value = self.gen_cairo_arg(arg)?;
+        Ok(match arg {
+            CairoArg::Span(values) => vec![value, values.len().into()],
+            _ => vec![value],
+        })
+    }
+
     pub fn write_arg(
         &mut self,
         ptr: Relocatable,
@@ -182,31 +206,74 @@ impl MemorySegmentManager {
     }
 
     pub fn get_memory_holes(&self) -> Result<usize, MemoryError> {
-        let data = &self.memory.data;
         let mut memory_holes = 0;
-        // Count the memory holes for each segment by substracting the amount of accessed_addresses from the segment's size
-        // Segments without accesses addresses are not accounted for when counting memory holes
-        for i in 0..data.len() {
-            let accessed_amount = match self.memory.get_amount_of_accessed_addresses_for_segment(i)
-            {
-                Some(accessed_amount) if accessed_amount > 0 => accessed_amount,
-                _ => continue,
-            };
-            let segment_size = self
-                .get_segment_size(i)
-                .ok_or(MemoryError::MissingSegmentUsedSizes)?;
-            if accessed_amount > segment_size {
-                return Err(MemoryError::SegmentHasMoreAccessedAddressesThanSize(
-                    i,
-                    accessed_amount,
-                    segment_size,
-                ));
-            }
-            memory_holes += segment_size - accessed_amount;
+        for (i, accessed_amount) in self
+            .get_accessed_addresses_amounts()
+            .into_iter()
+            .enumerate()
+        {
+            memory_holes += self.count_memory_holes(i, accessed_amount)?;
         }
         Ok(memory_holes)
     }
 
+    /// Counts the memory holes of a single segment, as [get_memory_holes](Self::get_memory_holes)
+    /// does for the whole memory
+    pub fn get_segment_memory_holes(&self, segment_index: usize) -> Result<usize, MemoryError> {
+        let accessed_amount = self
+            .get_accessed_addresses_amounts()
+            .get(segment_index)
+            .copied()
+            .unwrap_or_default();
+        self.count_memory_holes(segment_index, accessed_amount)
+    }
+
+    // Counts the memory holes of a segment by substracting the amount of accessed_addresses from the segment's size
+    // Segments without accesses addresses are not accounted for when counting memory holes
+    fn count_memory_holes(
+        &self,
+        segment_index: usize,
+        accessed_amount: usize,
+    ) -> Result<usize, MemoryError> {
+        if accessed_amount == 0 {
+            return Ok(0);
+        }
+        let segment_size = self
+            .get_segment_size(segment_index)
+            .ok_or(MemoryError::MissingSegmentUsedSizes)?;
+        if accessed_amount > segment_size {
+            return Err(MemoryError::SegmentHasMoreAccessedAddressesThanSize(
+                segment_index,
+                accessed_amount,
+                segment_size,
+            ));
+        }
+        Ok(segment_size - accessed_amount)
+    }
+
+    // Returns the amount of accessed addresses of each segment.
+    // Scanning the memory is only needed if it was modified since the last call
+    fn get_accessed_addresses_amounts(&self) -> Vec<usize> {
+        let mut cache = self.accessed_addresses_cache.borrow_mut();
+        match cache.as_ref() {
+            Some(amounts) if !self.memory.dirty.get() && amounts.len() == self.num_segments() => {
+                amounts.clone()
+            }
+            _ => {
+                let amounts: Vec<usize> = (0..self.num_segments())
+                    .map(|i| {
+                        self.memory
+                            .get_amount_of_accessed_addresses_for_segment(i)
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                *cache = Some(amounts.clone());
+                self.memory.dirty.set(false);
+                amounts
+            }
+        }
+    }
+
     // Writes the following information for the given segment:
     // * size - The size of the segment (to be used in relocate_segments).
     // * public_memory - A list of offsets for memory cells that will be considered as public
@@ -225,6 +292,71 @@ impl MemorySegmentManager {
                 .insert(segment_index, public_memory.clone());
         }
     }
+
+    /// Returns the public memory as (relocated_address, page_id) pairs, ordered by segment.
+    /// `segment_offsets` is the relocation table returned by [Self::relocate_segments].
+    pub fn get_public_memory_addresses(
+        &self,
+        segment_offsets: &[usize],
+    ) -> Result<Vec<(usize, usize)>, MemoryError> {
+        let mut addresses = Vec::new();
+        for segment_index in 0..self.num_segments() {
+            let offsets = match self.public_memory_offsets.get(&segment_index) {
+                Some(offsets) => offsets,
+                None => continue,
+            };
+            let segment_start = segment_offsets
+                .get(segment_index)
+                .ok_or(MemoryError::MalformedPublicMemory)?;
+            for (offset, page_id) in offsets {
+                addresses.push((segment_start + offset, *page_id));
+            }
+        }
+        Ok(addresses)
+    }
+
+    /// Returns the public memory as relocated (address, value) pairs grouped by page, the
+    /// outer vector being indexed by page id. Pairs keep the order in which they were added to
+    /// each segment's public memory, so the program page lists the bytecode in order.
+    /// `segment_offsets` is the relocation table returned by [Self::relocate_segments].
+    pub fn get_public_memory_pages(
+        &self,
+        segment_offsets: &[usize],
+    ) -> Result<Vec<Vec<(Felt, Felt)>>, MemoryError> {
+        let mut pages: Vec<Vec<(Felt, Felt)>> = Vec::new();
+        for segment_index in 0..self.num_segments() {
+            let offsets = match self.public_memory_offsets.get(&segment_index) {
+                Some(offsets) => offsets,
+                None => continue,
+            };
+            let segment_start = segment_offsets
+                .get(segment_index)
+                .ok_or(MemoryError::MalformedPublicMemory)?;
+            for (offset, page_id) in offsets {
+                let addr = Relocatable::from((segment_index as isize, *offset));
+                let value = match self
+                    .memory
+                    .get(&addr)
+                    .ok_or(MemoryError::UnknownMemoryCell(addr))?
+                    .into_owned()
+                {
+                    MaybeRelocatable::Int(value) => value,
+                    MaybeRelocatable::RelocatableValue(value) => {
+                        let value_start = usize::try_from(value.segment_index)
+                            .ok()
+                            .and_then(|index| segment_offsets.get(index))
+                            .ok_or(MemoryError::Relocation)?;
+                        Felt::from(value_start + value.offset)
+                    }
+                };
+                if pages.len() <= *page_id {
+                    pages.resize(page_id + 1, Vec::new());
+                }
+                pages[*page_id].push((Felt::from(segment_start + offset), value));
+            }
+        }
+        Ok(pages)
+    }
 }
 
 impl Default for MemorySegmentManager {
@@ -238,7 +370,6 @@ mod tests {
     use super::*;
     use crate::{relocatable, utils::test_utils::*, vm::vm_memory::memory::MemoryCell};
     use assert_matches::assert_matches;
-    use felt::Felt;
     use num_traits::Num;
 
     #[cfg(target_arch = "wasm32")]
@@ -599,6 +730,43 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_memory_holes_cached_until_memory_is_written() {
+        let mut memory_segment_manager = MemorySegmentManager::new();
+        memory_segment_manager.memory = memory![((0, 0), 0), ((0, 3), 3)];
+        memory_segment_manager
+            .memory
+            .mark_as_accessed((0, 0).into());
+        memory_segment_manager.segment_used_sizes = Some(vec![4]);
+        assert_eq!(memory_segment_manager.get_memory_holes(), Ok(3));
+        assert!(!memory_segment_manager.memory.dirty.get());
+        assert_eq!(
+            *memory_segment_manager.accessed_addresses_cache.borrow(),
+            Some(vec![1])
+        );
+
+        // The cached amounts match a fresh computation
+        assert_eq!(memory_segment_manager.get_memory_holes(), Ok(3));
+        *memory_segment_manager.accessed_addresses_cache.borrow_mut() = None;
+        assert_eq!(memory_segment_manager.get_memory_holes(), Ok(3));
+
+        // Writes and accesses invalidate the cache
+        memory_segment_manager
+            .memory
+            .insert_value((0, 1).into(), Felt::new(1))
+            .unwrap();
+        memory_segment_manager
+            .memory
+            .mark_as_accessed((0, 1).into());
+        assert!(memory_segment_manager.memory.dirty.get());
+        assert_eq!(memory_segment_manager.get_memory_holes(), Ok(2));
+
+        // Segment sizes are not cached, so finalizing a segment is taken into account
+        memory_segment_manager.finalize(Some(8), 0, None);
+        assert_eq!(memory_segment_manager.get_memory_holes(), Ok(6));
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_memory_holes_out_of_address_offset_bigger_than_size() {
@@ -682,6 +850,30 @@ mod tests {
         assert_eq!(memory_segment_manager.get_memory_holes(), Ok(7),);
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_segment_memory_holes() {
+        let mut memory_segment_manager = MemorySegmentManager::new();
+        memory_segment_manager.segment_used_sizes = Some(vec![4, 5, 3]);
+        memory_segment_manager.memory = memory![
+            ((0, 0), 0),
+            ((0, 3), 0),
+            ((1, 0), 0),
+            ((1, 1), 0),
+            ((1, 4), 0),
+            ((2, 0), 0)
+        ];
+        for addr in [(0, 0), (0, 3), (1, 0), (1, 1), (1, 4)] {
+            memory_segment_manager.memory.mark_as_accessed(addr.into());
+        }
+        assert_eq!(memory_segment_manager.get_segment_memory_holes(0), Ok(2));
+        assert_eq!(memory_segment_manager.get_segment_memory_holes(1), Ok(2));
+        // Segments without accessed addresses have no holes
+        assert_eq!(memory_segment_manager.get_segment_memory_holes(2), Ok(0));
+        assert_eq!(memory_segment_manager.get_segment_memory_holes(3), Ok(0));
+        assert_eq!(memory_segment_manager.get_memory_holes(), Ok(4));
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn get_memory_size_missing_segment() {
@@ -846,6 +1038,49 @@ mod tests {
         assert_eq!(segments.segment_sizes, HashMap::from([(0, 42)]));
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_public_memory_addresses_with_pages() {
+        let mut segments = MemorySegmentManager::new();
+        for _ in 0..3 {
+            segments.add();
+        }
+        segments.finalize(None, 0, Some(&vec![(0, 0), (1, 0)]));
+        segments.finalize(None, 2, Some(&vec![(0, 0), (1, 1), (2, 1)]));
+        assert_eq!(
+            segments.get_public_memory_addresses(&[1, 3, 10]),
+            Ok(vec![(1, 0), (2, 0), (10, 0), (11, 1), (12, 1)])
+        );
+        assert_eq!(
+            segments.get_public_memory_addresses(&[1]),
+            Err(MemoryError::MalformedPublicMemory)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn get_public_memory_pages_groups_by_page() {
+        let mut segments = segments![((0, 0), 10), ((0, 1), 11), ((2, 0), 20), ((2, 1), (0, 1))];
+        segments.finalize(None, 0, Some(&vec![(0, 0), (1, 0)]));
+        segments.finalize(None, 2, Some(&vec![(0, 2), (1, 0)]));
+        assert_eq!(
+            segments.get_public_memory_pages(&[1, 3, 10]),
+            Ok(vec![
+                vec![
+                    (Felt::new(1), Felt::new(10)),
+                    (Felt::new(2), Felt::new(11)),
+                    (Felt::new(11), Felt::new(2)),
+                ],
+                vec![],
+                vec![(Felt::new(10), Felt::new(20))],
+            ])
+        );
+        assert_eq!(
+            segments.get_public_memory_pages(&[1]),
+            Err(MemoryError::MalformedPublicMemory)
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn gen_cairo_arg_single() {
@@ -880,6 +1115,54 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn gen_cairo_arg_values_span() {
+        let mut memory_segment_manager = MemorySegmentManager::new();
+
+        assert_matches!(
+            memory_segment_manager.gen_cairo_arg_values(&CairoArg::Span(vec![
+                mayberelocatable!(4),
+                mayberelocatable!(5),
+                mayberelocatable!(6),
+            ])),
+            Ok(x) if x == vec![mayberelocatable!(0, 0), mayberelocatable!(3)]
+        );
+        check_memory!(
+            memory_segment_manager.memory,
+            ((0, 0), 4),
+            ((0, 1), 5),
+            ((0, 2), 6)
+        );
+        assert_matches!(
+            memory_segment_manager.gen_cairo_arg_values(&CairoArg::Single(mayberelocatable!(7))),
+            Ok(x) if x == vec![mayberelocatable!(7)]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn gen_cairo_arg_composed_with_span() {
+        let mut memory_segment_manager = MemorySegmentManager::new();
+        let cairo_args = CairoArg::Composed(vec![
+            CairoArg::Span(vec![mayberelocatable!(1), mayberelocatable!(2)]),
+            CairoArg::Single(mayberelocatable!(3)),
+        ]);
+
+        assert_matches!(
+            memory_segment_manager.gen_cairo_arg(&cairo_args),
+            Ok(x) if x == mayberelocatable!(1, 0)
+        );
+        check_memory!(
+            memory_segment_manager.memory,
+            ((0, 0), 1),
+            ((0, 1), 2),
+            ((1, 0), (0, 0)),
+            ((1, 1), 2),
+            ((1, 2), 3)
+        );
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
     fn gen_cairo_arg_composed() {
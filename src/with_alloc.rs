@@ -10,6 +10,7 @@ pub mod with_alloc {
     pub use alloc::vec;
 
     pub mod collections {
+        pub use alloc::collections::BTreeMap;
         pub use hashbrown::{HashMap, HashSet};
     }
 }